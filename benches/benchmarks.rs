@@ -1,6 +1,6 @@
 use std::{num::NonZeroUsize, time::Duration};
 
-use bfa::{Program, Table};
+use bfa::{BuildOptions, ExploreOrder, Program, StateHasher, Table};
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 
 const PROGRAMS: &[(&str, NonZeroUsize)] = &[
@@ -46,5 +46,168 @@ pub fn build_min_dot(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, build_min_dot);
+pub fn head_bound(c: &mut Criterion) {
+    // the head never leaves the first two cells, so the build only needs that much tape
+    let program = Program::new(",>,[-<+>]<[.,]", NonZeroUsize::new(64).unwrap());
+    c.bench_function("head_bound", |b| {
+        b.iter(|| black_box(Table::build(black_box(&program))));
+    });
+}
+
+pub fn dot_unminimized(c: &mut Criterion) {
+    let program = Program::new(
+        ",>>+[.[,<<[->+>-<<]>[-<+>]>]+]",
+        NonZeroUsize::new(5).unwrap(),
+    );
+    let table = Table::build(&program);
+    c.bench_function("dot_unminimized", |b| {
+        b.iter(|| black_box(table.dot()));
+    });
+}
+
+/// Peak frontier size is in the `--stats` report; this compares the time it costs.
+pub fn explore_order(c: &mut Criterion) {
+    let mut group = c.benchmark_group("explore_order");
+    for (code, cells) in PROGRAMS.iter().filter(|(_, cells)| cells.get() == 3) {
+        let program = Program::new(code, *cells);
+        for order in [ExploreOrder::Bfs, ExploreOrder::Dfs] {
+            let options = BuildOptions {
+                order,
+                ..BuildOptions::default()
+            };
+            group.bench_with_input(
+                BenchmarkId::new(format!("{order:?}"), code),
+                &options,
+                |b, options| {
+                    b.iter(|| black_box(Table::build_with(&program, options).unwrap()));
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+pub fn classify_batch(c: &mut Criterion) {
+    let mut table = Table::build(&Program::new(PROGRAMS[5].0, PROGRAMS[5].1));
+    table.minimize();
+    // a fixed pseudo-random corpus
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let inputs: Vec<Vec<u8>> = (0..100_000)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (0..seed % 32)
+                .map(|i| (seed >> (i % 16 * 4)) as u8 & 0x0F)
+                .collect()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("classify_batch");
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            black_box(
+                inputs
+                    .iter()
+                    .map(|input| table.accepts(input))
+                    .collect::<Vec<_>>(),
+            )
+        });
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| black_box(table.classify_batch(&inputs)));
+    });
+    group.finish();
+}
+
+/// Each read moves the head, so without the reduction every tape is seen at every rotation.
+pub fn rotation_symmetry(c: &mut Criterion) {
+    let program = Program::new("+[>,.]", NonZeroUsize::new(4).unwrap());
+    let mut group = c.benchmark_group("rotation_symmetry");
+    for rotation_symmetry in [false, true] {
+        let options = BuildOptions {
+            rotation_symmetry,
+            ..BuildOptions::default()
+        };
+        let states = Table::build_with(&program, &options)
+            .unwrap()
+            .0
+            .state_count();
+        group.bench_with_input(
+            BenchmarkId::new(format!("{rotation_symmetry}"), format!("{states} states")),
+            &options,
+            |b, options| {
+                b.iter(|| black_box(Table::build_with(&program, options).unwrap()));
+            },
+        );
+    }
+    group.finish();
+}
+
+pub fn hasher(c: &mut Criterion) {
+    let program = Program::new(PROGRAMS[5].0, NonZeroUsize::new(5).unwrap());
+    let mut group = c.benchmark_group("hasher");
+    for hasher in [StateHasher::Fx, StateHasher::Random] {
+        let options = BuildOptions {
+            hasher,
+            ..BuildOptions::default()
+        };
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{hasher:?}")),
+            &options,
+            |b, options| {
+                b.iter(|| black_box(Table::build_with(&program, options).unwrap()));
+            },
+        );
+    }
+    group.finish();
+}
+
+/// 40 cells spill to the heap unless built with the `wide-tape` feature, so compare the two.
+pub fn wide_tape(c: &mut Criterion) {
+    // the head walks the whole tape, so it isn't shrunk
+    let program = Program::new("+[[-]>+.,[-]+]", NonZeroUsize::new(40).unwrap());
+    c.bench_function("wide_tape", |b| {
+        b.iter(|| black_box(Table::build(black_box(&program))));
+    });
+}
+
+/// The largest unminimized table of the benchmark programs, built depth-first, which settles
+/// states soon enough for merging to matter, and minimized.
+pub fn merge_every(c: &mut Criterion) {
+    let program = Program::new(PROGRAMS[5].0, NonZeroUsize::new(5).unwrap());
+    let mut group = c.benchmark_group("merge_every");
+    for merge_every in [None, Some(1_000), Some(10_000)] {
+        let options = BuildOptions {
+            order: ExploreOrder::Dfs,
+            merge_every,
+            ..BuildOptions::default()
+        };
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{merge_every:?}")),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    let (mut table, _) = Table::build_with(&program, options).unwrap();
+                    table.minimize();
+                    black_box(table)
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    classify_batch,
+    build_min_dot,
+    head_bound,
+    dot_unminimized,
+    explore_order,
+    rotation_symmetry,
+    hasher,
+    wide_tape,
+    merge_every
+);
 criterion_main!(benches);
@@ -0,0 +1,135 @@
+//! Compile-time construction of [`bfa::CompiledTable`]s.
+
+use std::num::NonZeroUsize;
+
+use bfa::{BuildOptions, Program, Table};
+use proc_macro::{Delimiter, Group, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Builds tables with more states than this at compile time is almost certainly a mistake.
+const MAX_STATES: usize = 1 << 16;
+
+/// `bfa_dfa!(cells = 2, "+[>,,.<]")` builds and minimizes the program at compile time and
+/// expands to a `&'static bfa::CompiledTable`.
+#[proc_macro]
+pub fn bfa_dfa(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err((message, span)) => compile_error(&message, span),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, (String, Span)> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let usage = "expected `cells = <count>, \"<program>\"`";
+    let [TokenTree::Ident(key), TokenTree::Punct(equals), TokenTree::Literal(cells), TokenTree::Punct(comma), TokenTree::Literal(text)] =
+        &tokens[..]
+    else {
+        let span = tokens.first().map_or_else(Span::call_site, TokenTree::span);
+        return Err((usage.to_string(), span));
+    };
+    if key.to_string() != "cells" || equals.as_char() != '=' || comma.as_char() != ',' {
+        return Err((usage.to_string(), key.span()));
+    }
+
+    let cell_count = cells
+        .to_string()
+        .parse::<NonZeroUsize>()
+        .map_err(|e| (format!("invalid cell count: {e}"), cells.span()))?;
+    let program_text = string_literal(text)?;
+
+    let program = Program::new(&program_text, cell_count);
+    let options = BuildOptions {
+        max_states: Some(MAX_STATES),
+        ..BuildOptions::default()
+    };
+    let (mut table, _) =
+        Table::build_with(&program, &options).map_err(|e| (e.to_string(), text.span()))?;
+    table.minimize();
+
+    Ok(table_tokens(&table))
+}
+
+/// The contents of a plain or raw string literal.
+fn string_literal(literal: &Literal) -> Result<String, (String, Span)> {
+    let source = literal.to_string();
+    let error = |message: &str| (message.to_string(), literal.span());
+
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw
+            .get(hashes + 1..raw.len() - hashes - 1)
+            .map(str::to_string)
+            .ok_or_else(|| error("expected a string literal"));
+    }
+
+    let inner = source
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| error("expected a string literal"))?;
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some('r') => text.push('\r'),
+            Some('0') => text.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => text.push(c),
+            // line continuation
+            Some('\n') => {
+                let rest = chars.as_str().trim_start();
+                chars = rest.chars();
+            }
+            _ => return Err(error("unsupported escape in program text")),
+        }
+    }
+    Ok(text)
+}
+
+/// `{ static TABLE: CompiledTable = CompiledTable::from_static(&[..], &[..]); &TABLE }`
+fn table_tokens(table: &Table) -> TokenStream {
+    let accepting = (0..table.state_count())
+        .map(|state| table.is_accepting(state).to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let transitions = (0..table.state_count())
+        .map(|state| {
+            let row = table
+                .transitions(state)
+                .iter()
+                .map(|to| format!("{to}u32"))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("[{row}]")
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        "{{ static TABLE: ::bfa::CompiledTable = ::bfa::CompiledTable::from_static(&[{accepting}], &[{transitions}]); &TABLE }}"
+    )
+    .parse()
+    .expect("generated code is valid")
+}
+
+/// `compile_error!("message")` pointing at `span`.
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+    group.set_span(span);
+
+    [
+        TokenTree::Ident(proc_macro::Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(group),
+    ]
+    .into_iter()
+    .collect()
+}
@@ -0,0 +1,4 @@
+#[test]
+fn bad_input_is_a_compile_error() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,64 @@
+use std::num::NonZeroUsize;
+
+use bfa::{CompiledTable, Program, Table};
+use bfa_macros::bfa_dfa;
+
+fn build(text: &str, cells: usize) -> Table {
+    Table::build(&Program::new(text, NonZeroUsize::new(cells).unwrap())).minimized()
+}
+
+/// Every input of length at most 3.
+fn short_inputs() -> impl Iterator<Item = Vec<u8>> {
+    (0..=3u32).flat_map(|len| {
+        (0..16usize.pow(len)).map(move |n| {
+            (0..len)
+                .map(|i| ((n >> (4 * (len - 1 - i))) & 0x0F) as u8)
+                .collect()
+        })
+    })
+}
+
+fn assert_agrees(compiled: &CompiledTable, table: &Table) {
+    assert_eq!(compiled.state_count(), table.state_count());
+    for state in 0..table.state_count() {
+        assert_eq!(compiled.is_accepting(state), table.is_accepting(state));
+        for input in 0..16 {
+            assert_eq!(
+                compiled.transition(state, input),
+                table.transition(state, input)
+            );
+        }
+    }
+    for input in short_inputs() {
+        assert_eq!(compiled.accepts(&input), table.accepts(&input));
+    }
+}
+
+#[test]
+fn compiled_tables_agree_with_the_build() {
+    assert_agrees(bfa_dfa!(cells = 2, "+[>,,.<]"), &build("+[>,,.<]", 2));
+    assert_agrees(
+        bfa_dfa!(cells = 3, ">+[>.,[>]<<]"),
+        &build(">+[>.,[>]<<]", 3),
+    );
+    assert_agrees(
+        bfa_dfa!(cells = 2, ",>,[-<->]<[>.,<]"),
+        &build(",>,[-<->]<[>.,<]", 2),
+    );
+}
+
+#[test]
+fn string_literal_forms() {
+    // comments, escapes and raw strings only change the text around the instructions
+    let table = build(",[.,]", 1);
+    assert_agrees(bfa_dfa!(cells = 1, "read: ,\n[accept: . ,]"), &table);
+    assert_agrees(bfa_dfa!(cells = 1, r#"read "," then [.,]"#), &table);
+}
+
+#[test]
+fn compiled_tables_are_static() {
+    let table: &'static CompiledTable = bfa_dfa!(cells = 2, "+[>,,.<]");
+    assert!(table.accepts(&[1, 2]));
+    assert!(!table.accepts(&[1]));
+    assert!(!table.accepts(&[]));
+}
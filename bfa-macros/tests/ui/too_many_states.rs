@@ -0,0 +1,3 @@
+fn main() {
+    let _table = bfa_macros::bfa_dfa!(cells = 5, ",[>,]");
+}
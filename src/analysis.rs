@@ -0,0 +1,1051 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    fmt::Display,
+};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::Table;
+
+impl Table {
+    /// Predecessor lists, one per state, in ascending source and input order.
+    pub(crate) fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut predecessors = vec![Vec::new(); self.state_count()];
+        for from in 0..self.state_count() {
+            for &to in self.transitions(from) {
+                if to != Table::MISSING {
+                    predecessors[to].push(from);
+                }
+            }
+        }
+        predecessors
+    }
+
+    /// Marks every state from which some accepting state can be reached.
+    pub(crate) fn co_reachable(&self) -> Vec<bool> {
+        let predecessors = self.predecessors();
+        let mut co_reachable = vec![false; self.state_count()];
+        let mut stack: Vec<usize> = (0..self.state_count())
+            .filter(|&state| self.is_accepting(state))
+            .collect();
+        for &state in &stack {
+            co_reachable[state] = true;
+        }
+
+        while let Some(current) = stack.pop() {
+            for &from in &predecessors[current] {
+                if !co_reachable[from] {
+                    co_reachable[from] = true;
+                    stack.push(from);
+                }
+            }
+        }
+
+        co_reachable
+    }
+
+    /// States from which no accepting state is reachable, in ascending order.
+    pub fn dead_states(&self) -> Vec<usize> {
+        self.co_reachable()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(state, live)| if live { None } else { Some(state) })
+            .collect()
+    }
+
+    /// Whether each state is dead, see [`Table::dead_states`].
+    pub fn dead_flags(&self) -> Vec<bool> {
+        self.co_reachable().into_iter().map(|live| !live).collect()
+    }
+
+    /// States whose every transition is a self-loop, in ascending order.
+    pub fn sink_states(&self) -> Vec<usize> {
+        (0..self.state_count())
+            .filter(|&state| self.is_sink(state))
+            .collect()
+    }
+
+    /// Looks at the whole table on each call, so use [`Table::dead_flags`] to ask about many
+    /// states.
+    pub fn is_dead(&self, state: usize) -> bool {
+        !self.co_reachable()[state]
+    }
+
+    pub fn is_sink(&self, state: usize) -> bool {
+        self.transitions(state).iter().all(|&to| to == state)
+    }
+}
+
+impl Table {
+    /// Strongly connected components, each sorted, ordered by their smallest state.
+    pub fn sccs(&self) -> Vec<Vec<usize>> {
+        let state_count = self.state_count();
+        let mut index = vec![usize::MAX; state_count];
+        let mut lowlink = vec![0; state_count];
+        let mut on_stack = vec![false; state_count];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..state_count {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            index[root] = next_index;
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            // (state, next input to follow) for each state on the DFS path
+            let mut path = vec![(root, 0)];
+            while let Some((state, input)) = path.last_mut() {
+                let state = *state;
+                if *input < 16 {
+                    let to = self.transitions(state)[*input];
+                    *input += 1;
+                    if to == Table::MISSING {
+                        continue;
+                    }
+                    if index[to] == usize::MAX {
+                        index[to] = next_index;
+                        lowlink[to] = next_index;
+                        next_index += 1;
+                        stack.push(to);
+                        on_stack[to] = true;
+                        path.push((to, 0));
+                    } else if on_stack[to] {
+                        lowlink[state] = lowlink[state].min(index[to]);
+                    }
+                    continue;
+                }
+
+                path.pop();
+                if let Some(&(parent, _)) = path.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[state]);
+                }
+                if lowlink[state] == index[state] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == state {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+
+        components.sort_unstable_by_key(|component| component[0]);
+        components
+    }
+
+    /// Whether the component contains a cycle, i.e. has several states or a self-loop.
+    pub(crate) fn is_cyclic_component(&self, component: &[usize]) -> bool {
+        component.len() > 1 || self.transitions(component[0]).contains(&component[0])
+    }
+}
+
+impl Table {
+    /// Every accepted string of length at most `max_len`, shortest first and then in
+    /// lexicographic order. Prefixes that can no longer be accepted are pruned.
+    pub fn accepted_strings(&self, max_len: usize) -> Vec<Vec<u8>> {
+        let co_reachable = self.co_reachable();
+        let mut accepted = Vec::new();
+        let mut layer = Vec::new();
+        if co_reachable[0] {
+            layer.push((0, Vec::new()));
+        }
+
+        for len in 0..=max_len {
+            for (state, string) in &layer {
+                if self.is_accepting(*state) {
+                    accepted.push(string.clone());
+                }
+            }
+            if len == max_len {
+                break;
+            }
+
+            let mut next_layer = Vec::new();
+            for (state, string) in &layer {
+                for input in 0..16 {
+                    let to = self.transition(*state, input);
+                    if to != Table::MISSING && co_reachable[to] {
+                        let mut extended = string.clone();
+                        extended.push(input);
+                        next_layer.push((to, extended));
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+
+        accepted
+    }
+}
+
+impl Table {
+    /// Shortest input leading from the start to a state satisfying `target`, preferring
+    /// lexicographically smaller inputs among those of equal length.
+    pub(crate) fn shortest_to(&self, target: impl Fn(usize) -> bool) -> Option<Vec<u8>> {
+        self.shortest_path(0, target)
+    }
+
+    /// Like [`Table::shortest_to`], but starting from `from` instead of the start state.
+    pub(crate) fn shortest_path(
+        &self,
+        from: usize,
+        target: impl Fn(usize) -> bool,
+    ) -> Option<Vec<u8>> {
+        // (parent, symbol) of the first path found to each state
+        let mut parents = vec![None; self.state_count()];
+        let mut visited = vec![false; self.state_count()];
+        let mut queue = VecDeque::new();
+        visited[from] = true;
+        queue.push_back(from);
+
+        while let Some(state) = queue.pop_front() {
+            if target(state) {
+                let mut path = Vec::new();
+                let mut current = state;
+                while let Some((parent, symbol)) = parents[current] {
+                    path.push(symbol);
+                    current = parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for input in 0..16 {
+                let to = self.transition(state, input);
+                if to != Table::MISSING && !visited[to] {
+                    visited[to] = true;
+                    parents[to] = Some((state, input));
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Shortest accepted string, the lexicographically smallest among those of equal length.
+    pub fn shortest_accepted(&self) -> Option<Vec<u8>> {
+        self.shortest_to(|state| self.is_accepting(state))
+    }
+
+    /// Shortest rejected string, with the same tie-breaking as [`Table::shortest_accepted`].
+    /// `None` means every string is accepted.
+    pub fn shortest_rejected(&self) -> Option<Vec<u8>> {
+        // missing transitions reject too, so search the completed table
+        let table = self.completed();
+        table.shortest_to(|state| !table.is_accepting(state))
+    }
+
+    /// Up to `count` accepted strings, shortest first and then in lexicographic order.
+    pub fn shortest_accepted_strings(&self, count: usize) -> Vec<Vec<u8>> {
+        // length of the shortest accepted suffix from each state
+        let predecessors = self.predecessors();
+        let mut distances = vec![usize::MAX; self.state_count()];
+        let mut queue: VecDeque<usize> = (0..self.state_count())
+            .filter(|&state| self.is_accepting(state))
+            .collect();
+        for &state in &queue {
+            distances[state] = 0;
+        }
+        while let Some(current) = queue.pop_front() {
+            for &from in &predecessors[current] {
+                if distances[from] == usize::MAX {
+                    distances[from] = distances[current] + 1;
+                    queue.push_back(from);
+                }
+            }
+        }
+
+        // best first on the length of the shortest completion, which is exact, so accepting
+        // states come off the heap in the order they should be reported
+        let mut accepted = Vec::new();
+        let mut heap = BinaryHeap::new();
+        if distances[0] != usize::MAX {
+            heap.push(Reverse((distances[0], Vec::new(), 0)));
+        }
+        while let Some(Reverse((_, string, state))) = heap.pop() {
+            if accepted.len() == count {
+                break;
+            }
+            if self.is_accepting(state) {
+                accepted.push(string.clone());
+            }
+            for input in 0..16 {
+                let to = self.transition(state, input);
+                if to != Table::MISSING && distances[to] != usize::MAX {
+                    let mut extended = string.clone();
+                    extended.push(input);
+                    heap.push(Reverse((extended.len() + distances[to], extended, to)));
+                }
+            }
+        }
+
+        accepted
+    }
+}
+
+/// An eventually periodic set of lengths: `n` is in the set if `preperiod[n]` holds, or for
+/// larger `n` if `period[(n - preperiod.len()) % period.len()]` holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LengthSpectrum {
+    pub preperiod: Vec<bool>,
+    pub period: Vec<bool>,
+}
+
+impl LengthSpectrum {
+    pub fn contains(&self, len: usize) -> bool {
+        match self.preperiod.get(len) {
+            Some(&contained) => contained,
+            None => self.period[(len - self.preperiod.len()) % self.period.len()],
+        }
+    }
+
+    /// Shrinks both parts to the shortest description of the same set.
+    fn canonicalize(&mut self) {
+        let len = self.period.len();
+        if let Some(shortest) = (1..=len).find(|&candidate| {
+            len.is_multiple_of(candidate)
+                && (candidate..len).all(|i| self.period[i] == self.period[i - candidate])
+        }) {
+            self.period.truncate(shortest);
+        }
+
+        while self.preperiod.last().is_some() && self.preperiod.last() == self.period.last() {
+            self.preperiod.pop();
+            self.period.rotate_right(1);
+        }
+    }
+}
+
+impl Table {
+    /// The lengths of accepted strings.
+    ///
+    /// The live states reachable after exactly `n` symbols are tracked until that set repeats.
+    /// This is quick for typical tables, although the period can in principle grow
+    /// exponentially with the number of states.
+    pub fn length_spectrum(&self) -> LengthSpectrum {
+        let co_reachable = self.co_reachable();
+        let mut seen = HashMap::with_hasher(FxBuildHasher);
+        let mut flags = Vec::new();
+        let mut current = vec![false; self.state_count()];
+        current[0] = co_reachable[0];
+
+        let repeat_start = loop {
+            if let Some(&start) = seen.get(&current) {
+                break start;
+            }
+            flags.push(
+                (0..self.state_count()).any(|state| current[state] && self.is_accepting(state)),
+            );
+
+            let mut next = vec![false; self.state_count()];
+            for state in (0..self.state_count()).filter(|&state| current[state]) {
+                for &to in self.transitions(state) {
+                    if to != Table::MISSING && co_reachable[to] {
+                        next[to] = true;
+                    }
+                }
+            }
+            seen.insert(std::mem::replace(&mut current, next), flags.len() - 1);
+        };
+
+        let period = flags.split_off(repeat_start);
+        let mut spectrum = LengthSpectrum {
+            preperiod: flags,
+            period,
+        };
+        spectrum.canonicalize();
+        spectrum
+    }
+}
+
+impl Table {
+    /// Fraction of the `16^len` strings of length `len` that are accepted.
+    ///
+    /// The distribution over states is pushed forward one symbol at a time in `f64`, so nothing
+    /// overflows and the result is exact up to floating point rounding.
+    pub fn acceptance_probability(&self, len: usize) -> f64 {
+        let mut distribution = vec![0.0; self.state_count()];
+        distribution[0] = 1.0;
+        for _ in 0..len {
+            let mut next = vec![0.0; self.state_count()];
+            for (state, &probability) in distribution.iter().enumerate() {
+                if probability == 0.0 {
+                    continue;
+                }
+                for &to in self.transitions(state) {
+                    if to != Table::MISSING {
+                        next[to] += probability / 16.0;
+                    }
+                }
+            }
+            distribution = next;
+        }
+
+        distribution
+            .iter()
+            .enumerate()
+            .filter(|&(state, _)| self.is_accepting(state))
+            .map(|(_, probability)| probability)
+            .sum()
+    }
+}
+
+/// A pair of states in the product search of [`Table::suffix_closure_witness`], the seed state
+/// it was reached from, and its parent pair and symbol.
+type SeededPair = ((usize, usize), usize, Option<(usize, u8)>);
+
+impl Table {
+    /// Breadth-first tree from the start state, trying inputs in order: for each state, whether
+    /// it is reachable and the `(parent, symbol)` edge through which it was first reached.
+    pub(crate) fn bfs_tree(&self) -> (Vec<bool>, Vec<Option<(usize, u8)>>) {
+        let mut parents = vec![None; self.state_count()];
+        let mut reachable = vec![false; self.state_count()];
+        let mut queue = VecDeque::new();
+        reachable[0] = true;
+        queue.push_back(0);
+
+        while let Some(state) = queue.pop_front() {
+            for input in 0..16 {
+                let to = self.transition(state, input);
+                if to != Table::MISSING && !reachable[to] {
+                    reachable[to] = true;
+                    parents[to] = Some((state, input));
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        (reachable, parents)
+    }
+
+    /// Symbols along the tree path from the start to `state`.
+    pub(crate) fn tree_path(parents: &[Option<(usize, u8)>], state: usize) -> Vec<u8> {
+        let mut path = Vec::new();
+        let mut current = state;
+        while let Some((parent, symbol)) = parents[current] {
+            path.push(symbol);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    pub fn is_prefix_closed(&self) -> bool {
+        self.prefix_closure_witness().is_none()
+    }
+
+    /// An accepted string with a rejected prefix, if there is one.
+    ///
+    /// The language is prefix-closed exactly when every state that is both reachable and able
+    /// to reach acceptance is itself accepting.
+    pub fn prefix_closure_witness(&self) -> Option<Vec<u8>> {
+        let (reachable, parents) = self.bfs_tree();
+        let co_reachable = self.co_reachable();
+        let culprit = (0..self.state_count())
+            .find(|&state| reachable[state] && co_reachable[state] && !self.is_accepting(state))?;
+
+        let mut witness = Self::tree_path(&parents, culprit);
+        witness.extend(self.shortest_path(culprit, |state| self.is_accepting(state))?);
+        Some(witness)
+    }
+
+    pub fn is_suffix_closed(&self) -> bool {
+        self.suffix_closure_witness().is_none()
+    }
+
+    /// An accepted string with a rejected suffix, if there is one.
+    ///
+    /// Rather than determinizing the reversed automaton, this uses the equivalent condition that
+    /// the residual language of every reachable state is contained in the language itself,
+    /// checked with one product search seeded with every pair `(reachable state, start)`.
+    pub fn suffix_closure_witness(&self) -> Option<Vec<u8>> {
+        let table = self.completed();
+        let (reachable, parents) = table.bfs_tree();
+
+        let mut pair_ids = HashMap::with_hasher(FxBuildHasher);
+        // in discovery order
+        let mut pairs: Vec<SeededPair> = Vec::new();
+        for seed in (0..table.state_count()).filter(|&state| reachable[state]) {
+            if let Entry::Vacant(slot) = pair_ids.entry((seed, 0)) {
+                slot.insert(pairs.len());
+                pairs.push(((seed, 0), seed, None));
+            }
+        }
+
+        let mut next_pair = 0;
+        while next_pair < pairs.len() {
+            let ((a, b), seed, _) = pairs[next_pair];
+            if table.is_accepting(a) && !table.is_accepting(b) {
+                let mut suffix = Vec::new();
+                let mut current = next_pair;
+                while let (_, _, Some((parent, symbol))) = pairs[current] {
+                    suffix.push(symbol);
+                    current = parent;
+                }
+                suffix.reverse();
+
+                let mut witness = Self::tree_path(&parents, seed);
+                witness.extend(suffix);
+                return Some(witness);
+            }
+
+            for input in 0..16 {
+                let next = (table.transition(a, input), table.transition(b, input));
+                if let Entry::Vacant(slot) = pair_ids.entry(next) {
+                    slot.insert(pairs.len());
+                    pairs.push((next, seed, Some((next_pair, input))));
+                }
+            }
+            next_pair += 1;
+        }
+
+        None
+    }
+}
+
+/// Returned when a finite language was required: reading `prefix` and then `cycle` any number
+/// of times leads to a state from which acceptance is still possible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InfiniteLanguage {
+    pub prefix: Vec<u8>,
+    pub cycle: Vec<u8>,
+}
+
+impl Display for InfiniteLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the language is infinite: the input loop ")?;
+        for symbol in &self.cycle {
+            write!(f, "{symbol:X}")?;
+        }
+        write!(f, " can be repeated after the prefix \"")?;
+        for symbol in &self.prefix {
+            write!(f, "{symbol:X}")?;
+        }
+        write!(f, "\"")
+    }
+}
+
+impl std::error::Error for InfiniteLanguage {}
+
+impl Table {
+    pub fn is_finite(&self) -> bool {
+        self.infinite_language_witness().is_none()
+    }
+
+    /// The language is infinite exactly when some cycle passes through a state that is both
+    /// reachable and co-reachable. Such a cycle is returned as a witness, if there is one.
+    pub fn infinite_language_witness(&self) -> Option<InfiniteLanguage> {
+        let (reachable, parents) = self.bfs_tree();
+        let co_reachable = self.co_reachable();
+        // a component shares reachability and co-reachability among all its members
+        let component = self.sccs().into_iter().find(|component| {
+            reachable[component[0]]
+                && co_reachable[component[0]]
+                && self.is_cyclic_component(component)
+        })?;
+
+        let state = component[0];
+        let cycle = self.shortest_cycle(state)?;
+
+        Some(InfiniteLanguage {
+            prefix: Self::tree_path(&parents, state),
+            cycle,
+        })
+    }
+
+    /// A shortest non-empty input leading from `state` back to itself, if there is one.
+    pub(crate) fn shortest_cycle(&self, state: usize) -> Option<Vec<u8>> {
+        (0..16)
+            .filter_map(|input| {
+                let to = self.transition(state, input);
+                if to == Table::MISSING {
+                    return None;
+                }
+                let mut cycle = vec![input];
+                cycle.extend(self.shortest_path(to, |other| other == state)?);
+                Some(cycle)
+            })
+            .min_by_key(Vec::len)
+    }
+
+    /// The states that are both reachable and co-reachable, and those states ordered so that
+    /// each comes after all of its useful successors. Only meaningful for finite languages,
+    /// where the useful states form a DAG.
+    pub(crate) fn useful_dag_order(&self) -> (Vec<bool>, Vec<usize>) {
+        let (reachable, _) = self.bfs_tree();
+        let co_reachable = self.co_reachable();
+        let useful: Vec<bool> = (0..self.state_count())
+            .map(|state| reachable[state] && co_reachable[state])
+            .collect();
+
+        // peel off states whose useful successors have all been placed
+        let mut pending = vec![0; self.state_count()];
+        for state in (0..self.state_count()).filter(|&state| useful[state]) {
+            pending[state] = self
+                .transitions(state)
+                .iter()
+                .filter(|&&to| to != Table::MISSING && useful[to])
+                .count();
+        }
+        let predecessors = self.predecessors();
+        let mut ready: Vec<usize> = (0..self.state_count())
+            .filter(|&state| useful[state] && pending[state] == 0)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(state) = ready.pop() {
+            order.push(state);
+            for &from in &predecessors[state] {
+                if useful[from] {
+                    pending[from] -= 1;
+                    if pending[from] == 0 {
+                        ready.push(from);
+                    }
+                }
+            }
+        }
+
+        (useful, order)
+    }
+
+    /// The longest accepted string, the lexicographically smallest among those of that length,
+    /// or `None` if nothing is accepted.
+    pub fn longest_accepted(&self) -> Result<Option<Vec<u8>>, InfiniteLanguage> {
+        if let Some(witness) = self.infinite_language_witness() {
+            return Err(witness);
+        }
+
+        let (useful, order) = self.useful_dag_order();
+        if !useful[0] {
+            return Ok(None);
+        }
+
+        // longest path to acceptance from each useful state
+        let mut longest = vec![0; self.state_count()];
+        for &state in &order {
+            for &to in self.transitions(state) {
+                if to != Table::MISSING && useful[to] {
+                    longest[state] = longest[state].max(longest[to] + 1);
+                }
+            }
+        }
+
+        let mut string = Vec::with_capacity(longest[0]);
+        let mut state = 0;
+        while longest[state] > 0 {
+            let input = (0..16)
+                .find(|&input| {
+                    let to = self.transition(state, input);
+                    to != Table::MISSING && useful[to] && longest[to] + 1 == longest[state]
+                })
+                .expect("some successor realizes the longest path");
+            string.push(input);
+            state = self.transition(state, input);
+        }
+        Ok(Some(string))
+    }
+}
+
+/// `prefix · cycle^k · suffix` is accepted for every `k`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pump {
+    pub prefix: Vec<u8>,
+    pub cycle: Vec<u8>,
+    pub suffix: Vec<u8>,
+}
+
+impl Table {
+    /// A pumpable decomposition with a non-empty cycle, or `None` if the language is finite.
+    pub fn pump(&self) -> Option<Pump> {
+        let InfiniteLanguage { prefix, cycle } = self.infinite_language_witness()?;
+        let state = self.derivative_state(&prefix);
+        let suffix = self.shortest_path(state, |other| self.is_accepting(other))?;
+        let pump = Pump {
+            prefix,
+            cycle,
+            suffix,
+        };
+
+        debug_assert!((0..3).all(|k| {
+            let mut input = pump.prefix.clone();
+            for _ in 0..k {
+                input.extend(&pump.cycle);
+            }
+            input.extend(&pump.suffix);
+            self.accepts(&input)
+        }));
+        Some(pump)
+    }
+}
+
+impl Table {
+    /// Merges every state that can never reach acceptance into one dead state that loops on
+    /// every input, and returns the new id of each old state. Missing transitions also lead to
+    /// the dead state, so the result is complete.
+    ///
+    /// Surviving states keep their relative order, and the dead state takes the place of the
+    /// first hopeless one, so a hopeless start (an empty language) stays at 0.
+    pub fn trim_hopeless(&mut self) -> Vec<usize> {
+        let old_count = self.state_count();
+        let table = self.completed();
+        let co_reachable = table.co_reachable();
+
+        let mut new_ids = Vec::with_capacity(table.state_count());
+        let mut dead = None;
+        let mut next_id = 0;
+        for &hopeful in &co_reachable {
+            if hopeful {
+                new_ids.push(next_id);
+                next_id += 1;
+            } else {
+                new_ids.push(*dead.get_or_insert_with(|| {
+                    next_id += 1;
+                    next_id - 1
+                }));
+            }
+        }
+
+        let mut rows = Vec::with_capacity(next_id);
+        for (state, &hopeful) in co_reachable.iter().enumerate() {
+            if hopeful {
+                let (accepting, edges) = *table.row(state);
+                rows.push((accepting, edges.map(|to| new_ids[to])));
+            } else if rows.len() == new_ids[state] {
+                // the first hopeless state, which became the dead state
+                rows.push((false, [new_ids[state]; 16]));
+            }
+        }
+
+        drop(table);
+        *self = Table::from_rows(rows);
+        new_ids.truncate(old_count);
+        new_ids
+    }
+}
+
+impl Table {
+    /// For each state, the shortest input reaching it from the start, the lexicographically
+    /// smallest among those of equal length. `None` for unreachable states.
+    pub fn access_strings(&self) -> Vec<Option<Vec<u8>>> {
+        let (reachable, parents) = self.bfs_tree();
+        (0..self.state_count())
+            .map(|state| reachable[state].then(|| Self::tree_path(&parents, state)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::{InfiniteLanguage, LengthSpectrum, Pump};
+    use crate::{
+        tests::{exactly, short_inputs},
+        Program, Table,
+    };
+
+    #[test]
+    fn access_strings_lead_to_their_states() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            for (state, access) in table.access_strings().into_iter().enumerate() {
+                assert_eq!(table.run_from(0, &access.unwrap()), state);
+            }
+        }
+
+        assert_eq!(
+            exactly(&[1, 2]).access_strings(),
+            [Some(vec![]), Some(vec![1]), Some(vec![1, 2]), Some(vec![0])]
+        );
+    }
+
+    #[test]
+    fn unreachable_states_have_no_access_string() {
+        let table = Table::from_rows(vec![(false, [0; 16]), (true, [0; 16])]);
+        assert_eq!(table.access_strings(), [Some(vec![]), None]);
+    }
+
+    #[test]
+    fn hopeless_region_collapses() {
+        // 2 and 3 cycle and can leave for the sink 4, but none of them accept
+        let mut start = [4; 16];
+        start[0] = 1;
+        start[1] = 2;
+        let mut three = [2; 16];
+        three[0] = 4;
+        let mut table = Table::from_rows(vec![
+            (false, start),
+            (true, [1; 16]),
+            (false, [3; 16]),
+            (false, three),
+            (false, [4; 16]),
+        ]);
+        let before = table.clone();
+        assert_eq!(table.trim_hopeless(), [0, 1, 2, 2, 2]);
+
+        let mut start = [2; 16];
+        start[0] = 1;
+        assert!(table
+            .rows()
+            .eq([(false, start), (true, [1; 16]), (false, [2; 16])].iter()));
+        for input in short_inputs(3) {
+            assert_eq!(table.accepts(&input), before.accepts(&input));
+        }
+    }
+
+    #[test]
+    fn trimming_completes_the_table() {
+        let mut edges = [0; 16];
+        edges[5] = Table::MISSING;
+        let mut table = Table::from_rows(vec![(true, edges)]);
+        assert_eq!(table.trim_hopeless(), [0]);
+        edges[5] = 1;
+        assert!(table.rows().eq([(true, edges), (false, [1; 16])].iter()));
+    }
+
+    #[test]
+    fn hopeless_start_leaves_one_dead_state() {
+        let mut table = Table::from_rows(vec![(false, [1; 16]), (false, [0; 16])]);
+        assert_eq!(table.trim_hopeless(), [0, 0]);
+        assert!(table.rows().eq([(false, [0; 16])].iter()));
+    }
+
+    #[test]
+    fn reading_a_zero_is_fatal() {
+        // accepts the inputs without a 0
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let mut table = Table::build(&program);
+        table.minimize();
+        assert_eq!(table.state_count(), 2);
+        assert_eq!(table.dead_states(), vec![1]);
+        assert_eq!(table.sink_states(), vec![1]);
+        assert_eq!(table.dead_flags(), vec![false, true]);
+        assert!(table.is_dead(1));
+        assert!(!table.is_dead(0));
+    }
+
+    #[test]
+    fn hopeless_cycle_is_dead_without_being_a_sink() {
+        // 0 accepts and leaves on 0 for 1 and 2, which swap on every symbol
+        let mut start = [0; 16];
+        start[0] = 1;
+        let table = Table::from_rows(vec![(true, start), (false, [2; 16]), (false, [1; 16])]);
+        assert_eq!(table.dead_states(), vec![1, 2]);
+        assert!(table.sink_states().is_empty());
+        assert!(table.is_dead(2));
+    }
+
+    #[test]
+    fn universal_and_empty_languages() {
+        let universal = Table::from_rows(vec![(true, [0; 16])]);
+        assert!(universal.dead_states().is_empty());
+        assert_eq!(universal.sink_states(), vec![0]);
+        let empty = Table::from_rows(vec![(false, [0; 16])]);
+        assert_eq!(empty.dead_states(), vec![0]);
+    }
+
+    #[test]
+    fn shortest_rejected_strings() {
+        assert_eq!(
+            Table::from_rows(vec![(true, [0; 16])]).shortest_rejected(),
+            None
+        );
+
+        // rejects exactly the strings starting with 0
+        let mut start = [2; 16];
+        start[0] = 1;
+        let table = Table::from_rows(vec![(true, start), (false, [1; 16]), (true, [2; 16])]);
+        assert_eq!(table.shortest_rejected(), Some(vec![0]));
+        assert_eq!(table.complement().shortest_accepted(), Some(vec![0]));
+
+        // a missing transition rejects too
+        let mut edges = [0; 16];
+        edges[4] = Table::MISSING;
+        assert_eq!(
+            Table::from_rows(vec![(true, edges)]).shortest_rejected(),
+            Some(vec![4])
+        );
+        assert_eq!(
+            Table::from_rows(vec![(false, [0; 16])]).shortest_rejected(),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn ties_go_to_the_smallest_string() {
+        // accepts the strings ending in 3 or 5
+        let mut edges = [0; 16];
+        edges[3] = 1;
+        edges[5] = 1;
+        let table = Table::from_rows(vec![(false, edges), (true, edges)]);
+        assert_eq!(table.shortest_accepted(), Some(vec![3]));
+        assert_eq!(table.shortest_rejected(), Some(vec![]));
+        assert_eq!(table.complement().shortest_rejected(), Some(vec![3]));
+    }
+
+    /// Accepts the strings of length `n` and more.
+    fn at_least(n: usize) -> Table {
+        Table::from_rows(
+            (0..=n)
+                .map(|state| (state == n, [(state + 1).min(n); 16]))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn even_lengths() {
+        let table = Table::from_rows(vec![(true, [1; 16]), (false, [0; 16])]);
+        let spectrum = table.length_spectrum();
+        assert_eq!(
+            spectrum,
+            LengthSpectrum {
+                preperiod: vec![],
+                period: vec![true, false],
+            }
+        );
+        assert!(spectrum.contains(0));
+        assert!(!spectrum.contains(7));
+        assert!(spectrum.contains(100));
+    }
+
+    #[test]
+    fn lengths_from_three() {
+        let spectrum = at_least(3).length_spectrum();
+        assert_eq!(
+            spectrum,
+            LengthSpectrum {
+                preperiod: vec![false, false, false],
+                period: vec![true],
+            }
+        );
+        assert!(!spectrum.contains(2));
+        assert!(spectrum.contains(3));
+        assert!(spectrum.contains(1000));
+    }
+
+    #[test]
+    fn empty_language_spectrum() {
+        let spectrum = Table::from_rows(vec![(false, [0; 16])]).length_spectrum();
+        assert!((0..10).all(|len| !spectrum.contains(len)));
+        assert_eq!(spectrum.period, vec![false]);
+    }
+
+    #[test]
+    fn acceptance_probability_matches_brute_force() {
+        for program in crate::tests::benchmarks().take(2) {
+            let table = Table::build(&program);
+            let mut accepted = [0usize; 5];
+            for input in short_inputs(4) {
+                if table.accepts(&input) {
+                    accepted[input.len()] += 1;
+                }
+            }
+            for (len, &count) in accepted.iter().enumerate() {
+                let expected = count as f64 / 16f64.powi(len as i32);
+                let probability = table.acceptance_probability(len);
+                assert!(
+                    (probability - expected).abs() < 1e-12,
+                    "{len}: {probability} {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn closed_languages() {
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        for table in [
+            Table::build(&program),
+            Table::from_rows(vec![(true, [0; 16])]),
+            Table::from_rows(vec![(false, [0; 16])]),
+        ] {
+            assert!(table.is_prefix_closed());
+            assert!(table.is_suffix_closed());
+        }
+    }
+
+    #[test]
+    fn open_languages() {
+        let word = exactly(&[0, 1]);
+        assert_eq!(word.prefix_closure_witness(), Some(vec![0, 1]));
+        assert_eq!(word.suffix_closure_witness(), Some(vec![0, 1]));
+
+        // accepts the strings not starting with 0
+        let mut start = [2; 16];
+        start[0] = 1;
+        let table = Table::from_rows(vec![(true, start), (false, [1; 16]), (true, [2; 16])]);
+        assert!(table.is_prefix_closed());
+        assert!(!table.is_suffix_closed());
+        // 10 is accepted but 0 isn't
+        assert_eq!(table.suffix_closure_witness(), Some(vec![1, 0]));
+    }
+
+    #[test]
+    fn longest_of_finite_languages() {
+        let table = exactly(&[3, 1]).union(&exactly(&[2]));
+        assert!(table.is_finite());
+        assert_eq!(table.longest_accepted(), Ok(Some(vec![3, 1])));
+        // ties go to the smallest string
+        let table = exactly(&[1, 2]).union(&exactly(&[0, 5]));
+        assert_eq!(table.longest_accepted(), Ok(Some(vec![0, 5])));
+        assert_eq!(exactly(&[]).longest_accepted(), Ok(Some(vec![])));
+        assert_eq!(
+            Table::from_rows(vec![(false, [0; 16])]).longest_accepted(),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn longest_of_an_infinite_language() {
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let table = Table::build(&program).minimized();
+        assert!(!table.is_finite());
+        assert_eq!(
+            table.longest_accepted(),
+            Err(InfiniteLanguage {
+                prefix: vec![],
+                cycle: vec![1],
+            })
+        );
+    }
+
+    #[test]
+    fn hand_checked_pumps() {
+        let cells = NonZeroUsize::new(3).unwrap();
+        // the inputs without a 0: any nonzero symbol can be repeated right away
+        let table = Table::build(&Program::new(">+[>.,[>]<<]", cells)).canonical();
+        assert_eq!(
+            table.pump(),
+            Some(Pump {
+                prefix: vec![],
+                cycle: vec![1],
+                suffix: vec![],
+            })
+        );
+        // the nonempty inputs of even length: after one symbol, pairs can be repeated, and one
+        // more symbol is needed to accept
+        let cells = NonZeroUsize::new(2).unwrap();
+        let table = Table::build(&Program::new("+[>,,.<]", cells)).canonical();
+        assert_eq!(
+            table.pump(),
+            Some(Pump {
+                prefix: vec![0],
+                cycle: vec![0, 0],
+                suffix: vec![0],
+            })
+        );
+        assert_eq!(exactly(&[1, 2]).pump(), None);
+    }
+}
@@ -0,0 +1,122 @@
+use std::num::NonZeroUsize;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::{Instruction, Program};
+
+const MAX_CELLS: usize = 3;
+const MAX_INSTRUCTIONS: usize = 64;
+const MAX_NESTING: usize = 4;
+
+// Reads and accepts are weighted up so that a good share of programs have nontrivial languages.
+const WEIGHTS: [(Instruction, u8); 8] = [
+    (Instruction::MoveLeft, 2),
+    (Instruction::MoveRight, 2),
+    (Instruction::Increment, 2),
+    (Instruction::Decrement, 2),
+    (Instruction::StartLoop, 2),
+    (Instruction::EndLoop, 2),
+    (Instruction::Read, 3),
+    (Instruction::Accept, 2),
+];
+
+/// Generates programs with balanced brackets. Every prefix of the input bytes decodes to a
+/// shorter balanced program, so fuzzers shrinking the input also shrink the program.
+impl<'a> Arbitrary<'a> for Program {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let cell_count = NonZeroUsize::new(u.int_in_range(1..=MAX_CELLS)?).unwrap();
+
+        let mut instructions = Vec::new();
+        let mut depth = 0;
+        while instructions.len() < MAX_INSTRUCTIONS && !u.is_empty() {
+            let candidates: Vec<(Instruction, u8)> = WEIGHTS
+                .into_iter()
+                .filter(|&(instruction, _)| match instruction {
+                    Instruction::StartLoop => depth < MAX_NESTING,
+                    Instruction::EndLoop => depth > 0,
+                    _ => true,
+                })
+                .collect();
+            let total: u8 = candidates.iter().map(|(_, weight)| weight).sum();
+
+            let mut choice = u.int_in_range(0..=total - 1)?;
+            let mut chosen = candidates[0].0;
+            for (instruction, weight) in candidates {
+                if choice < weight {
+                    chosen = instruction;
+                    break;
+                }
+                choice -= weight;
+            }
+
+            match chosen {
+                Instruction::StartLoop => depth += 1,
+                Instruction::EndLoop => depth -= 1,
+                _ => {}
+            }
+            instructions.push(chosen);
+        }
+        instructions.extend(std::iter::repeat_n(Instruction::EndLoop, depth));
+
+        Ok(Self {
+            cell_count,
+            instructions,
+            spans: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use super::{MAX_CELLS, MAX_NESTING};
+    use crate::{rng::Rng, BuildOptions, Instruction, Program, Table};
+
+    fn programs() -> impl Iterator<Item = Program> {
+        let mut rng = Rng::new(109);
+        (0..300).map(move |_| {
+            let bytes: Vec<u8> = (0..rng.below(200)).map(|_| rng.next_u64() as u8).collect();
+            Program::arbitrary(&mut Unstructured::new(&bytes)).unwrap()
+        })
+    }
+
+    #[test]
+    fn brackets_are_balanced_and_bounded() {
+        for program in programs() {
+            assert!(program.cell_count.get() <= MAX_CELLS);
+            let mut depth = 0;
+            for instruction in &program.instructions {
+                match instruction {
+                    Instruction::StartLoop => depth += 1,
+                    Instruction::EndLoop => depth -= 1,
+                    _ => {}
+                }
+                assert!((0..=MAX_NESTING).contains(&depth), "{program:?}");
+            }
+            assert_eq!(depth, 0, "{program:?}");
+        }
+    }
+
+    #[test]
+    fn build_and_minimize_are_well_behaved() {
+        let options = BuildOptions {
+            max_states: Some(20_000),
+            ..BuildOptions::default()
+        };
+        for program in programs() {
+            let Ok((table, _)) = Table::build_with(&program, &options) else {
+                continue;
+            };
+            let minimized = table.minimized();
+            assert!(minimized.state_count() <= table.state_count());
+            // minimizing again may renumber the states, but nothing else
+            let again = minimized.minimized();
+            assert_eq!(again.state_count(), minimized.state_count(), "{program:?}");
+            assert!(again
+                .restrict_reachable(0)
+                .rows()
+                .eq(minimized.restrict_reachable(0).rows()));
+        }
+    }
+}
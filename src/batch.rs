@@ -0,0 +1,64 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::Table;
+
+/// Inputs per rayon task, so that short strings don't drown in scheduling overhead.
+#[cfg(feature = "rayon")]
+const MIN_CHUNK: usize = 4096;
+
+impl Table {
+    /// [`Table::accepts`] for every input, in order. Runs in parallel with the `rayon`
+    /// feature.
+    pub fn classify_batch(&self, inputs: &[Vec<u8>]) -> Vec<bool> {
+        #[cfg(feature = "rayon")]
+        {
+            inputs
+                .par_iter()
+                .with_min_len(MIN_CHUNK)
+                .map(|input| self.accepts(input))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            self.classify_iter(inputs).collect()
+        }
+    }
+
+    /// Lazily runs [`Table::accepts`] on each input, without collecting anything.
+    pub fn classify_iter<'a, I>(&'a self, inputs: I) -> impl Iterator<Item = bool> + 'a
+    where
+        I: IntoIterator + 'a,
+        I::Item: AsRef<[u8]>,
+    {
+        inputs
+            .into_iter()
+            .map(move |input| self.accepts(input.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::short_inputs, Table};
+
+    #[test]
+    fn batches_match_one_at_a_time() {
+        // more inputs than a rayon task takes
+        let inputs: Vec<Vec<u8>> = short_inputs(3).collect();
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            let expected: Vec<bool> = inputs.iter().map(|input| table.accepts(input)).collect();
+            assert_eq!(table.classify_batch(&inputs), expected);
+            assert!(table.classify_iter(&inputs).eq(expected.iter().copied()));
+        }
+    }
+
+    #[test]
+    fn classify_iter_takes_any_byte_slices() {
+        // accepts exactly 1 2
+        let table = crate::tests::exactly(&[1, 2]);
+        let inputs: [&[u8]; 3] = [&[1, 2], &[1], &[]];
+        assert!(table.classify_iter(inputs).eq([true, false, false]));
+        assert!(table.classify_batch(&[]).is_empty());
+    }
+}
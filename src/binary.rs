@@ -0,0 +1,156 @@
+use crate::{ImportError, Table};
+
+const MAGIC: &[u8; 4] = b"BFA1";
+/// Stands for [`Table::MISSING`], which doesn't fit in the 32-bit targets.
+const MISSING: u32 = u32::MAX;
+
+impl Table {
+    /// A compact binary encoding: the magic `BFA1`, the state count as a little-endian `u32`,
+    /// then per state an accepting byte and 16 little-endian `u32` targets, with `u32::MAX`
+    /// for a missing transition.
+    ///
+    /// # Panics
+    ///
+    /// If the table has `u32::MAX` states or more.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let state_count = u32::try_from(self.state_count())
+            .ok()
+            .filter(|&count| count != MISSING)
+            .expect("too many states for the binary format");
+
+        let mut bytes = Vec::with_capacity(8 + self.state_count() * 65);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&state_count.to_le_bytes());
+        for (accepting, edges) in self.rows() {
+            bytes.push(u8::from(*accepting));
+            for &to in edges {
+                let to = if to == Self::MISSING {
+                    MISSING
+                } else {
+                    to as u32
+                };
+                bytes.extend_from_slice(&to.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Reads the format written by [`Table::to_bytes`], checked with [`Table::validate`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ImportError> {
+        let malformed = |message: &str| ImportError::Format(message.to_string());
+        let rest = bytes
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| malformed("missing BFA1 header"))?;
+        let (count, mut rest) = rest
+            .split_first_chunk::<4>()
+            .ok_or_else(|| malformed("truncated state count"))?;
+        let state_count = u32::from_le_bytes(*count) as usize;
+        if rest.len() != state_count.saturating_mul(65) {
+            return Err(malformed("length doesn't match the state count"));
+        }
+
+        let mut rows = Vec::with_capacity(state_count);
+        for _ in 0..state_count {
+            let (&accepting, mut targets) = rest.split_first().unwrap();
+            let accepting = match accepting {
+                0 => false,
+                1 => true,
+                _ => return Err(malformed("accepting flags must be 0 or 1")),
+            };
+            let mut edges = [Self::MISSING; 16];
+            for edge in &mut edges {
+                let (to, remaining) = targets.split_first_chunk::<4>().unwrap();
+                let to = u32::from_le_bytes(*to);
+                if to != MISSING {
+                    *edge = to as usize;
+                }
+                targets = remaining;
+            }
+            rows.push((accepting, edges));
+            rest = targets;
+        }
+
+        let table = Self::from_rows(rows);
+        table.validate().map_err(ImportError::Defects)?;
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::exactly, ImportError, Table, TableDefect};
+
+    fn error(bytes: &[u8]) -> ImportError {
+        Table::from_bytes(bytes).unwrap_err()
+    }
+
+    fn malformed(message: &str) -> ImportError {
+        ImportError::Format(message.to_string())
+    }
+
+    #[test]
+    fn hand_encoded_table() {
+        // an accepting start and the sink after it, both going to the sink
+        let mut expected = b"BFA1\x02\x00\x00\x00".to_vec();
+        for accepting in [1, 0] {
+            expected.push(accepting);
+            for _ in 0..16 {
+                expected.extend_from_slice(&[1, 0, 0, 0]);
+            }
+        }
+        assert_eq!(exactly(&[]).to_bytes(), expected);
+    }
+
+    #[test]
+    fn tables_round_trip() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            let loaded = Table::from_bytes(&table.to_bytes()).unwrap();
+            assert!(loaded.rows().eq(table.rows()));
+        }
+
+        let mut edges = [Table::MISSING; 16];
+        edges[3] = 0;
+        let partial = Table::from_rows(vec![(true, edges)]);
+        let bytes = partial.to_bytes();
+        assert_eq!(bytes[9..13], [0xFF; 4]);
+        assert!(Table::from_bytes(&bytes).unwrap().rows().eq(partial.rows()));
+    }
+
+    #[test]
+    fn malformed_bytes() {
+        let bytes = exactly(&[5]).to_bytes();
+        assert_eq!(error(b"BFA2"), malformed("missing BFA1 header"));
+        assert_eq!(error(b"BFA1\x01"), malformed("truncated state count"));
+        assert_eq!(
+            error(&bytes[..bytes.len() - 1]),
+            malformed("length doesn't match the state count")
+        );
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_eq!(
+            error(&longer),
+            malformed("length doesn't match the state count")
+        );
+
+        let mut flag = bytes.clone();
+        flag[8] = 2;
+        assert_eq!(error(&flag), malformed("accepting flags must be 0 or 1"));
+
+        // state 0's transition on 1 goes to a state that doesn't exist
+        let mut target = bytes;
+        target[13] = 7;
+        assert_eq!(
+            error(&target),
+            ImportError::Defects(vec![TableDefect::TargetOutOfRange {
+                state: 0,
+                input: 1,
+                target: 7
+            }])
+        );
+        assert_eq!(
+            error(b"BFA1\x00\x00\x00\x00"),
+            ImportError::Defects(vec![TableDefect::NoStates])
+        );
+    }
+}
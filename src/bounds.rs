@@ -0,0 +1,211 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Instruction, Program};
+
+/// What a cell can hold when the machine waits at a `,`, as found by [`Program::read_bounds`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadBounds {
+    /// Position of the `,`.
+    pub position: usize,
+    pub head_position: usize,
+    /// For each cell, bit `v` is set if the cell may hold `v`.
+    pub cells: Vec<u16>,
+}
+
+impl ReadBounds {
+    pub fn allows(&self, cell: usize, value: u8) -> bool {
+        self.cells[cell] & (1 << (value & 0x0F)) != 0
+    }
+}
+
+/// Position of the bracket matching each bracket, as the interpreter finds it.
+fn matching_brackets(instructions: &[Instruction]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; instructions.len()];
+    let mut open = Vec::new();
+    for (position, instruction) in instructions.iter().enumerate() {
+        match instruction {
+            Instruction::StartLoop => open.push(position),
+            Instruction::EndLoop => {
+                if let Some(start) = open.pop() {
+                    matches[start] = Some(position);
+                    matches[position] = Some(start);
+                }
+            }
+            _ => {}
+        }
+    }
+    matches
+}
+
+/// Abstract states by `(position, head position)`, and those whose successors need updating.
+struct Analysis {
+    instruction_count: usize,
+    states: HashMap<(usize, usize), Vec<u16>>,
+    worklist: VecDeque<(usize, usize)>,
+}
+
+impl Analysis {
+    /// Joins `cells` into the abstract state at `position`, `head`.
+    fn flow(&mut self, position: usize, head: usize, cells: Vec<u16>) {
+        if position >= self.instruction_count {
+            return;
+        }
+        let changed = match self.states.get_mut(&(position, head)) {
+            Some(known) => {
+                let mut changed = false;
+                for (known, new) in known.iter_mut().zip(&cells) {
+                    changed |= *known | new != *known;
+                    *known |= new;
+                }
+                changed
+            }
+            None => {
+                self.states.insert((position, head), cells);
+                true
+            }
+        };
+        if changed {
+            self.worklist.push_back((position, head));
+        }
+    }
+}
+
+impl Program {
+    /// Over-approximates the cell values at every `,` the machine can reach, separately for
+    /// each head position it can be at there, ordered by position and then head position.
+    ///
+    /// This is an abstract interpretation tracking a set of values per cell. It never rules
+    /// out a configuration the machine can actually reach, so the build, which only explores
+    /// configurations it reached, has nothing to prune with it; it's for understanding the
+    /// program, e.g. seeing that a cell only ever holds 0 or 1.
+    pub fn read_bounds(&self) -> Vec<ReadBounds> {
+        let cell_count = self.cell_count.get();
+        let matches = matching_brackets(&self.instructions);
+        let mut analysis = Analysis {
+            instruction_count: self.instructions.len(),
+            states: HashMap::new(),
+            worklist: VecDeque::new(),
+        };
+
+        analysis.flow(0, 0, vec![1; cell_count]);
+        while let Some((position, head)) = analysis.worklist.pop_front() {
+            let mut cells = analysis.states[&(position, head)].clone();
+            match self.instructions[position] {
+                Instruction::MoveLeft => {
+                    analysis.flow(position + 1, (head + cell_count - 1) % cell_count, cells)
+                }
+                Instruction::MoveRight => {
+                    analysis.flow(position + 1, (head + 1) % cell_count, cells)
+                }
+                Instruction::Increment => {
+                    cells[head] = cells[head].rotate_left(1);
+                    analysis.flow(position + 1, head, cells);
+                }
+                Instruction::Decrement => {
+                    cells[head] = cells[head].rotate_right(1);
+                    analysis.flow(position + 1, head, cells);
+                }
+                Instruction::StartLoop => {
+                    let values = cells[head];
+                    if values & !1 != 0 {
+                        let mut inside = cells.clone();
+                        inside[head] = values & !1;
+                        analysis.flow(position + 1, head, inside);
+                    }
+                    // an unmatched `[` halts on zero
+                    if let (true, Some(end)) = (values & 1 != 0, matches[position]) {
+                        cells[head] = 1;
+                        analysis.flow(end + 1, head, cells);
+                    }
+                }
+                Instruction::EndLoop => {
+                    if let Some(start) = matches[position] {
+                        analysis.flow(start, head, cells);
+                    }
+                }
+                Instruction::Read => {
+                    cells[head] = u16::MAX;
+                    analysis.flow(position + 1, head, cells);
+                }
+                Instruction::Accept => analysis.flow(position + 1, head, cells),
+            }
+        }
+
+        let mut bounds: Vec<ReadBounds> = analysis
+            .states
+            .into_iter()
+            .filter(|&((position, _), _)| self.instructions[position] == Instruction::Read)
+            .map(|((position, head_position), cells)| ReadBounds {
+                position,
+                head_position,
+                cells,
+            })
+            .collect();
+        bounds.sort_unstable_by_key(|bounds| (bounds.position, bounds.head_position));
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, ops::ControlFlow};
+
+    use crate::{BuildOptions, Program, Table};
+
+    use super::ReadBounds;
+
+    #[test]
+    fn straight_line_values() {
+        let program = Program::new("+>++,<,", NonZeroUsize::new(2).unwrap());
+        assert_eq!(
+            program.read_bounds(),
+            [
+                ReadBounds {
+                    position: 4,
+                    head_position: 1,
+                    cells: vec![1 << 1, 1 << 2],
+                },
+                ReadBounds {
+                    position: 6,
+                    head_position: 0,
+                    cells: vec![1 << 1, u16::MAX],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn loops_are_joined() {
+        // the inner loop always leaves the cell at 0, so the second read sees a 1
+        let program = Program::new(",[-[-]+,]", NonZeroUsize::new(1).unwrap());
+        let bounds = program.read_bounds();
+        assert_eq!(bounds.len(), 2);
+        assert_eq!((bounds[0].position, bounds[0].cells[0]), (0, 1));
+        assert_eq!((bounds[1].position, bounds[1].cells[0]), (7, 1 << 1));
+        assert!(bounds[1].allows(0, 1));
+        assert!(!bounds[1].allows(0, 0));
+    }
+
+    #[test]
+    fn reached_configurations_are_within_bounds() {
+        for program in crate::tests::benchmarks() {
+            let bounds = program.read_bounds();
+            Table::build_observed(&program, &BuildOptions::default(), |_, state| {
+                if let Some(configuration) = state.configuration() {
+                    let bound = bounds
+                        .iter()
+                        .find(|bound| {
+                            bound.position + 1 == configuration.instruction_position
+                                && bound.head_position == configuration.head_position
+                        })
+                        .expect("every reachable read has bounds");
+                    for (cell, &value) in configuration.cells.iter().enumerate() {
+                        assert!(bound.allows(cell, value));
+                    }
+                }
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{Program, Table};
+
+/// A table read as a Büchi automaton over endless input streams: a stream is accepted when
+/// the run passes through accepting states infinitely often.
+///
+/// States whose machine has halted (or diverged) have no run beyond them, so they never
+/// contribute to acceptance even though the table gives them self-loops.
+#[derive(Clone, Debug)]
+pub struct BuchiTable {
+    table: Table,
+    halted: Vec<bool>,
+}
+
+impl BuchiTable {
+    /// Explores the same states as [`Table::build`], with the same numbering.
+    pub fn build(program: &Program) -> Self {
+        let (table, _, halted) = Table::explore(program, &Default::default(), None, None)
+            .expect("no limits are set by default");
+        Self { table, halted }
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn is_halted(&self, state: usize) -> bool {
+        self.halted[state]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lasso().is_none()
+    }
+
+    /// An accepted stream `prefix · cycle · cycle · …`, if there is one. The cycle starts and
+    /// ends at an accepting state and is never empty.
+    pub fn lasso(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        let (reachable, parents) = self.table.bfs_tree();
+        let state = self
+            .table
+            .sccs()
+            .into_iter()
+            .filter(|component| {
+                reachable[component[0]]
+                    && !self.halted[component[0]]
+                    && self.table.is_cyclic_component(component)
+            })
+            .find_map(|component| {
+                component
+                    .into_iter()
+                    .find(|&state| self.table.is_accepting(state))
+            })?;
+
+        let cycle = self.table.shortest_cycle(state)?;
+        Some((Table::tree_path(&parents, state), cycle))
+    }
+
+    /// Same as [`Table::dot`] on the underlying table.
+    pub fn dot(&self) -> String {
+        self.table.dot()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::BuchiTable;
+    use crate::{Program, Table};
+
+    fn build(text: &str) -> BuchiTable {
+        BuchiTable::build(&Program::new(text, NonZeroUsize::new(1).unwrap()))
+    }
+
+    #[test]
+    fn finite_language_has_no_accepting_cycle() {
+        // accepts the strings of length 1, then halts on the next symbol
+        let buchi = build(",.,");
+        assert_eq!(
+            buchi.table().accepted_strings(3).len(),
+            16,
+            "accepts finitely many finite strings"
+        );
+        assert!(buchi.is_empty());
+        assert_eq!(buchi.lasso(), None);
+    }
+
+    #[test]
+    fn halted_acceptance_does_not_count() {
+        let buchi = build(".");
+        assert!(buchi.table().accepts(&[1, 2, 3]));
+        assert!(buchi.is_halted(0));
+        assert!(buchi.is_empty());
+    }
+
+    #[test]
+    fn accepting_loop() {
+        // accepts again after every nonzero symbol, and reading 1 restores the start
+        let buchi = build("+[.,]");
+        assert!(!buchi.is_empty());
+        assert_eq!(buchi.lasso(), Some((vec![], vec![1])));
+        assert!(buchi.table().rows().eq(Table::build(&Program::new(
+            "+[.,]",
+            NonZeroUsize::new(1).unwrap()
+        ))
+        .rows()));
+    }
+}
@@ -0,0 +1,99 @@
+use num_bigint::BigUint;
+
+use crate::Table;
+
+/// The number of strings in a language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cardinality {
+    Finite(BigUint),
+    Infinite,
+}
+
+impl Table {
+    /// Counts the accepted strings by summing paths to acceptance over the DAG of useful
+    /// states. Even small tables can accept astronomically many strings, so the count is a
+    /// big integer.
+    pub fn cardinality(&self) -> Cardinality {
+        if !self.is_finite() {
+            return Cardinality::Infinite;
+        }
+
+        let (useful, order) = self.useful_dag_order();
+        let mut counts = vec![BigUint::ZERO; self.state_count()];
+        for &state in &order {
+            let mut count = BigUint::from(u8::from(self.is_accepting(state)));
+            for &to in self.transitions(state) {
+                if to != Table::MISSING && useful[to] {
+                    count += &counts[to];
+                }
+            }
+            counts[state] = count;
+        }
+
+        Cardinality::Finite(counts.swap_remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use num_bigint::BigUint;
+
+    use super::Cardinality;
+    use crate::{
+        tests::{exactly, short_inputs},
+        Program, Table,
+    };
+
+    /// Accepts every string of length at most `n`.
+    fn at_most(n: usize) -> Table {
+        let sink = n + 1;
+        Table::from_rows(
+            (0..=sink)
+                .map(|state| (state <= n, [(state + 1).min(sink); 16]))
+                .collect(),
+        )
+    }
+
+    fn brute_force(table: &Table, max_len: usize) -> Cardinality {
+        let count = short_inputs(max_len)
+            .filter(|input| table.accepts(input))
+            .count();
+        Cardinality::Finite(BigUint::from(count))
+    }
+
+    #[test]
+    fn counts_match_enumeration() {
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let no_zeros = Table::build(&program);
+        for (table, max_len) in [
+            (at_most(3), 3),
+            (no_zeros.intersection(&at_most(3)), 3),
+            (exactly(&[1, 2]).union(&exactly(&[3])), 2),
+            (exactly(&[]), 0),
+            (Table::from_rows(vec![(false, [0; 16])]), 2),
+        ] {
+            assert_eq!(table.cardinality(), brute_force(&table, max_len));
+        }
+        assert_eq!(
+            no_zeros.intersection(&at_most(3)).cardinality(),
+            Cardinality::Finite(BigUint::from(1u32 + 15 + 225 + 3375))
+        );
+    }
+
+    #[test]
+    fn infinite_languages() {
+        let program = Program::new("+[>,,.<]", NonZeroUsize::new(2).unwrap());
+        assert_eq!(Table::build(&program).cardinality(), Cardinality::Infinite);
+    }
+
+    #[test]
+    fn counts_beyond_u64() {
+        // 16^20 strings of length 20 alone
+        assert_eq!(
+            at_most(20).cardinality(),
+            Cardinality::Finite((0..=20).map(|len| BigUint::from(16u32).pow(len)).sum())
+        );
+    }
+}
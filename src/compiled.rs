@@ -0,0 +1,43 @@
+/// A minimized table stored in static arrays, as produced by the `bfa_dfa!` macro of the
+/// `bfa-macros` crate. Running it never allocates and needs nothing beyond `core`.
+#[derive(Clone, Copy, Debug)]
+pub struct CompiledTable {
+    accepting: &'static [bool],
+    transitions: &'static [[u32; 16]],
+}
+
+impl CompiledTable {
+    /// Used by the macro expansion; the arrays must describe a complete table with at least
+    /// one state.
+    #[doc(hidden)]
+    pub const fn from_static(
+        accepting: &'static [bool],
+        transitions: &'static [[u32; 16]],
+    ) -> Self {
+        Self {
+            accepting,
+            transitions,
+        }
+    }
+
+    pub const fn state_count(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub const fn is_accepting(&self, state: usize) -> bool {
+        self.accepting[state]
+    }
+
+    pub const fn transition(&self, state: usize, input: u8) -> usize {
+        self.transitions[state][(input & 0x0F) as usize] as usize
+    }
+
+    /// Only the low four bits of each symbol are used, as in [`crate::Table::accepts`].
+    pub fn accepts(&self, input: &[u8]) -> bool {
+        let mut state = 0;
+        for &symbol in input {
+            state = self.transition(state, symbol);
+        }
+        self.is_accepting(state)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::Table;
+
+/// Interpreter steps over all transitions of a built table.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CostStats {
+    pub max: u32,
+    pub mean: f64,
+    pub total: u64,
+}
+
+impl Table {
+    /// Instructions the interpreter executed for this transition during the build, including
+    /// those run before a divergence was detected. `None` for tables that didn't come straight
+    /// from a build, such as minimized or imported ones.
+    pub fn cost(&self, state: usize, input: u8) -> Option<u32> {
+        self.costs
+            .get(state)
+            .map(|costs| costs[(input & 0x0F) as usize])
+    }
+
+    /// Aggregate of [`Table::cost`] over every transition, or `None` if costs are unknown.
+    pub fn cost_stats(&self) -> Option<CostStats> {
+        if self.costs.is_empty() {
+            return None;
+        }
+
+        let costs = self.costs.iter().flatten();
+        let total: u64 = costs.clone().map(|&cost| u64::from(cost)).sum();
+        Some(CostStats {
+            max: costs.copied().max().unwrap_or(0),
+            mean: total as f64 / (self.costs.len() * 16) as f64,
+            total,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{Program, Table};
+
+    fn build(text: &str) -> Table {
+        Table::build(&Program::new(text, NonZeroUsize::new(1).unwrap()))
+    }
+
+    #[test]
+    fn hand_counted_costs() {
+        // `[-]` takes three steps per iteration, plus the failing test and the `.`
+        let table = build(",[-].");
+        assert_eq!(table.cost(0, 0), Some(2));
+        assert_eq!(table.cost(0, 1), Some(5));
+        assert_eq!(table.cost(0, 15), Some(47));
+        // the halted state runs nothing
+        assert_eq!(table.cost(table.transition(0, 0), 3), Some(0));
+
+        let stats = table.cost_stats().unwrap();
+        assert_eq!(stats.max, 47);
+        assert_eq!(stats.total, 392);
+        assert_eq!(stats.mean, 392.0 / 32.0);
+    }
+
+    #[test]
+    fn divergence_costs_the_steps_until_detection() {
+        // `[`, `]` and then `[` again in the same configuration
+        let table = build(",[]");
+        assert_eq!(table.cost(0, 0), Some(1));
+        assert_eq!(table.cost(0, 1), Some(3));
+    }
+
+    #[test]
+    fn derived_tables_have_no_costs() {
+        let table = build(",[-].").minimized();
+        assert_eq!(table.cost(0, 0), None);
+        assert!(table.cost_stats().is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::Table;
+
+/// Which parts of a table a set of inputs exercises, see [`Table::coverage`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coverage {
+    pub visited_states: usize,
+    pub total_states: usize,
+    pub traversed_transitions: usize,
+    /// Transitions other than [`Table::MISSING`] ones.
+    pub total_transitions: usize,
+    pub unvisited_states: Vec<usize>,
+    /// `(state, input)` pairs in ascending order.
+    pub untraversed_transitions: Vec<(usize, u8)>,
+    visited: Vec<bool>,
+    traversed: Vec<[bool; 16]>,
+}
+
+impl Coverage {
+    pub fn is_state_visited(&self, state: usize) -> bool {
+        self.visited.get(state).copied().unwrap_or(false)
+    }
+
+    pub fn is_transition_traversed(&self, state: usize, input: u8) -> bool {
+        self.traversed
+            .get(state)
+            .is_some_and(|traversed| traversed[(input & 0x0F) as usize])
+    }
+}
+
+impl Table {
+    /// Runs every input and records the states and transitions they pass through. The start
+    /// state counts as visited, and a run stops at a missing transition.
+    pub fn coverage<I>(&self, inputs: I) -> Coverage
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut visited = vec![false; self.state_count()];
+        let mut traversed = vec![[false; 16]; self.state_count()];
+        visited[0] = true;
+        for input in inputs {
+            let mut state = 0;
+            for &symbol in input.as_ref() {
+                let symbol = symbol & 0x0F;
+                let next = self.transition(state, symbol);
+                if next == Self::MISSING {
+                    break;
+                }
+                traversed[state][symbol as usize] = true;
+                visited[next] = true;
+                state = next;
+            }
+        }
+
+        let mut untraversed_transitions = Vec::new();
+        let mut total_transitions = 0;
+        for (state, edges) in self.rows().map(|(_, edges)| edges).enumerate() {
+            for input in 0..16 {
+                if edges[input as usize] != Self::MISSING {
+                    total_transitions += 1;
+                    if !traversed[state][input as usize] {
+                        untraversed_transitions.push((state, input));
+                    }
+                }
+            }
+        }
+        let unvisited_states: Vec<usize> = (0..self.state_count())
+            .filter(|&state| !visited[state])
+            .collect();
+
+        Coverage {
+            visited_states: self.state_count() - unvisited_states.len(),
+            total_states: self.state_count(),
+            traversed_transitions: total_transitions - untraversed_transitions.len(),
+            total_transitions,
+            unvisited_states,
+            untraversed_transitions,
+            visited,
+            traversed,
+        }
+    }
+}
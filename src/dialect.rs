@@ -0,0 +1,395 @@
+use std::{fmt::Display, num::NonZeroUsize};
+
+use crate::{Instruction, Program, Span};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// Text that's part of the dialect's syntax but doesn't stand for an instruction.
+    UnknownToken { span: Span },
+    /// The source ends partway through an instruction.
+    Incomplete { span: Span },
+    /// A `[` or `]` without a match, rejected by [`Program::from_instructions`].
+    UnmatchedBracket { span: Span },
+    /// A repetition count that's zero, too large, or after an instruction that can't be
+    /// repeated, spanning its digits.
+    InvalidCount { span: Span },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownToken { span } => write!(f, "unknown token at offset {}", span.start),
+            Self::Incomplete { span } => {
+                write!(f, "incomplete instruction at offset {}", span.start)
+            }
+            Self::UnmatchedBracket { span } => {
+                write!(f, "unmatched bracket at offset {}", span.start)
+            }
+            Self::InvalidCount { span } => write!(f, "invalid count at offset {}", span.start),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A surface syntax for the eight instructions.
+pub trait Dialect {
+    /// The instructions in `src`, each with its byte range in `src`.
+    fn tokenize(&self, src: &str) -> Result<Vec<(Instruction, Span)>, ParseError>;
+}
+
+/// The usual syntax, where every other character is a comment. Never fails.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Brainfuck;
+
+impl Dialect for Brainfuck {
+    fn tokenize(&self, src: &str) -> Result<Vec<(Instruction, Span)>, ParseError> {
+        Ok(src
+            .char_indices()
+            .filter_map(|(start, c)| {
+                let span = Span {
+                    start,
+                    end: start + c.len_utf8(),
+                };
+                Some((Instruction::from_char(c)?, span))
+            })
+            .collect())
+    }
+}
+
+/// The usual syntax, where `+`, `-`, `<` and `>` may be followed by a decimal count of at
+/// most [`Counted::MAX_COUNT`], e.g. `>12` for twelve `>`. Each repetition spans the whole
+/// token. Digits that don't follow an instruction are a comment.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Counted;
+
+impl Counted {
+    pub const MAX_COUNT: usize = u16::MAX as usize;
+}
+
+impl Dialect for Counted {
+    fn tokenize(&self, src: &str) -> Result<Vec<(Instruction, Span)>, ParseError> {
+        let mut tokens = Vec::new();
+        let mut chars = src.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let Some(instruction) = Instruction::from_char(c) else {
+                continue;
+            };
+            let digits_start = start + c.len_utf8();
+            let mut end = digits_start;
+            while let Some(&(_, digit @ '0'..='9')) = chars.peek() {
+                end += digit.len_utf8();
+                chars.next();
+            }
+            if end == digits_start {
+                tokens.push((instruction, Span { start, end }));
+                continue;
+            }
+
+            let count_span = Span {
+                start: digits_start,
+                end,
+            };
+            let repeatable = matches!(
+                instruction,
+                Instruction::Increment
+                    | Instruction::Decrement
+                    | Instruction::MoveLeft
+                    | Instruction::MoveRight
+            );
+            let count = src[digits_start..end]
+                .parse::<usize>()
+                .ok()
+                .filter(|&count| repeatable && (1..=Self::MAX_COUNT).contains(&count))
+                .ok_or(ParseError::InvalidCount { span: count_span })?;
+            tokens.extend(std::iter::repeat_n(
+                (instruction, Span { start, end }),
+                count,
+            ));
+        }
+        Ok(tokens)
+    }
+}
+
+/// Ook!, where each instruction is a pair of `Ook.`, `Ook?` and `Ook!` words, e.g.
+/// `Ook. Ook?` for `>`. Text between the words is a comment.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ook;
+
+impl Dialect for Ook {
+    fn tokenize(&self, src: &str) -> Result<Vec<(Instruction, Span)>, ParseError> {
+        let words: Vec<(u8, usize)> = src
+            .match_indices("Ook")
+            .filter_map(|(start, _)| {
+                let mark = *src.as_bytes().get(start + 3)?;
+                matches!(mark, b'.' | b'?' | b'!').then_some((mark, start))
+            })
+            .collect();
+
+        words
+            .chunks(2)
+            .map(|pair| {
+                let &[(first, start), (second, second_start)] = pair else {
+                    let start = pair[0].1;
+                    return Err(ParseError::Incomplete {
+                        span: Span {
+                            start,
+                            end: start + 4,
+                        },
+                    });
+                };
+                let span = Span {
+                    start,
+                    end: second_start + 4,
+                };
+                let instruction = match (first, second) {
+                    (b'.', b'?') => Instruction::MoveRight,
+                    (b'?', b'.') => Instruction::MoveLeft,
+                    (b'.', b'.') => Instruction::Increment,
+                    (b'!', b'!') => Instruction::Decrement,
+                    (b'!', b'.') => Instruction::Accept,
+                    (b'.', b'!') => Instruction::Read,
+                    (b'!', b'?') => Instruction::StartLoop,
+                    (b'?', b'!') => Instruction::EndLoop,
+                    _ => return Err(ParseError::UnknownToken { span }),
+                };
+                Ok((instruction, span))
+            })
+            .collect()
+    }
+}
+
+/// How [`Program::parse`] reads the program text.
+#[derive(Clone, Copy)]
+pub struct ProgramOptions<'d> {
+    pub dialect: &'d dyn Dialect,
+}
+
+impl Default for ProgramOptions<'_> {
+    fn default() -> Self {
+        Self {
+            dialect: &Brainfuck,
+        }
+    }
+}
+
+impl Program {
+    /// Like [`Program::new`], but in the dialect given by `options`. Spans refer to
+    /// `program_text`.
+    pub fn parse(
+        program_text: &str,
+        cell_count: NonZeroUsize,
+        options: &ProgramOptions,
+    ) -> Result<Self, ParseError> {
+        let (instructions, spans) = options.dialect.tokenize(program_text)?.into_iter().unzip();
+        Ok(Self {
+            cell_count,
+            instructions,
+            spans,
+        })
+    }
+
+    /// A program running `instructions`, which must have balanced brackets. Each span is
+    /// the instruction's offset in the [`Display`](std::fmt::Display) text. Programs with
+    /// unbalanced brackets can still be made by filling in the fields directly.
+    pub fn from_instructions(
+        instructions: Vec<Instruction>,
+        cell_count: NonZeroUsize,
+    ) -> Result<Self, ParseError> {
+        let span = |start| Span {
+            start,
+            end: start + 1,
+        };
+        let mut open = Vec::new();
+        for (position, instruction) in instructions.iter().enumerate() {
+            match instruction {
+                Instruction::StartLoop => open.push(position),
+                Instruction::EndLoop if open.pop().is_none() => {
+                    return Err(ParseError::UnmatchedBracket {
+                        span: span(position),
+                    });
+                }
+                _ => {}
+            }
+        }
+        if let Some(&position) = open.first() {
+            return Err(ParseError::UnmatchedBracket {
+                span: span(position),
+            });
+        }
+
+        Ok(Self {
+            cell_count,
+            spans: (0..instructions.len()).map(span).collect(),
+            instructions,
+        })
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::Table;
+
+    #[test]
+    fn programs_from_instructions() {
+        use Instruction::*;
+        let cell_count = NonZeroUsize::new(2).unwrap();
+        let instructions = vec![Read, StartLoop, MoveRight, Accept, Read, EndLoop];
+        let program = Program::from_instructions(instructions.clone(), cell_count).unwrap();
+        assert_eq!(program.instructions, instructions);
+        // spans point into the displayed text
+        assert_eq!(program.to_string(), ",[>.,]");
+        assert_eq!(program.spans[3], Span { start: 3, end: 4 });
+        assert!(Table::build(&program)
+            .rows()
+            .eq(Table::build(&Program::new(",[>.,]", cell_count)).rows()));
+
+        assert_eq!(
+            Program::from_instructions(vec![Read, EndLoop, StartLoop], cell_count).unwrap_err(),
+            ParseError::UnmatchedBracket {
+                span: Span { start: 1, end: 2 }
+            }
+        );
+        // the outermost unclosed `[` is reported
+        assert_eq!(
+            Program::from_instructions(vec![StartLoop, StartLoop, EndLoop], cell_count)
+                .unwrap_err(),
+            ParseError::UnmatchedBracket {
+                span: Span { start: 0, end: 1 }
+            }
+        );
+        assert!(Program::from_instructions(Vec::new(), cell_count)
+            .unwrap()
+            .instructions
+            .is_empty());
+    }
+
+    fn counted(src: &str) -> Result<Program, ParseError> {
+        Program::parse(
+            src,
+            NonZeroUsize::new(2).unwrap(),
+            &ProgramOptions { dialect: &Counted },
+        )
+    }
+
+    fn ook(src: &str) -> Result<Program, ParseError> {
+        Program::parse(
+            src,
+            NonZeroUsize::new(2).unwrap(),
+            &ProgramOptions { dialect: &Ook },
+        )
+    }
+
+    /// The Ook! spelling of a program in the usual syntax.
+    fn to_ook(src: &str) -> String {
+        let words: Vec<&str> = src
+            .chars()
+            .filter_map(|c| match c {
+                '>' => Some("Ook. Ook?"),
+                '<' => Some("Ook? Ook."),
+                '+' => Some("Ook. Ook."),
+                '-' => Some("Ook! Ook!"),
+                '.' => Some("Ook! Ook."),
+                ',' => Some("Ook. Ook!"),
+                '[' => Some("Ook! Ook?"),
+                ']' => Some("Ook? Ook!"),
+                _ => None,
+            })
+            .collect();
+        words.join(" ")
+    }
+
+    #[test]
+    fn ook_matches_the_usual_syntax() {
+        for &(text, cells) in crate::tests::BENCHMARKS {
+            let cell_count = NonZeroUsize::new(cells).unwrap();
+            let usual = Program::new(text, cell_count);
+            let spelled =
+                Program::parse(&to_ook(text), cell_count, &ProgramOptions { dialect: &Ook })
+                    .unwrap();
+            assert_eq!(spelled.instructions, usual.instructions);
+            assert!(Table::build(&spelled)
+                .rows()
+                .eq(Table::build(&usual).rows()));
+        }
+    }
+
+    #[test]
+    fn ook_spans_and_comments() {
+        let program = ook("Ook. Ook! then Ook!\nOok. Ooky").unwrap();
+        assert_eq!(
+            program.instructions,
+            [Instruction::Read, Instruction::Accept]
+        );
+        assert_eq!(
+            program.spans,
+            [Span { start: 0, end: 9 }, Span { start: 15, end: 24 }]
+        );
+        assert!(ook("no words").unwrap().instructions.is_empty());
+    }
+
+    #[test]
+    fn ook_errors() {
+        assert_eq!(
+            ook("Ook. Ook? Ook!").unwrap_err(),
+            ParseError::Incomplete {
+                span: Span { start: 10, end: 14 }
+            }
+        );
+        assert_eq!(
+            ook("Ook. Ook. Ook? Ook?").unwrap_err(),
+            ParseError::UnknownToken {
+                span: Span { start: 10, end: 19 }
+            }
+        );
+    }
+
+    #[test]
+    fn counts_expand_to_repetitions() {
+        let program = counted(",+5[>2.<-3]").unwrap();
+        let expanded = Program::new(",+++++[>>.<---]", NonZeroUsize::new(2).unwrap());
+        assert_eq!(program.instructions, expanded.instructions);
+        assert!(Table::build(&program)
+            .rows()
+            .eq(Table::build(&expanded).rows()));
+    }
+
+    #[test]
+    fn repetitions_span_the_whole_token() {
+        let program = counted("a+12.").unwrap();
+        assert_eq!(program.instructions.len(), 13);
+        assert!(program.spans[..12]
+            .iter()
+            .all(|&span| span == Span { start: 1, end: 4 }));
+        assert_eq!(program.spans[12], Span { start: 4, end: 5 });
+    }
+
+    #[test]
+    fn misused_counts_point_at_their_digits() {
+        for (src, start, end) in [
+            ("+[2-]", 2, 3),
+            ("+[-]3", 4, 5),
+            (",10", 1, 3),
+            ("..7", 2, 3),
+            ("++0", 2, 3),
+            ("+99999999999999999.", 1, 18),
+            ("+65536", 1, 6),
+        ] {
+            assert_eq!(
+                counted(src).unwrap_err(),
+                ParseError::InvalidCount {
+                    span: Span { start, end }
+                },
+                "{src}"
+            );
+        }
+        assert!(counted("+65535").is_ok());
+    }
+}
@@ -0,0 +1,935 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use crate::{Coverage, NameMap, Table};
+
+/// Color of the states and transitions that [`DotOptions::coverage`] marks as uncovered.
+const UNCOVERED_COLOR: &str = "#bbbbbb";
+
+/// Light fills cycled through for clusters.
+const CLUSTER_FILLS: [&str; 6] = [
+    "#eef3ff", "#effaef", "#fff6e5", "#fdeef3", "#ecfafa", "#f4effc",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Cluster {
+    #[default]
+    None,
+    /// One cluster for the accepting states and one for the rest.
+    ByAcceptance,
+    /// One cluster per strongly connected component that contains a cycle.
+    ByScc,
+}
+
+/// Appearance of the generated graph. Unset fields leave Graphviz's defaults alone, and string
+/// values are always emitted as escaped quoted strings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DotStyle {
+    pub node_shape: Option<String>,
+    pub font_name: Option<String>,
+    /// Ignored unless finite and positive.
+    pub font_size: Option<f64>,
+    pub edge_color: Option<String>,
+    pub accepting_fill: Option<String>,
+    /// Outline color for the start state, unless it is dead or grayed out by
+    /// [`DotOptions::coverage`].
+    pub start_color: Option<String>,
+    /// Outline and font color for dead states, which are also drawn dashed. Graying out by
+    /// [`DotOptions::coverage`] takes precedence.
+    pub dead_color: Option<String>,
+}
+
+impl DotStyle {
+    /// A high-contrast look for slides and documents.
+    pub fn presentation() -> Self {
+        Self {
+            node_shape: Some("circle".to_string()),
+            font_name: Some("Helvetica".to_string()),
+            font_size: Some(14.0),
+            edge_color: Some("#333333".to_string()),
+            accepting_fill: Some("#9be3b0".to_string()),
+            start_color: Some("#1f4fd1".to_string()),
+            dead_color: Some("#999999".to_string()),
+        }
+    }
+}
+
+/// How edges reflect the interpreter steps recorded by the build, see [`Table::cost`].
+///
+/// Tables without cost data, such as minimized ones, are drawn as if this were `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EdgeWeighting {
+    #[default]
+    None,
+    /// Thicker and redder edges for more steps, on a log scale. An edge standing for several
+    /// inputs uses the most expensive of them.
+    Cost,
+    /// Like `Cost`, and also adds the step count to the label.
+    CostLabeled,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DotOptions {
+    pub cluster: Cluster,
+    pub style: DotStyle,
+    pub edge_weighting: EdgeWeighting,
+    /// Grays out the states a corpus never visits and the edges none of whose inputs it
+    /// traverses, see [`Table::coverage`].
+    pub coverage: Option<Coverage>,
+    /// Labels the states with these names instead of their ids.
+    pub names: Option<NameMap>,
+    /// Labels the edge to each state's most common target `else`, so that only the other
+    /// inputs are listed. States with a missing transition, or only one target, are drawn as
+    /// usual. Ties go to the smallest target. Edges into states left out by `hide_sink` count
+    /// as missing.
+    pub else_edges: bool,
+    /// Leaves out the rejecting [sink states](Table::sink_states) other than the start state,
+    /// and the edges into them, so that a missing edge means rejection.
+    pub hide_sink: bool,
+    /// Lays the states out left to right in ranks by their distance from the start, so that
+    /// each rank holds the states some shortest input of that length reaches. Unreachable
+    /// states share a final rank.
+    pub layered: bool,
+}
+
+/// Pen width and color for an edge costing `cost` steps, when the most expensive costs `max`.
+fn cost_attributes(cost: u32, max: u32) -> (String, String) {
+    let scale = if max == 0 {
+        0.0
+    } else {
+        f64::from(cost).ln_1p() / f64::from(max).ln_1p()
+    };
+    let red = (0x99 as f64 + scale * (0xdd - 0x99) as f64) as u8;
+    let other = (0x99 as f64 * (1.0 - scale)) as u8;
+    (
+        format!("penwidth={:.2}", 1.0 + 4.0 * scale),
+        format!("\"#{red:02x}{other:02x}{other:02x}\""),
+    )
+}
+
+/// The most frequent value in a sorted slice, the smallest among equally frequent ones.
+fn most_common(sorted: &[usize]) -> Option<usize> {
+    let mut best = None;
+    let mut best_count = 0;
+    let mut start = 0;
+    while start < sorted.len() {
+        let end = start
+            + sorted[start..]
+                .iter()
+                .take_while(|&&x| x == sorted[start])
+                .count();
+        if end - start > best_count {
+            best = Some(sorted[start]);
+            best_count = end - start;
+        }
+        start = end;
+    }
+    best
+}
+
+/// Quotes a value for use as a dot attribute, so that it can't end the attribute early.
+fn quoted(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' | '\r' => output.push(' '),
+            _ => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+impl Table {
+    pub fn dot(&self) -> String {
+        self.dot_with(&DotOptions::default())
+    }
+
+    pub fn dot_with(&self, options: &DotOptions) -> String {
+        let mut output = "digraph G {\n".to_string();
+        let style = &options.style;
+
+        let font_size = style
+            .font_size
+            .filter(|size| size.is_finite() && *size > 0.0);
+        let mut node_defaults = Vec::new();
+        let mut edge_defaults = Vec::new();
+        if let Some(shape) = &style.node_shape {
+            node_defaults.push(format!("shape={}", quoted(shape)));
+        }
+        if let Some(font) = &style.font_name {
+            node_defaults.push(format!("fontname={}", quoted(font)));
+            edge_defaults.push(format!("fontname={}", quoted(font)));
+        }
+        if let Some(size) = font_size {
+            node_defaults.push(format!("fontsize={size}"));
+            edge_defaults.push(format!("fontsize={size}"));
+        }
+        if let Some(color) = &style.edge_color {
+            edge_defaults.push(format!("color={}", quoted(color)));
+        }
+        if !node_defaults.is_empty() {
+            writeln!(&mut output, "    node [{}];", node_defaults.join(", ")).unwrap();
+        }
+        if !edge_defaults.is_empty() {
+            writeln!(&mut output, "    edge [{}];", edge_defaults.join(", ")).unwrap();
+        }
+        let hidden: Vec<bool> = (0..self.state_count())
+            .map(|state| {
+                options.hide_sink && state != 0 && !self.is_accepting(state) && self.is_sink(state)
+            })
+            .collect();
+
+        if options.layered {
+            output.push_str("    rankdir=LR;\n");
+            for rank in self.ranks() {
+                let members: Vec<String> = rank
+                    .iter()
+                    .filter(|&&state| !hidden[state])
+                    .map(|state| format!("{state};"))
+                    .collect();
+                if members.is_empty() {
+                    continue;
+                }
+                writeln!(&mut output, "    {{ rank=same; {} }}", members.join(" ")).unwrap();
+            }
+        }
+
+        // clusters come first so that the edges below don't declare their nodes at the top level
+        let clusters = self
+            .clusters(options.cluster)
+            .into_iter()
+            .filter_map(|members| {
+                let members: Vec<usize> = members
+                    .into_iter()
+                    .filter(|&state| !hidden[state])
+                    .collect();
+                (!members.is_empty()).then_some(members)
+            });
+        for (cluster_id, members) in clusters.enumerate() {
+            writeln!(&mut output, "    subgraph cluster_{cluster_id} {{").unwrap();
+            writeln!(
+                &mut output,
+                "        style=filled; fillcolor=\"{}\"; color=\"#cccccc\";",
+                CLUSTER_FILLS[cluster_id % CLUSTER_FILLS.len()]
+            )
+            .unwrap();
+            for member in members {
+                writeln!(&mut output, "        {member};").unwrap();
+            }
+            writeln!(&mut output, "    }}").unwrap();
+        }
+
+        let max_cost = match options.edge_weighting {
+            EdgeWeighting::None => None,
+            _ => self.cost_stats().map(|stats| stats.max),
+        };
+        for (from, (_, edges)) in self.rows().enumerate() {
+            if hidden[from] {
+                continue;
+            }
+            // edges into hidden states are drawn as missing
+            let edges = edges.map(|to| {
+                if to != Self::MISSING && hidden[to] {
+                    Self::MISSING
+                } else {
+                    to
+                }
+            });
+            // one edge per distinct target, in ascending order
+            let mut targets = edges;
+            targets.sort_unstable();
+            let else_target = if options.else_edges && targets[15] != Self::MISSING {
+                most_common(&targets).filter(|&to| targets[0] != to || targets[15] != to)
+            } else {
+                None
+            };
+            let mut previous = None;
+            for &to in &targets {
+                if to == Self::MISSING || previous == Some(to) {
+                    continue;
+                }
+                previous = Some(to);
+
+                write!(&mut output, "    {from} -> {to} [label=\"").unwrap();
+                let mut input = if else_target == Some(to) {
+                    output.push_str("else");
+                    16
+                } else {
+                    0
+                };
+                while input < 16 {
+                    if edges[input] != to {
+                        input += 1;
+                        continue;
+                    }
+                    let start = input;
+                    while input < 16 && edges[input] == to {
+                        input += 1;
+                    }
+                    if input - start < 4 {
+                        for n in start..input {
+                            write!(&mut output, "{n:X}").unwrap();
+                        }
+                    } else {
+                        write!(&mut output, "{start:X}-{:X}", input - 1).unwrap();
+                    }
+                }
+
+                let mut attributes = Vec::new();
+                // graying out an uncovered edge wins over its cost color
+                let mut color = None;
+                if let Some(max_cost) = max_cost {
+                    let cost = (0..16)
+                        .filter(|&input| edges[input as usize] == to)
+                        .filter_map(|input| self.cost(from, input))
+                        .max()
+                        .unwrap_or(0);
+                    if options.edge_weighting == EdgeWeighting::CostLabeled {
+                        write!(&mut output, "\\n{cost} steps").unwrap();
+                    }
+                    let (pen_width, cost_color) = cost_attributes(cost, max_cost);
+                    attributes.push(pen_width);
+                    color = Some(cost_color);
+                }
+                if let Some(coverage) = &options.coverage {
+                    let covered = (0..16).any(|input| {
+                        edges[input as usize] == to && coverage.is_transition_traversed(from, input)
+                    });
+                    if !covered {
+                        color = Some(quoted(UNCOVERED_COLOR));
+                        attributes.push(format!("fontcolor={}", quoted(UNCOVERED_COLOR)));
+                    }
+                }
+                if let Some(color) = color {
+                    attributes.push(format!("color={color}"));
+                }
+                output.push('"');
+                for attribute in attributes {
+                    write!(&mut output, ", {attribute}").unwrap();
+                }
+                writeln!(&mut output, "];").unwrap();
+            }
+        }
+
+        let co_reachable = if style.dead_color.is_some() {
+            self.co_reachable()
+        } else {
+            Vec::new()
+        };
+        for (id, (accepting, _)) in self.rows().enumerate() {
+            if hidden[id] {
+                continue;
+            }
+            let mut attributes = Vec::new();
+            let mut node_style = Vec::new();
+            // one color each: uncovered wins over dead, which wins over the start color, and the
+            // start color only applies to the outline
+            let mut color = None;
+            let mut font_color = None;
+            if *accepting {
+                attributes.push("peripheries=2".to_string());
+                if let Some(fill) = &style.accepting_fill {
+                    node_style.push("filled");
+                    attributes.push(format!("fillcolor={}", quoted(fill)));
+                }
+            }
+            if let Some(dead_color) = &style.dead_color {
+                if !co_reachable[id] {
+                    node_style.push("dashed");
+                    color = Some(dead_color.as_str());
+                    font_color = color;
+                }
+            }
+            if let Some(coverage) = &options.coverage {
+                if !coverage.is_state_visited(id) {
+                    color = Some(UNCOVERED_COLOR);
+                    font_color = color;
+                }
+            }
+            if let Some(name) = options.names.as_ref().and_then(|names| names.get(id)) {
+                attributes.push(format!("label={}", quoted(name)));
+            }
+            if let Some(start_color) = &style.start_color {
+                if id == 0 {
+                    color = color.or(Some(start_color.as_str()));
+                    attributes.push("penwidth=2".to_string());
+                }
+            }
+            if let Some(color) = color {
+                attributes.push(format!("color={}", quoted(color)));
+            }
+            if let Some(font_color) = font_color {
+                attributes.push(format!("fontcolor={}", quoted(font_color)));
+            }
+            if !node_style.is_empty() {
+                attributes.push(format!("style=\"{}\"", node_style.join(",")));
+            }
+
+            if !attributes.is_empty() {
+                writeln!(&mut output, "    {id}[{}];", attributes.join(", ")).unwrap();
+            }
+        }
+
+        writeln!(&mut output, "}}").unwrap();
+        output
+    }
+
+    /// The states at each distance from the start, in ascending order, followed by the
+    /// unreachable ones if there are any.
+    fn ranks(&self) -> Vec<Vec<usize>> {
+        if self.state_count() == 0 {
+            return Vec::new();
+        }
+        let mut depths = vec![usize::MAX; self.state_count()];
+        let mut queue = VecDeque::from([0]);
+        depths[0] = 0;
+        while let Some(current) = queue.pop_front() {
+            for &to in self.transitions(current) {
+                if to != Self::MISSING && depths[to] == usize::MAX {
+                    depths[to] = depths[current] + 1;
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        let deepest = depths
+            .iter()
+            .filter(|&&depth| depth != usize::MAX)
+            .max()
+            .copied()
+            .unwrap_or(0);
+        let mut ranks = vec![Vec::new(); deepest + 2];
+        for (state, &depth) in depths.iter().enumerate() {
+            ranks[depth.min(deepest + 1)].push(state);
+        }
+        ranks.retain(|rank| !rank.is_empty());
+        ranks
+    }
+
+    /// Groups of states to wrap in clusters, ordered by their smallest member.
+    fn clusters(&self, cluster: Cluster) -> Vec<Vec<usize>> {
+        match cluster {
+            Cluster::None => Vec::new(),
+            Cluster::ByAcceptance => {
+                let (accepting, rejecting): (Vec<usize>, Vec<usize>) =
+                    (0..self.state_count()).partition(|&state| self.is_accepting(state));
+                let mut groups: Vec<Vec<usize>> = [accepting, rejecting]
+                    .into_iter()
+                    .filter(|group| !group.is_empty())
+                    .collect();
+                groups.sort_unstable_by_key(|group| group[0]);
+                groups
+            }
+            Cluster::ByScc => self
+                .sccs()
+                .into_iter()
+                .filter(|component| self.is_cyclic_component(component))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SummaryOptions {
+    /// Components with at most this many states list their members, larger ones only show
+    /// how many there are.
+    pub list_members_up_to: usize,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> Self {
+        Self {
+            list_members_up_to: 3,
+        }
+    }
+}
+
+impl Table {
+    /// The condensation of the graph: one node per strongly connected component, numbered like
+    /// [`Table::sccs`], with an edge wherever some transition crosses between two components.
+    /// Components containing an accepting state are drawn with a double border and the start
+    /// state's component in bold.
+    pub fn dot_summary(&self, options: &SummaryOptions) -> String {
+        let sccs = self.sccs();
+        let mut component_of = vec![0; self.state_count()];
+        for (component, members) in sccs.iter().enumerate() {
+            for &member in members {
+                component_of[member] = component;
+            }
+        }
+
+        let mut output = "digraph G {\n".to_string();
+        for (component, members) in sccs.iter().enumerate() {
+            let label = if members.len() <= options.list_members_up_to {
+                members
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            } else {
+                format!("{} states", members.len())
+            };
+            let mut attributes = vec![format!("label={}", quoted(&label))];
+            if members.iter().any(|&member| self.is_accepting(member)) {
+                attributes.push("peripheries=2".to_string());
+            }
+            if component == component_of[0] {
+                attributes.push("style=bold".to_string());
+            }
+            writeln!(&mut output, "    {component}[{}];", attributes.join(", ")).unwrap();
+        }
+
+        for (component, members) in sccs.iter().enumerate() {
+            let mut targets: Vec<usize> = members
+                .iter()
+                .flat_map(|&member| self.transitions(member))
+                .filter(|&&to| to != Self::MISSING)
+                .map(|&to| component_of[to])
+                .filter(|&target| target != component)
+                .collect();
+            targets.sort_unstable();
+            targets.dedup();
+            for target in targets {
+                writeln!(&mut output, "    {component} -> {target};").unwrap();
+            }
+        }
+
+        writeln!(&mut output, "}}").unwrap();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::{Cluster, DotOptions, DotStyle, EdgeWeighting, SummaryOptions};
+    use crate::{tests::no_zeros, Program, Table};
+
+    #[test]
+    fn plain_output() {
+        assert_eq!(
+            no_zeros().dot(),
+            "digraph G {\n    0 -> 0 [label=\"1-F\"];\n    0 -> 1 [label=\"0\"];\n    \
+             1 -> 1 [label=\"0-F\"];\n    0[peripheries=2];\n}\n"
+        );
+    }
+
+    /// The output of the old quadratic `dot`, which looked for the edges into every state in
+    /// turn.
+    fn quadratic_dot(table: &Table) -> String {
+        let mut output = "digraph G {\n".to_string();
+        for (from, (_, edges)) in table.rows().enumerate() {
+            for maybe_to in 0..table.state_count() {
+                let inputs: Vec<usize> = (0..16).filter(|&i| edges[i] == maybe_to).collect();
+                if inputs.is_empty() {
+                    continue;
+                }
+                let mut label = String::new();
+                let mut i = 0;
+                while i < inputs.len() {
+                    let mut j = i;
+                    while j + 1 < inputs.len() && inputs[j + 1] == inputs[j] + 1 {
+                        j += 1;
+                    }
+                    if j - i < 3 {
+                        for input in &inputs[i..=j] {
+                            label += &format!("{input:X}");
+                        }
+                    } else {
+                        label += &format!("{:X}-{:X}", inputs[i], inputs[j]);
+                    }
+                    i = j + 1;
+                }
+                output += &format!("    {from} -> {maybe_to} [label=\"{label}\"];\n");
+            }
+        }
+        for (id, (accepting, _)) in table.rows().enumerate() {
+            if *accepting {
+                output += &format!("    {id}[peripheries=2];\n");
+            }
+        }
+        output + "}\n"
+    }
+
+    #[test]
+    fn benchmark_output_is_unchanged() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            assert_eq!(table.dot(), quadratic_dot(&table));
+            let minimized = table.minimized();
+            assert_eq!(minimized.dot(), quadratic_dot(&minimized));
+        }
+
+        let even = Table::build(&Program::new("+[>,,.<]", NonZeroUsize::new(2).unwrap()));
+        assert_eq!(
+            even.canonical().dot(),
+            "digraph G {\n    0 -> 1 [label=\"0-F\"];\n    1 -> 2 [label=\"0-F\"];\n    \
+             2 -> 1 [label=\"0-F\"];\n    2[peripheries=2];\n}\n"
+        );
+        let halts = Table::build(&Program::new(",[-[-]]]", NonZeroUsize::new(1).unwrap()));
+        assert_eq!(
+            halts.dot(),
+            "digraph G {\n    0 -> 1 [label=\"0-F\"];\n    1 -> 1 [label=\"0-F\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn hide_sink_drops_the_sink_and_its_edges() {
+        let options = DotOptions {
+            hide_sink: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            no_zeros().dot_with(&options),
+            "digraph G {\n    0 -> 0 [label=\"1-F\"];\n    0[peripheries=2];\n}\n"
+        );
+    }
+
+    #[test]
+    fn hide_sink_keeps_a_rejecting_start() {
+        let table = Table::from_rows(vec![(false, [0; 16])]);
+        let options = DotOptions {
+            hide_sink: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            table.dot_with(&options),
+            "digraph G {\n    0 -> 0 [label=\"0-F\"];\n}\n"
+        );
+    }
+
+    /// Sends 0-D to 1, E to 2 and F back to 0, with 2 a rejecting sink.
+    fn dominant_target() -> Table {
+        let mut start = [1; 16];
+        start[0xE] = 2;
+        start[0xF] = 0;
+        Table::from_rows(vec![(false, start), (true, [0; 16]), (false, [2; 16])])
+    }
+
+    #[test]
+    fn else_edge_goes_to_the_most_common_target() {
+        let options = DotOptions {
+            else_edges: true,
+            ..Default::default()
+        };
+        let output = dominant_target().dot_with(&options);
+        assert_eq!(output.matches("else").count(), 1);
+        assert!(output.contains("    0 -> 0 [label=\"F\"];\n"));
+        assert!(output.contains("    0 -> 1 [label=\"else\"];\n"));
+        assert!(output.contains("    0 -> 2 [label=\"E\"];\n"));
+        // the other states have a single target
+        assert!(output.contains("    1 -> 0 [label=\"0-F\"];\n"));
+        assert!(output.contains("    2 -> 2 [label=\"0-F\"];\n"));
+    }
+
+    #[test]
+    fn else_edges_with_a_hidden_sink_list_every_input() {
+        let options = DotOptions {
+            else_edges: true,
+            hide_sink: true,
+            ..Default::default()
+        };
+        let output = dominant_target().dot_with(&options);
+        assert!(!output.contains("else"));
+        assert!(output.contains("    0 -> 0 [label=\"F\"];\n"));
+        assert!(output.contains("    0 -> 1 [label=\"0-D\"];\n"));
+        assert!(!output.contains("-> 2"));
+    }
+
+    fn colored() -> DotStyle {
+        DotStyle {
+            start_color: Some("blue".to_string()),
+            dead_color: Some("red".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn each_node_has_one_color() {
+        let table = no_zeros();
+        let options = DotOptions {
+            style: colored(),
+            coverage: Some(table.coverage([[1]])),
+            ..Default::default()
+        };
+        let output = table.dot_with(&options);
+        assert!(output.contains("    0[peripheries=2, penwidth=2, color=\"blue\"];\n"));
+        assert!(
+            output.contains("    1[color=\"#bbbbbb\", fontcolor=\"#bbbbbb\", style=\"dashed\"];\n")
+        );
+        assert!(output
+            .contains("    0 -> 1 [label=\"0\", fontcolor=\"#bbbbbb\", color=\"#bbbbbb\"];\n"));
+        for line in output.lines() {
+            assert!(line.matches(" color=").count() + line.matches("[color=").count() <= 1);
+        }
+    }
+
+    #[test]
+    fn dead_start_is_drawn_as_dead() {
+        let table = Table::from_rows(vec![(false, [0; 16])]);
+        let options = DotOptions {
+            style: colored(),
+            ..Default::default()
+        };
+        assert!(table
+            .dot_with(&options)
+            .contains("    0[penwidth=2, color=\"red\", fontcolor=\"red\", style=\"dashed\"];\n"));
+    }
+
+    #[test]
+    fn uncovered_edges_lose_their_cost_color() {
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let table = Table::build(&program);
+        let options = DotOptions {
+            edge_weighting: EdgeWeighting::Cost,
+            coverage: Some(table.coverage([[1]])),
+            ..Default::default()
+        };
+        let output = table.dot_with(&options);
+        assert!(output.contains(", penwidth="));
+        assert!(output.contains(", color=\"#bbbbbb\""));
+        for line in output.lines() {
+            assert!(line.matches(" color=").count() <= 1);
+        }
+    }
+
+    /// Moves the first symbol `x` to the second cell in `6x + 2` steps, a distinct state for
+    /// each, then halts on the next read.
+    fn moving_costs() -> Table {
+        Table::build(&Program::new(",[->+<],", NonZeroUsize::new(2).unwrap()))
+    }
+
+    #[test]
+    fn pen_width_grows_with_cost() {
+        let table = moving_costs();
+        let options = DotOptions {
+            edge_weighting: EdgeWeighting::CostLabeled,
+            ..Default::default()
+        };
+        let output = table.dot_with(&options);
+        assert!(output
+            .contains("    0 -> 1 [label=\"0\\n2 steps\", penwidth=1.97, color=\"#a97373\"];\n"));
+        assert!(output
+            .contains("    0 -> 16 [label=\"F\\n92 steps\", penwidth=5.00, color=\"#dd0000\"];\n"));
+        assert!(output.contains(
+            "    1 -> 17 [label=\"0-F\\n0 steps\", penwidth=1.00, color=\"#999999\"];\n"
+        ));
+
+        let widths: Vec<f64> = (1..=16)
+            .map(|to| {
+                let line = output
+                    .lines()
+                    .find(|line| line.starts_with(&format!("    0 -> {to} ")))
+                    .unwrap();
+                let width = line.split("penwidth=").nth(1).unwrap();
+                width[..width.find(',').unwrap()].parse().unwrap()
+            })
+            .collect();
+        assert!(widths.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn weighting_without_costs_is_a_no_op() {
+        let table = moving_costs().minimized();
+        let options = DotOptions {
+            edge_weighting: EdgeWeighting::CostLabeled,
+            ..Default::default()
+        };
+        assert_eq!(table.dot_with(&options), table.dot());
+    }
+
+    /// 0 and 1 form a cycle, and everything but that cycle ends in the sink 2.
+    fn two_sccs() -> Table {
+        let mut start = [2; 16];
+        start[0] = 1;
+        Table::from_rows(vec![(false, start), (true, [0; 16]), (false, [2; 16])])
+    }
+
+    const TWO_SCCS_EDGES: &str = "    0 -> 1 [label=\"0\"];\n    0 -> 2 [label=\"1-F\"];\n    \
+                                  1 -> 0 [label=\"0-F\"];\n    2 -> 2 [label=\"0-F\"];\n    \
+                                  1[peripheries=2];\n}\n";
+
+    #[test]
+    fn clusters_by_scc() {
+        let options = DotOptions {
+            cluster: Cluster::ByScc,
+            ..Default::default()
+        };
+        let expected = "digraph G {\n    subgraph cluster_0 {\n        \
+                        style=filled; fillcolor=\"#eef3ff\"; color=\"#cccccc\";\n        \
+                        0;\n        1;\n    }\n    subgraph cluster_1 {\n        \
+                        style=filled; fillcolor=\"#effaef\"; color=\"#cccccc\";\n        \
+                        2;\n    }\n"
+            .to_string()
+            + TWO_SCCS_EDGES;
+        assert_eq!(two_sccs().dot_with(&options), expected);
+    }
+
+    #[test]
+    fn clusters_by_acceptance() {
+        let options = DotOptions {
+            cluster: Cluster::ByAcceptance,
+            ..Default::default()
+        };
+        let expected = "digraph G {\n    subgraph cluster_0 {\n        \
+                        style=filled; fillcolor=\"#eef3ff\"; color=\"#cccccc\";\n        \
+                        0;\n        2;\n    }\n    subgraph cluster_1 {\n        \
+                        style=filled; fillcolor=\"#effaef\"; color=\"#cccccc\";\n        \
+                        1;\n    }\n"
+            .to_string()
+            + TWO_SCCS_EDGES;
+        assert_eq!(two_sccs().dot_with(&options), expected);
+    }
+
+    #[test]
+    fn acyclic_components_get_no_cluster() {
+        // 0 only has edges into the sink, so it is a component without a cycle
+        let table = Table::from_rows(vec![(true, [1; 16]), (false, [1; 16])]);
+        let options = DotOptions {
+            cluster: Cluster::ByScc,
+            ..Default::default()
+        };
+        let output = table.dot_with(&options);
+        assert_eq!(output.matches("subgraph").count(), 1);
+        assert!(output.contains("subgraph cluster_0 {\n        style=filled; fillcolor=\"#eef3ff\"; color=\"#cccccc\";\n        1;\n    }\n"));
+    }
+
+    #[test]
+    fn default_style_is_plain() {
+        let options = DotOptions {
+            style: DotStyle::default(),
+            ..Default::default()
+        };
+        assert_eq!(no_zeros().dot_with(&options), no_zeros().dot());
+    }
+
+    #[test]
+    fn presentation_style() {
+        let options = DotOptions {
+            style: DotStyle::presentation(),
+            ..Default::default()
+        };
+        assert_eq!(
+            no_zeros().dot_with(&options),
+            "digraph G {\n    \
+             node [shape=\"circle\", fontname=\"Helvetica\", fontsize=14];\n    \
+             edge [fontname=\"Helvetica\", fontsize=14, color=\"#333333\"];\n    \
+             0 -> 0 [label=\"1-F\"];\n    0 -> 1 [label=\"0\"];\n    \
+             1 -> 1 [label=\"0-F\"];\n    \
+             0[peripheries=2, fillcolor=\"#9be3b0\", penwidth=2, color=\"#1f4fd1\", \
+             style=\"filled\"];\n    \
+             1[color=\"#999999\", fontcolor=\"#999999\", style=\"dashed\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn style_values_stay_inside_their_attribute() {
+        let options = DotOptions {
+            style: DotStyle {
+                node_shape: Some("box\"]; evil [label=\"\\".to_string()),
+                font_size: Some(f64::NAN),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let output = no_zeros().dot_with(&options);
+        assert!(output.contains("    node [shape=\"box\\\"]; evil [label=\\\"\\\\\"];\n"));
+        assert!(!output.contains("fontsize"));
+    }
+
+    #[test]
+    fn summary_of_two_sccs() {
+        assert_eq!(
+            two_sccs().dot_summary(&SummaryOptions::default()),
+            "digraph G {\n    0[label=\"0 1\", peripheries=2, style=bold];\n    \
+             1[label=\"2\"];\n    0 -> 1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn summary_has_one_node_per_component() {
+        // ten rings of a hundred states, where 0 moves around a ring and 1 on to the next
+        let rows = (0..1000)
+            .map(|state| {
+                let ring = state / 100;
+                let mut edges = [state; 16];
+                edges[0] = ring * 100 + (state + 1) % 100;
+                edges[1] = if ring < 9 { (ring + 1) * 100 } else { state };
+                (ring == 9, edges)
+            })
+            .collect();
+        let output = Table::from_rows(rows).dot_summary(&SummaryOptions::default());
+
+        let nodes: Vec<&str> = output
+            .lines()
+            .filter(|line| line.contains("[label="))
+            .collect();
+        assert_eq!(nodes.len(), 10);
+        assert!(nodes
+            .iter()
+            .all(|node| node.contains("label=\"100 states\"")));
+        assert_eq!(output.matches("peripheries=2").count(), 1);
+        assert!(output.contains("    9[label=\"100 states\", peripheries=2];\n"));
+        let edges: Vec<&str> = output
+            .lines()
+            .filter(|line| line.contains(" -> "))
+            .collect();
+        let expected: Vec<String> = (0..9)
+            .map(|ring| format!("    {ring} -> {};", ring + 1))
+            .collect();
+        assert_eq!(edges, expected);
+    }
+
+    /// A start branching into two states that join again, on to an accepting loop, and an
+    /// unreachable state leading back to the start.
+    fn chain_with_branches() -> Table {
+        let mut start = [2; 16];
+        start[0] = 1;
+        Table::from_rows(vec![
+            (false, start),
+            (false, [3; 16]),
+            (false, [3; 16]),
+            (false, [4; 16]),
+            (true, [4; 16]),
+            (false, [0; 16]),
+        ])
+    }
+
+    #[test]
+    fn layered_ranks() {
+        let table = chain_with_branches();
+        let layered = table.dot_with(&DotOptions {
+            layered: true,
+            ..Default::default()
+        });
+        assert!(layered.starts_with(
+            "digraph G {\n    rankdir=LR;\n    { rank=same; 0; }\n    { rank=same; 1; 2; }\n    \
+             { rank=same; 3; }\n    { rank=same; 4; }\n    { rank=same; 5; }\n    0 -> 1 "
+        ));
+        // otherwise the same as the plain output
+        let plain: String = layered
+            .lines()
+            .filter(|line| !line.contains("rank"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        assert_eq!(plain, table.dot());
+    }
+
+    #[test]
+    fn layered_without_the_sink() {
+        let layered = no_zeros().dot_with(&DotOptions {
+            layered: true,
+            hide_sink: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            layered,
+            "digraph G {\n    rankdir=LR;\n    { rank=same; 0; }\n    \
+             0 -> 0 [label=\"1-F\"];\n    0[peripheries=2];\n}\n"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{import::swap_start, Row, Table, TableDefect};
+
+/// Builds or patches a table one state and transition at a time. Nothing is checked until
+/// [`TableEditor::finish`], so a half-edited table never escapes.
+#[derive(Clone, Debug, Default)]
+pub struct TableEditor {
+    rows: Vec<Row>,
+    start: usize,
+}
+
+impl TableEditor {
+    /// An editor with no states, starting at state 0 once there is one.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_table(table: &Table) -> Self {
+        Self {
+            rows: table.rows().copied().collect(),
+            start: 0,
+        }
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Adds a state without transitions and returns its id.
+    pub fn add_state(&mut self, accepting: bool) -> usize {
+        self.rows.push((accepting, [Table::MISSING; 16]));
+        self.rows.len() - 1
+    }
+
+    /// Only the low four bits of `input` are used. `to` is checked by [`TableEditor::finish`].
+    ///
+    /// # Panics
+    ///
+    /// If `from` isn't a state.
+    pub fn set_transition(&mut self, from: usize, input: u8, to: usize) {
+        self.rows[from].1[(input & 0x0F) as usize] = to;
+    }
+
+    /// # Panics
+    ///
+    /// If `from` isn't a state.
+    pub fn remove_transition(&mut self, from: usize, input: u8) {
+        self.set_transition(from, input, Table::MISSING);
+    }
+
+    /// # Panics
+    ///
+    /// If `state` isn't a state.
+    pub fn set_accepting(&mut self, state: usize, accepting: bool) {
+        self.rows[state].0 = accepting;
+    }
+
+    /// Checked by [`TableEditor::finish`].
+    pub fn set_start(&mut self, state: usize) {
+        self.start = state;
+    }
+
+    /// The edited table, with the start state moved to 0 as in [`Table::from_json`]. Missing
+    /// transitions are kept, see [`Table::make_complete`].
+    pub fn finish(self) -> Result<Table, Vec<TableDefect>> {
+        let Self { mut rows, start } = self;
+        if rows.is_empty() {
+            return Err(vec![TableDefect::NoStates]);
+        }
+        if start >= rows.len() {
+            return Err(vec![TableDefect::StartOutOfRange { start }]);
+        }
+        let table = Table::from_rows(rows.clone());
+        table.validate()?;
+
+        swap_start(&mut rows, start);
+        Ok(Table::from_rows(rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::exactly, Table, TableDefect};
+
+    use super::TableEditor;
+
+    #[test]
+    fn building_a_word() {
+        let mut editor = TableEditor::new();
+        let states: Vec<usize> = [false, false, true, false]
+            .into_iter()
+            .map(|accepting| editor.add_state(accepting))
+            .collect();
+        assert_eq!(states, [0, 1, 2, 3]);
+        for state in states {
+            for input in 0..16 {
+                editor.set_transition(state, input, 3);
+            }
+        }
+        editor.set_transition(0, 0x11, 1);
+        editor.set_transition(1, 2, 2);
+        assert_eq!(editor.state_count(), 4);
+        assert!(editor.finish().unwrap().rows().eq(exactly(&[1, 2]).rows()));
+    }
+
+    #[test]
+    fn patching_a_table() {
+        let table = exactly(&[1]);
+        assert!(TableEditor::from_table(&table)
+            .finish()
+            .unwrap()
+            .rows()
+            .eq(table.rows()));
+
+        let mut editor = TableEditor::from_table(&table);
+        editor.remove_transition(0, 1);
+        editor.set_accepting(0, true);
+        let patched = editor.finish().unwrap();
+        assert!(!patched.is_complete());
+        assert!(patched.accepts(&[]));
+        assert!(!patched.accepts(&[1]));
+    }
+
+    #[test]
+    fn moving_the_start() {
+        // 0 -1-> 1 -2-> 2, starting from 1
+        let mut editor = TableEditor::from_table(&exactly(&[1, 2]));
+        editor.set_start(1);
+        let moved = editor.finish().unwrap();
+        assert!(moved.accepts(&[2]));
+        assert!(!moved.accepts(&[1, 2]));
+        assert_eq!(moved.transition(1, 1), 0);
+        assert_eq!(moved.transition(0, 2), 2);
+    }
+
+    #[test]
+    fn defects_are_found_when_finishing() {
+        assert_eq!(
+            TableEditor::new().finish().unwrap_err(),
+            [TableDefect::NoStates]
+        );
+
+        let mut editor = TableEditor::new();
+        let state = editor.add_state(true);
+        editor.set_start(1);
+        assert_eq!(
+            editor.clone().finish().unwrap_err(),
+            [TableDefect::StartOutOfRange { start: 1 }]
+        );
+
+        editor.set_start(state);
+        editor.set_transition(state, 4, 7);
+        editor.set_transition(state, 9, Table::MISSING);
+        assert_eq!(
+            editor.finish().unwrap_err(),
+            [TableDefect::TargetOutOfRange {
+                state: 0,
+                input: 4,
+                target: 7
+            }]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn transitions_need_a_state() {
+        TableEditor::new().set_transition(0, 0, 0);
+    }
+}
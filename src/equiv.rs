@@ -0,0 +1,118 @@
+use crate::{BuildError, BuildOptions, Program, Table};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    A,
+    B,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EquivResult {
+    /// Both languages are equal; the state counts are those of the minimized tables.
+    Equivalent { states_a: usize, states_b: usize },
+    /// `witness` is a shortest string accepted by exactly one side, namely `accepted_by`.
+    Different { witness: Vec<u8>, accepted_by: Side },
+}
+
+impl Program {
+    /// Builds and minimizes both programs without limits and compares their languages.
+    pub fn equivalent(a: &Program, b: &Program) -> Result<EquivResult, BuildError> {
+        Self::equivalent_with(a, b, &BuildOptions::default())
+    }
+
+    pub fn equivalent_with(
+        a: &Program,
+        b: &Program,
+        options: &BuildOptions,
+    ) -> Result<EquivResult, BuildError> {
+        let (mut table_a, _) = Table::build_with(a, options)?;
+        let (mut table_b, _) = Table::build_with(b, options)?;
+        table_a.minimize();
+        table_b.minimize();
+        Ok(table_a.compare(&table_b))
+    }
+}
+
+impl Table {
+    /// Compares the languages of two tables, finding a shortest witness when they differ.
+    pub fn compare(&self, other: &Table) -> EquivResult {
+        let side_of = |witness: Vec<u8>| {
+            let accepted_by = if self.accepts(&witness) {
+                Side::A
+            } else {
+                Side::B
+            };
+            EquivResult::Different {
+                witness,
+                accepted_by,
+            }
+        };
+
+        // an empty language against a nonempty one needs no product
+        match (self.shortest_accepted(), other.shortest_accepted()) {
+            (None, None) => {
+                return EquivResult::Equivalent {
+                    states_a: self.state_count(),
+                    states_b: other.state_count(),
+                }
+            }
+            (Some(witness), None) | (None, Some(witness)) => return side_of(witness),
+            (Some(_), Some(_)) => {}
+        }
+
+        match self.symmetric_difference(other).shortest_accepted() {
+            Some(witness) => side_of(witness),
+            None => EquivResult::Equivalent {
+                states_a: self.state_count(),
+                states_b: other.state_count(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::{EquivResult, Side};
+    use crate::Program;
+
+    fn program(text: &str, cells: usize) -> Program {
+        Program::new(text, NonZeroUsize::new(cells).unwrap())
+    }
+
+    #[test]
+    fn equivalent_with_different_cell_counts() {
+        // both accept the inputs without a 0
+        assert_eq!(
+            Program::equivalent(&program("+[.,]", 1), &program(">+[>.,[>]<<]", 3)),
+            Ok(EquivResult::Equivalent {
+                states_a: 2,
+                states_b: 2
+            })
+        );
+    }
+
+    #[test]
+    fn inequivalent_with_a_witness() {
+        // the second also rejects inputs with an F
+        assert_eq!(
+            Program::equivalent(&program("+[.,]", 1), &program(">+[>.,+[-[>]]<<]", 3)),
+            Ok(EquivResult::Different {
+                witness: vec![0xF],
+                accepted_by: Side::A
+            })
+        );
+    }
+
+    #[test]
+    fn empty_against_nonempty() {
+        assert_eq!(
+            Program::equivalent(&program(",", 1), &program(".", 1)),
+            Ok(EquivResult::Different {
+                witness: vec![],
+                accepted_by: Side::B
+            })
+        );
+    }
+}
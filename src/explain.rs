@@ -0,0 +1,261 @@
+use std::fmt::Write;
+
+use crate::{NameMap, Table};
+
+/// How a string fared, see [`Table::explain`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Accepted,
+    /// Rejected, but some continuation would be accepted; `completion` is a shortest one,
+    /// the lexicographically smallest among those.
+    Incomplete {
+        completion: Vec<u8>,
+    },
+    /// Rejected with no way back, having entered a state that can never accept, or taken a
+    /// missing transition, after reading `at` symbols.
+    Dead {
+        at: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    /// The state after each prefix, starting with the start state for the empty one. Ends
+    /// early at a missing transition.
+    pub states: Vec<usize>,
+    /// Length of the longest prefix after which acceptance was still possible, or `None`
+    /// if the language is empty.
+    pub viable_prefix: Option<usize>,
+    pub verdict: Verdict,
+}
+
+impl Table {
+    /// Follows `input` and reports where it stopped being acceptable. Only the low four bits
+    /// of each symbol are used, as in [`Table::accepts`].
+    pub fn explain(&self, input: &[u8]) -> Explanation {
+        let co_reachable = self.co_reachable();
+        let mut states = vec![0];
+        let mut viable_prefix = None;
+        for (position, &symbol) in input.iter().enumerate() {
+            let state = states[position];
+            if !co_reachable[state] {
+                break;
+            }
+            viable_prefix = Some(position);
+            let to = self.transition(state, symbol & 0x0F);
+            if to == Table::MISSING {
+                break;
+            }
+            states.push(to);
+        }
+        let last = *states.last().unwrap();
+        if states.len() == input.len() + 1 && co_reachable[last] {
+            viable_prefix = Some(input.len());
+        }
+
+        let verdict = if viable_prefix != Some(input.len()) {
+            Verdict::Dead {
+                at: viable_prefix.map_or(0, |viable| viable + 1),
+            }
+        } else if self.is_accepting(last) {
+            Verdict::Accepted
+        } else {
+            Verdict::Incomplete {
+                completion: self
+                    .shortest_path(last, |state| self.is_accepting(state))
+                    .expect("co-reachable states can reach acceptance"),
+            }
+        };
+        Explanation {
+            states,
+            viable_prefix,
+            verdict,
+        }
+    }
+}
+
+impl Table {
+    /// The state after each prefix of `input`, starting with the start state, so one more
+    /// than there are symbols. Symbols are used as in [`Table::accepts`]. Every state after
+    /// a missing transition is [`Table::MISSING`].
+    pub fn trace(&self, input: &[u8]) -> Vec<usize> {
+        let mut states = Vec::with_capacity(input.len() + 1);
+        let mut state = 0;
+        states.push(state);
+        for &symbol in input {
+            if state != Table::MISSING {
+                state = self.transition(state, symbol);
+            }
+            states.push(state);
+        }
+        states
+    }
+
+    /// Like [`Table::trace`], with whether each state is accepting. The last flag is the
+    /// verdict of [`Table::accepts`].
+    pub fn trace_accepting(&self, input: &[u8]) -> Vec<(usize, bool)> {
+        self.trace(input)
+            .into_iter()
+            .map(|state| (state, state != Table::MISSING && self.is_accepting(state)))
+            .collect()
+    }
+
+    /// The trace one step per line, `start: <state>` and then `<symbol> -> <state>`, marking
+    /// accepting states. States are shown by name from `names`, or by access string if it's
+    /// `None`.
+    pub fn trace_pretty(&self, input: &[u8], names: Option<&NameMap>) -> String {
+        let access_strings;
+        let names = match names {
+            Some(names) => names,
+            None => {
+                access_strings = NameMap::access_strings(self);
+                &access_strings
+            }
+        };
+        let name = |state: usize| match names.get(state) {
+            Some(name) => name.to_string(),
+            None => state.to_string(),
+        };
+
+        let mut output = String::new();
+        for (step, (state, accepting)) in self.trace_accepting(input).into_iter().enumerate() {
+            if step == 0 {
+                output.push_str("start: ");
+            } else {
+                write!(&mut output, "{:X} -> ", input[step - 1] & 0x0F).unwrap();
+            }
+            if state == Table::MISSING {
+                output.push_str("missing");
+            } else {
+                output.push_str(&name(state));
+            }
+            if accepting {
+                output.push_str(" (accepting)");
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{exactly, short_inputs},
+        NameMap, Table,
+    };
+
+    use super::{Explanation, Verdict};
+
+    fn explanation(
+        states: &[usize],
+        viable_prefix: Option<usize>,
+        verdict: Verdict,
+    ) -> Explanation {
+        Explanation {
+            states: states.to_vec(),
+            viable_prefix,
+            verdict,
+        }
+    }
+
+    #[test]
+    fn verdicts_on_a_word() {
+        let table = exactly(&[1, 2]);
+        assert_eq!(
+            table.explain(&[1, 2]),
+            explanation(&[0, 1, 2], Some(2), Verdict::Accepted)
+        );
+        assert_eq!(table.explain(&[0x11, 0x12]), table.explain(&[1, 2]));
+        assert_eq!(
+            table.explain(&[1]),
+            explanation(
+                &[0, 1],
+                Some(1),
+                Verdict::Incomplete {
+                    completion: vec![2]
+                }
+            )
+        );
+        assert_eq!(
+            table.explain(&[]),
+            explanation(
+                &[0],
+                Some(0),
+                Verdict::Incomplete {
+                    completion: vec![1, 2]
+                }
+            )
+        );
+        // the 3 leads to the sink, after which nothing more is read
+        assert_eq!(
+            table.explain(&[1, 3, 5]),
+            explanation(&[0, 1, 3], Some(1), Verdict::Dead { at: 2 })
+        );
+        assert_eq!(
+            table.explain(&[1, 2, 0]),
+            explanation(&[0, 1, 2, 3], Some(2), Verdict::Dead { at: 3 })
+        );
+    }
+
+    #[test]
+    fn missing_transitions_and_empty_languages() {
+        let partial = exactly(&[1, 2]).to_partial();
+        assert_eq!(
+            partial.explain(&[5, 1]),
+            explanation(&[0], Some(0), Verdict::Dead { at: 1 })
+        );
+
+        let empty = Table::from_rows(vec![(false, [0; 16])]);
+        assert_eq!(
+            empty.explain(&[]),
+            explanation(&[0], None, Verdict::Dead { at: 0 })
+        );
+        assert_eq!(
+            empty.explain(&[3]),
+            explanation(&[0], None, Verdict::Dead { at: 0 })
+        );
+    }
+
+    #[test]
+    fn traced_paths() {
+        let table = exactly(&[1, 2]);
+        assert_eq!(table.trace(&[1, 2, 0]), [0, 1, 2, 3]);
+        assert_eq!(
+            table.trace_accepting(&[1, 2]),
+            [(0, false), (1, false), (2, true)]
+        );
+
+        // missing transitions stay missing
+        let partial = table.to_partial();
+        assert_eq!(partial.trace(&[5, 1]), [0, Table::MISSING, Table::MISSING]);
+        assert_eq!(
+            partial.trace_accepting(&[5]),
+            [(0, false), (Table::MISSING, false)]
+        );
+
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program).minimized();
+            for input in short_inputs(2) {
+                let trace = table.trace_accepting(&input);
+                assert_eq!(trace.len(), input.len() + 1);
+                assert_eq!(trace.last().unwrap().1, table.accepts(&input));
+            }
+        }
+    }
+
+    #[test]
+    fn pretty_traces() {
+        let table = exactly(&[1, 2]);
+        assert_eq!(
+            table.trace_pretty(&[1, 0x12], None),
+            "start: ε\n1 -> 1\n2 -> 12 (accepting)\n"
+        );
+        assert_eq!(
+            table.to_partial().trace_pretty(&[5, 1], None),
+            "start: ε\n5 -> missing\n1 -> missing\n"
+        );
+        let names = NameMap::new(vec!["s".to_string()]);
+        assert_eq!(table.trace_pretty(&[1], Some(&names)), "start: s\n1 -> 1\n");
+    }
+}
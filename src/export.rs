@@ -0,0 +1,249 @@
+use std::fmt::Write;
+
+use crate::{json, NameMap, Table};
+
+const HTML_TEMPLATE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>bfa automaton</title>
+<style>
+body { font-family: sans-serif; margin: 1em; }
+svg { width: 100%; max-height: 85vh; }
+.node circle { fill: #fff; stroke: #333; stroke-width: 1.5; }
+.node.start circle.outer { stroke: #1f4fd1; stroke-width: 3; }
+.node.current circle { fill: #ffe9a8; }
+.edge path { fill: none; stroke: #777; stroke-width: 1.2; }
+.edge text { font-size: 11px; fill: #555; }
+.edge.current path { stroke: #d97706; stroke-width: 3; }
+text { text-anchor: middle; dominant-baseline: middle; }
+#verdict { margin-left: 1em; font-weight: bold; }
+</style>
+</head>
+<body>
+<label>Input (hex digits): <input id="input" autocomplete="off" spellcheck="false"></label>
+<span id="verdict"></span>
+<svg id="graph" xmlns="http://www.w3.org/2000/svg">
+<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="7" markerHeight="7" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="#777"/></marker></defs>
+<g id="edges"></g><g id="nodes"></g>
+</svg>
+<script>
+const table = __TABLE_JSON__;
+const ns = "http://www.w3.org/2000/svg";
+const count = table.states.length;
+const radius = Math.max(120, count * 20);
+const size = 2 * radius + 140;
+const graph = document.getElementById("graph");
+graph.setAttribute("viewBox", `0 0 ${size} ${size}`);
+const position = table.states.map((_, i) => {
+  const angle = 2 * Math.PI * i / count - Math.PI / 2;
+  return [size / 2 + radius * Math.cos(angle), size / 2 + radius * Math.sin(angle)];
+});
+
+function element(name, attributes, parent) {
+  const e = document.createElementNS(ns, name);
+  for (const key in attributes) e.setAttribute(key, attributes[key]);
+  parent.appendChild(e);
+  return e;
+}
+
+function label(inputs) {
+  let text = "";
+  for (let i = 0; i < inputs.length;) {
+    let j = i;
+    while (j + 1 < inputs.length && inputs[j + 1] === inputs[j] + 1) j++;
+    if (j - i < 3) {
+      for (let k = i; k <= j; k++) text += inputs[k].toString(16).toUpperCase();
+    } else {
+      text += inputs[i].toString(16).toUpperCase() + "-" + inputs[j].toString(16).toUpperCase();
+    }
+    i = j + 1;
+  }
+  return text;
+}
+
+const edges = new Map();
+const edgeLayer = document.getElementById("edges");
+table.states.forEach((state, from) => {
+  const groups = new Map();
+  state.transitions.forEach((to, input) => {
+    if (to === null) return;
+    if (!groups.has(to)) groups.set(to, []);
+    groups.get(to).push(input);
+  });
+  for (const [to, inputs] of groups) {
+    const g = element("g", { class: "edge" }, edgeLayer);
+    const [x1, y1] = position[from];
+    let d, lx, ly;
+    if (to === from) {
+      d = `M ${x1 - 8} ${y1 - 19} C ${x1 - 35} ${y1 - 70}, ${x1 + 35} ${y1 - 70}, ${x1 + 8} ${y1 - 19}`;
+      lx = x1;
+      ly = y1 - 62;
+    } else {
+      const [x2, y2] = position[to];
+      const length = Math.hypot(x2 - x1, y2 - y1);
+      const [ux, uy] = [(x2 - x1) / length, (y2 - y1) / length];
+      const [mx, my] = [(x1 + x2) / 2 - uy * 25, (y1 + y2) / 2 + ux * 25];
+      d = `M ${x1 + ux * 20} ${y1 + uy * 20} Q ${mx} ${my} ${x2 - ux * 22} ${y2 - uy * 22}`;
+      lx = mx;
+      ly = my;
+    }
+    element("path", { d, "marker-end": "url(#arrow)" }, g);
+    element("text", { x: lx, y: ly }, g).textContent = label(inputs);
+    edges.set(from + "," + to, g);
+  }
+});
+
+const nodes = [];
+const nodeLayer = document.getElementById("nodes");
+table.states.forEach((state, id) => {
+  const [x, y] = position[id];
+  const g = element("g", { class: "node" + (id === table.start ? " start" : "") }, nodeLayer);
+  element("circle", { class: "outer", cx: x, cy: y, r: 18 }, g);
+  if (state.accepting) element("circle", { cx: x, cy: y, r: 14 }, g);
+  element("text", { x, y }, g).textContent = id;
+  nodes.push(g);
+});
+
+let timers = [];
+function highlight(path) {
+  timers.forEach(clearTimeout);
+  timers = [];
+  document.querySelectorAll(".current").forEach(e => e.classList.remove("current"));
+  path.forEach((state, step) => {
+    timers.push(setTimeout(() => {
+      document.querySelectorAll(".current").forEach(e => e.classList.remove("current"));
+      nodes[state].classList.add("current");
+      if (step > 0) edges.get(path[step - 1] + "," + state).classList.add("current");
+    }, step * 400));
+  });
+}
+
+document.getElementById("input").addEventListener("input", event => {
+  const verdict = document.getElementById("verdict");
+  const text = event.target.value.replace(/\s/g, "");
+  if (!/^[0-9a-fA-F]*$/.test(text)) {
+    verdict.textContent = "not a hex string";
+    return;
+  }
+  const path = [table.start];
+  let accepted = true;
+  for (const c of text) {
+    const next = table.states[path[path.length - 1]].transitions[parseInt(c, 16)];
+    if (next === null) {
+      accepted = false;
+      break;
+    }
+    path.push(next);
+  }
+  accepted = accepted && table.states[path[path.length - 1]].accepting;
+  verdict.textContent = accepted ? "accepted" : "rejected";
+  highlight(path);
+});
+highlight([table.start]);
+</script>
+</body>
+</html>
+"##;
+
+#[derive(Clone, Debug, Default)]
+pub struct JsonOptions {
+    /// Adds a `name` to each state, which [`Table::from_json`] ignores.
+    pub names: Option<NameMap>,
+}
+
+impl Table {
+    /// Describes the table as `{"start": 0, "states": [{"accepting": bool, "transitions": [..]}]}`
+    /// with 16 transitions per state, using `null` for missing transitions.
+    pub fn to_json(&self) -> String {
+        self.to_json_with(&JsonOptions::default())
+    }
+
+    pub fn to_json_with(&self, options: &JsonOptions) -> String {
+        let mut output = "{\"start\":0,\"states\":[".to_string();
+        for (id, (accepting, edges)) in self.rows().enumerate() {
+            if id > 0 {
+                output.push(',');
+            }
+            output.push('{');
+            if let Some(name) = options.names.as_ref().and_then(|names| names.get(id)) {
+                write!(&mut output, "\"name\":{},", json::quoted(name)).unwrap();
+            }
+            write!(&mut output, "\"accepting\":{accepting},\"transitions\":[").unwrap();
+            for (input, &to) in edges.iter().enumerate() {
+                if input > 0 {
+                    output.push(',');
+                }
+                if to == Self::MISSING {
+                    output.push_str("null");
+                } else {
+                    write!(&mut output, "{to}").unwrap();
+                }
+            }
+            output.push_str("]}");
+        }
+        output.push_str("]}");
+        output
+    }
+
+    /// A self-contained page that draws the automaton and animates the path taken by a typed
+    /// hex string. Nothing is fetched over the network.
+    ///
+    /// The page is about 5 KB plus the [`Table::to_json`] data (roughly 60 bytes per state).
+    /// States are laid out on a circle, which stays readable up to a few dozen states, so
+    /// minimize first.
+    pub fn to_html(&self) -> String {
+        HTML_TEMPLATE.replace("__TABLE_JSON__", &self.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Table;
+
+    #[test]
+    fn html_embeds_the_table() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program).minimized();
+            let html = table.to_html();
+            let embedded = html
+                .split_once("const table = ")
+                .and_then(|(_, rest)| rest.split_once(";\n"))
+                .map(|(json, _)| json)
+                .unwrap();
+            assert_eq!(embedded, table.to_json());
+            let reimported = Table::from_json(embedded).unwrap();
+            assert!(reimported.rows().eq(table.rows()));
+        }
+    }
+
+    #[test]
+    fn html_is_well_formed() {
+        let html = Table::build(&crate::tests::benchmarks().next().unwrap()).to_html();
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert!(!html.contains("__TABLE_JSON__"));
+        for tag in ["html", "head", "title", "style", "body", "svg", "script"] {
+            assert_eq!(
+                html.matches(&format!("<{tag}")).count(),
+                html.matches(&format!("</{tag}>")).count(),
+                "{tag}"
+            );
+        }
+        // nothing is loaded from elsewhere
+        assert!(!html.contains("src="));
+        assert!(!html.contains("href="));
+    }
+
+    #[test]
+    fn json_round_trips_partial_tables() {
+        let mut edges = [Table::MISSING; 16];
+        edges[3] = 0;
+        let table = Table::from_rows(vec![(true, edges)]);
+        let json = table.to_json();
+        assert!(json.starts_with(
+            "{\"start\":0,\"states\":[{\"accepting\":true,\"transitions\":[null,null,null,0,null"
+        ));
+        assert!(Table::from_json(&json).unwrap().rows().eq(table.rows()));
+    }
+}
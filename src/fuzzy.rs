@@ -0,0 +1,140 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::Table;
+
+impl Table {
+    /// The smallest number of symbol substitutions, insertions and deletions turning `input`
+    /// into an accepted string, if it is at most `k`.
+    ///
+    /// This walks the product of the table with the Levenshtein automaton of `input` without
+    /// building it: a node is a state and how much of `input` has been consumed, and the search
+    /// only visits nodes within distance `k`.
+    pub fn accepts_within(&self, input: &[u8], k: usize) -> Option<usize> {
+        let mut distances = HashMap::with_hasher(FxBuildHasher);
+        // 0-1 breadth-first search: free moves go to the front, edits to the back
+        let mut queue = VecDeque::new();
+        distances.insert((0, 0), 0);
+        queue.push_back((0, 0, 0));
+
+        while let Some((state, consumed, distance)) = queue.pop_front() {
+            if distances[&(state, consumed)] < distance {
+                continue;
+            }
+            if consumed == input.len() && self.is_accepting(state) {
+                return Some(distance);
+            }
+
+            let mut relax = |node: (usize, usize), cost: usize| {
+                let distance = distance + cost;
+                if node.0 == Table::MISSING || distance > k {
+                    return;
+                }
+                match distances.entry(node) {
+                    Entry::Occupied(mut slot) if *slot.get() > distance => {
+                        slot.insert(distance);
+                    }
+                    Entry::Occupied(_) => return,
+                    Entry::Vacant(slot) => {
+                        slot.insert(distance);
+                    }
+                }
+                if cost == 0 {
+                    queue.push_front((node.0, node.1, distance));
+                } else {
+                    queue.push_back((node.0, node.1, distance));
+                }
+            };
+
+            if let Some(&symbol) = input.get(consumed) {
+                for replacement in 0..16 {
+                    // keeping the symbol is free, anything else is a substitution
+                    let cost = usize::from(replacement != symbol & 0x0F);
+                    relax((self.transition(state, replacement), consumed + 1), cost);
+                }
+                relax((state, consumed + 1), 1);
+            }
+            for inserted in 0..16 {
+                relax((self.transition(state, inserted), consumed), 1);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        tests::{exactly, short_inputs},
+        Table,
+    };
+
+    /// Every string one substitution, insertion or deletion away from `word`.
+    fn edits(word: &[u8]) -> Vec<Vec<u8>> {
+        let mut edits = Vec::new();
+        for position in 0..=word.len() {
+            for symbol in 0..16 {
+                let mut inserted = word.to_vec();
+                inserted.insert(position, symbol);
+                edits.push(inserted);
+                if position < word.len() {
+                    let mut substituted = word.to_vec();
+                    substituted[position] = symbol;
+                    edits.push(substituted);
+                }
+            }
+            if position < word.len() {
+                let mut deleted = word.to_vec();
+                deleted.remove(position);
+                edits.push(deleted);
+            }
+        }
+        edits
+    }
+
+    fn brute_force(table: &Table, input: &[u8], k: usize) -> Option<usize> {
+        let mut neighbourhood = HashSet::from([input.to_vec()]);
+        let mut distance = 0;
+        while !neighbourhood.iter().any(|word| table.accepts(word)) {
+            if distance == k {
+                return None;
+            }
+            neighbourhood = neighbourhood.iter().flat_map(|word| edits(word)).collect();
+            distance += 1;
+        }
+        Some(distance)
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let tables = crate::tests::benchmarks()
+            .map(|program| Table::build(&program).minimized())
+            .chain([exactly(&[1, 2, 3]), exactly(&[])]);
+        for table in tables {
+            for input in short_inputs(2).filter(|input| input.iter().all(|&s| s % 7 == 0)) {
+                for k in 0..=2 {
+                    assert_eq!(
+                        table.accepts_within(&input, k),
+                        brute_force(&table, &input, k),
+                        "{input:?} within {k}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hand_checked_distances() {
+        let table = exactly(&[1, 2, 3]);
+        assert_eq!(table.accepts_within(&[1, 2, 3], 0), Some(0));
+        assert_eq!(table.accepts_within(&[1, 3], 2), Some(1));
+        assert_eq!(table.accepts_within(&[1, 5, 2, 3], 2), Some(1));
+        assert_eq!(table.accepts_within(&[0, 2, 0], 2), Some(2));
+        assert_eq!(table.accepts_within(&[0, 2, 0], 1), None);
+        assert_eq!(table.accepts_within(&[], 2), None);
+    }
+}
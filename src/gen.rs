@@ -0,0 +1,249 @@
+//! Random programs for stress-testing the builder.
+
+use std::num::NonZeroUsize;
+
+use crate::{rng::Rng, BuildOptions, Program, Table};
+
+/// Builds of candidates for [`generate_nontrivial`] give up after this many states.
+pub const NONTRIVIAL_STATE_LIMIT: usize = 10_000;
+
+/// Relative frequencies of the instructions in generated programs. Brackets are only drawn
+/// where they keep the program balanced and within the nesting bound.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstructionWeights {
+    pub increment: u32,
+    pub decrement: u32,
+    pub move_left: u32,
+    pub move_right: u32,
+    pub read: u32,
+    pub accept: u32,
+    pub start_loop: u32,
+    pub end_loop: u32,
+}
+
+impl Default for InstructionWeights {
+    fn default() -> Self {
+        Self {
+            increment: 4,
+            decrement: 4,
+            move_left: 3,
+            move_right: 3,
+            read: 3,
+            accept: 1,
+            start_loop: 2,
+            end_loop: 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratorConfig {
+    /// Number of instructions.
+    pub len: usize,
+    pub max_nesting: usize,
+    pub weights: InstructionWeights,
+    pub seed: u64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            len: 24,
+            max_nesting: 3,
+            weights: InstructionWeights::default(),
+            seed: 0,
+        }
+    }
+}
+
+/// A program of exactly `config.len` instructions with balanced brackets nested at most
+/// `config.max_nesting` deep. The same config always gives the same program.
+pub fn generate(config: &GeneratorConfig) -> String {
+    let weights = &config.weights;
+    let mut rng = Rng::new(config.seed);
+    let mut program = String::with_capacity(config.len);
+    let mut depth = 0;
+
+    while program.len() < config.len {
+        let remaining = config.len - program.len();
+        let mut candidates = Vec::new();
+        // every open loop still needs a `]`
+        if remaining > depth {
+            candidates.extend([
+                ('+', weights.increment),
+                ('-', weights.decrement),
+                ('<', weights.move_left),
+                ('>', weights.move_right),
+                (',', weights.read),
+                ('.', weights.accept),
+            ]);
+        }
+        if depth < config.max_nesting && remaining >= depth + 2 {
+            candidates.push(('[', weights.start_loop));
+        }
+        if depth > 0 {
+            candidates.push((']', weights.end_loop));
+        }
+        if candidates.is_empty() {
+            break;
+        }
+
+        let total: u64 = candidates
+            .iter()
+            .map(|&(_, weight)| u64::from(weight))
+            .sum();
+        let instruction = if total == 0 {
+            candidates[rng.below(candidates.len())].0
+        } else {
+            let mut pick = rng.next_u64() % total;
+            candidates
+                .iter()
+                .find(|&&(_, weight)| {
+                    let found = pick < u64::from(weight);
+                    pick = pick.saturating_sub(u64::from(weight));
+                    found
+                })
+                .unwrap()
+                .0
+        };
+        match instruction {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+        program.push(instruction);
+    }
+
+    program
+}
+
+/// The first program generated with seeds `config.seed`, `config.seed + 1`, … whose language
+/// is neither empty nor universal, i.e. whose minimized table has at least two states.
+/// Candidates that exceed [`NONTRIVIAL_STATE_LIMIT`] states are skipped too. `None` if all
+/// `max_attempts` candidates were rejected.
+pub fn generate_nontrivial(
+    config: &GeneratorConfig,
+    cell_count: NonZeroUsize,
+    max_attempts: usize,
+) -> Option<String> {
+    let options = BuildOptions {
+        max_states: Some(NONTRIVIAL_STATE_LIMIT),
+        ..BuildOptions::default()
+    };
+    (0..max_attempts as u64)
+        .map(|attempt| {
+            generate(&GeneratorConfig {
+                seed: config.seed.wrapping_add(attempt),
+                ..config.clone()
+            })
+        })
+        .find(|text| {
+            let program = Program::new(text, cell_count);
+            Table::build_with(&program, &options)
+                .is_ok_and(|(table, _)| table.minimized().state_count() >= 2)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{Program, Table};
+
+    use super::{generate, generate_nontrivial, GeneratorConfig, InstructionWeights};
+
+    fn max_depth(program: &str) -> Option<usize> {
+        let (mut depth, mut max) = (0usize, 0);
+        for c in program.chars() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth = depth.checked_sub(1)?,
+                _ => {}
+            }
+            max = max.max(depth);
+        }
+        (depth == 0).then_some(max)
+    }
+
+    #[test]
+    fn programs_are_balanced_and_bounded() {
+        for seed in 0..200 {
+            for (len, max_nesting) in [(0, 3), (1, 3), (2, 1), (24, 3), (40, 0), (9, 5)] {
+                let config = GeneratorConfig {
+                    len,
+                    max_nesting,
+                    seed,
+                    ..GeneratorConfig::default()
+                };
+                let program = generate(&config);
+                assert_eq!(program.len(), len);
+                assert!(max_depth(&program).is_some_and(|depth| depth <= max_nesting));
+                assert!(program.chars().all(|c| "+-<>,.[]".contains(c)));
+                assert_eq!(generate(&config), program);
+            }
+        }
+    }
+
+    #[test]
+    fn seeds_give_different_programs() {
+        let programs: std::collections::HashSet<_> = (0..20)
+            .map(|seed| {
+                generate(&GeneratorConfig {
+                    seed,
+                    ..GeneratorConfig::default()
+                })
+            })
+            .collect();
+        assert!(programs.len() > 1);
+    }
+
+    #[test]
+    fn weights_choose_the_instructions() {
+        let only_increments = GeneratorConfig {
+            len: 10,
+            weights: InstructionWeights {
+                increment: 1,
+                decrement: 0,
+                move_left: 0,
+                move_right: 0,
+                read: 0,
+                accept: 0,
+                start_loop: 0,
+                end_loop: 0,
+            },
+            ..GeneratorConfig::default()
+        };
+        assert_eq!(generate(&only_increments), "++++++++++");
+
+        // with nothing weighted, instructions are drawn uniformly but stay balanced
+        let unweighted = GeneratorConfig {
+            len: 30,
+            weights: InstructionWeights {
+                increment: 0,
+                ..only_increments.weights.clone()
+            },
+            ..only_increments
+        };
+        assert!(max_depth(&generate(&unweighted)).is_some());
+    }
+
+    #[test]
+    fn nontrivial_programs() {
+        let cell_count = NonZeroUsize::new(2).unwrap();
+        let config = GeneratorConfig::default();
+        let text = generate_nontrivial(&config, cell_count, 100).unwrap();
+        let table = Table::build(&Program::new(&text, cell_count));
+        assert!(table.minimized().state_count() >= 2);
+
+        assert_eq!(generate_nontrivial(&config, cell_count, 0), None);
+        // without a `.` nothing is ever accepted
+        let never_accepts = GeneratorConfig {
+            weights: InstructionWeights {
+                accept: 0,
+                ..InstructionWeights::default()
+            },
+            ..config
+        };
+        assert_eq!(generate_nontrivial(&never_accepts, cell_count, 20), None);
+    }
+}
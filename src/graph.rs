@@ -0,0 +1,175 @@
+use petgraph::{graph::DiGraph, visit::EdgeRef};
+
+use crate::{Table, TableDefect};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NodeInfo {
+    pub state: usize,
+    pub accepting: bool,
+}
+
+/// The inputs an edge stands for, bit `i` for input `i`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EdgeInfo {
+    pub inputs: u16,
+}
+
+impl EdgeInfo {
+    pub fn contains(&self, input: u8) -> bool {
+        self.inputs & (1 << (input & 0x0F)) != 0
+    }
+
+    pub fn inputs(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..16).filter(|&input| self.contains(input))
+    }
+}
+
+impl Table {
+    /// The automaton as a graph with node `i` for state `i` and, like [`Table::dot`], one edge
+    /// per distinct target of each state, in ascending order of target.
+    pub fn to_petgraph(&self) -> DiGraph<NodeInfo, EdgeInfo> {
+        let mut graph = DiGraph::with_capacity(self.state_count(), self.state_count() * 16);
+        let nodes: Vec<_> = (0..self.state_count())
+            .map(|state| {
+                graph.add_node(NodeInfo {
+                    state,
+                    accepting: self.is_accepting(state),
+                })
+            })
+            .collect();
+        for (from, &node) in nodes.iter().enumerate() {
+            let mut targets = *self.transitions(from);
+            targets.sort_unstable();
+            let mut previous = None;
+            for &to in &targets {
+                if to == Self::MISSING || previous == Some(to) {
+                    continue;
+                }
+                previous = Some(to);
+                let inputs = (0..16)
+                    .filter(|&input| self.transitions(from)[input] == to)
+                    .fold(0, |inputs, input| inputs | 1 << input);
+                graph.add_edge(node, nodes[to], EdgeInfo { inputs });
+            }
+        }
+        graph
+    }
+
+    /// Reads a graph shaped like the output of [`Table::to_petgraph`]: node `i` is state `i`,
+    /// whatever its [`NodeInfo::state`], and node 0 is the start state. Inputs no edge
+    /// covers are missing transitions, and two edges of a node can't share an input.
+    pub fn from_petgraph(graph: &DiGraph<NodeInfo, EdgeInfo>) -> Result<Table, Vec<TableDefect>> {
+        let mut rows: Vec<_> = graph
+            .node_weights()
+            .map(|node| (node.accepting, [Self::MISSING; 16]))
+            .collect();
+        if rows.is_empty() {
+            return Err(vec![TableDefect::NoStates]);
+        }
+
+        let mut defects = Vec::new();
+        for edge in graph.edge_references() {
+            let (from, to) = (edge.source().index(), edge.target().index());
+            for input in edge.weight().inputs() {
+                let target = &mut rows[from].1[input as usize];
+                if *target == Self::MISSING {
+                    *target = to;
+                } else {
+                    defects.push(TableDefect::ConflictingTransitions { state: from, input });
+                }
+            }
+        }
+
+        if defects.is_empty() {
+            Ok(Table::from_rows(rows))
+        } else {
+            Err(defects)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{algo::tarjan_scc, graph::DiGraph, visit::EdgeRef};
+
+    use crate::{tests::exactly, Table, TableDefect};
+
+    use super::{EdgeInfo, NodeInfo};
+
+    #[test]
+    fn one_edge_per_target() {
+        // the inputs without a 0
+        let mut start = [0; 16];
+        start[0] = 1;
+        let graph = Table::from_rows(vec![(true, start), (false, [1; 16])]).to_petgraph();
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(
+            graph.node_weights().copied().collect::<Vec<_>>(),
+            [
+                NodeInfo {
+                    state: 0,
+                    accepting: true
+                },
+                NodeInfo {
+                    state: 1,
+                    accepting: false
+                },
+            ]
+        );
+        let edges: Vec<_> = graph
+            .edge_references()
+            .map(|edge| {
+                (
+                    edge.source().index(),
+                    edge.target().index(),
+                    edge.weight().inputs,
+                )
+            })
+            .collect();
+        assert_eq!(edges, [(0, 0, 0xFFFE), (0, 1, 0x0001), (1, 1, 0xFFFF)]);
+        let inputs: Vec<u8> = EdgeInfo { inputs: 0b1010 }.inputs().collect();
+        assert_eq!(inputs, [1, 3]);
+    }
+
+    #[test]
+    fn graphs_round_trip() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program).minimized();
+            let graph = table.to_petgraph();
+            assert!(Table::from_petgraph(&graph)
+                .unwrap()
+                .rows()
+                .eq(table.rows()));
+            assert_eq!(tarjan_scc(&graph).len(), table.sccs().len());
+        }
+
+        // missing transitions have no edge
+        let partial = exactly(&[1, 2]).to_partial();
+        let graph = partial.to_petgraph();
+        assert_eq!(graph.edge_count(), 2);
+        assert!(Table::from_petgraph(&graph)
+            .unwrap()
+            .rows()
+            .eq(partial.rows()));
+    }
+
+    #[test]
+    fn malformed_graphs() {
+        assert_eq!(
+            Table::from_petgraph(&DiGraph::new()).unwrap_err(),
+            [TableDefect::NoStates]
+        );
+
+        let mut graph = DiGraph::new();
+        let node = graph.add_node(NodeInfo {
+            state: 0,
+            accepting: false,
+        });
+        graph.add_edge(node, node, EdgeInfo { inputs: 0b0110 });
+        graph.add_edge(node, node, EdgeInfo { inputs: 0b0100 });
+        assert_eq!(
+            Table::from_petgraph(&graph).unwrap_err(),
+            [TableDefect::ConflictingTransitions { state: 0, input: 2 }]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use crate::Table;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphStats {
+    pub states: usize,
+    pub accepting_states: usize,
+    /// Distinct targets per state, ignoring missing transitions.
+    pub out_degree_min: usize,
+    pub out_degree_mean: f64,
+    pub out_degree_max: usize,
+    /// Longest shortest path from the start to a reachable state.
+    pub diameter: usize,
+    pub scc_count: usize,
+    pub largest_scc: usize,
+    /// States from which no accepting state is reachable.
+    pub hopeless_states: usize,
+}
+
+impl Table {
+    pub fn graph_stats(&self) -> GraphStats {
+        let out_degrees: Vec<usize> = (0..self.state_count())
+            .map(|state| {
+                let mut targets = *self.transitions(state);
+                targets.sort_unstable();
+                let mut count = 0;
+                for (i, &to) in targets.iter().enumerate() {
+                    if to != Table::MISSING && (i == 0 || targets[i - 1] != to) {
+                        count += 1;
+                    }
+                }
+                count
+            })
+            .collect();
+        let sccs = self.sccs();
+
+        GraphStats {
+            states: self.state_count(),
+            accepting_states: (0..self.state_count())
+                .filter(|&state| self.is_accepting(state))
+                .count(),
+            out_degree_min: out_degrees.iter().copied().min().unwrap_or(0),
+            out_degree_mean: out_degrees.iter().sum::<usize>() as f64 / out_degrees.len() as f64,
+            out_degree_max: out_degrees.iter().copied().max().unwrap_or(0),
+            diameter: self
+                .access_strings()
+                .iter()
+                .flatten()
+                .map(Vec::len)
+                .max()
+                .unwrap_or(0),
+            scc_count: sccs.len(),
+            largest_scc: sccs.iter().map(Vec::len).max().unwrap_or(0),
+            hopeless_states: self.dead_states().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GraphStats;
+    use crate::Table;
+
+    #[test]
+    fn hand_analyzed_metrics() {
+        // 2 and 3 cycle and can leave for the accepting 1, while 4 is a hopeless sink
+        let mut start = [4; 16];
+        start[0] = 1;
+        start[1] = 2;
+        let mut three = [2; 16];
+        three[0] = 1;
+        let table = Table::from_rows(vec![
+            (false, start),
+            (true, [1; 16]),
+            (false, [3; 16]),
+            (false, three),
+            (false, [4; 16]),
+        ]);
+        assert_eq!(
+            table.graph_stats(),
+            GraphStats {
+                states: 5,
+                accepting_states: 1,
+                out_degree_min: 1,
+                out_degree_mean: 1.6,
+                out_degree_max: 3,
+                diameter: 2,
+                scc_count: 4,
+                largest_scc: 2,
+                hopeless_states: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_transitions_are_not_targets() {
+        let mut edges = [Table::MISSING; 16];
+        edges[3] = 0;
+        let stats = Table::from_rows(vec![(true, edges)]).graph_stats();
+        assert_eq!(stats.out_degree_max, 1);
+        assert_eq!(stats.diameter, 0);
+        assert_eq!(stats.scc_count, 1);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::Table;
+
+/// A table whose transitions each read a group of `k` symbols, built by
+/// [`Table::group_symbols`]. Group symbols are numbers below `16^k` whose hex digits, most
+/// significant first, are the original symbols; for `k = 2` they are bytes.
+#[derive(Clone, Debug)]
+pub struct GroupedTable {
+    group_size: usize,
+    accepting: Vec<bool>,
+    /// Target of each `(state, group)` at index `state * 16^k + group`, possibly
+    /// [`Table::MISSING`].
+    transitions: Vec<usize>,
+}
+
+impl Table {
+    /// The same machine reading `k` symbols per transition. A group string is accepted when
+    /// its expansion into symbols is, so strings whose length isn't a multiple of `k` can't be
+    /// expressed at all.
+    ///
+    /// # Panics
+    ///
+    /// If `k` is zero or `16^k` overflows.
+    pub fn group_symbols(&self, k: usize) -> GroupedTable {
+        assert!(k > 0, "groups must contain at least one symbol");
+        let width = u32::try_from(k)
+            .ok()
+            .and_then(|k| 16usize.checked_pow(k))
+            .expect("the grouped alphabet is too large");
+
+        let mut transitions = Vec::with_capacity(self.state_count() * width);
+        for state in 0..self.state_count() {
+            for group in 0..width {
+                let mut current = state;
+                for digit in (0..k).rev() {
+                    if current == Table::MISSING {
+                        break;
+                    }
+                    current = self.transition(current, (group >> (4 * digit)) as u8);
+                }
+                transitions.push(current);
+            }
+        }
+
+        GroupedTable {
+            group_size: k,
+            accepting: (0..self.state_count())
+                .map(|state| self.is_accepting(state))
+                .collect(),
+            transitions,
+        }
+    }
+}
+
+impl GroupedTable {
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    /// `16^k`, the number of distinct groups.
+    pub fn alphabet_size(&self) -> usize {
+        self.transitions.len() / self.accepting.len()
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting[state]
+    }
+
+    /// # Panics
+    ///
+    /// If `group` is not below [`GroupedTable::alphabet_size`].
+    pub fn transition(&self, state: usize, group: usize) -> usize {
+        assert!(group < self.alphabet_size(), "group symbol out of range");
+        self.transitions[state * self.alphabet_size() + group]
+    }
+
+    /// A missing transition rejects the input.
+    pub fn accepts(&self, input: &[usize]) -> bool {
+        let mut state = 0;
+        for &group in input {
+            state = self.transition(state, group);
+            if state == Table::MISSING {
+                return false;
+            }
+        }
+        self.is_accepting(state)
+    }
+
+    /// [`GroupedTable::accepts`] for a table grouping two symbols, reading each byte as the
+    /// pair of its high and low nibble.
+    ///
+    /// # Panics
+    ///
+    /// If the group size is not 2.
+    pub fn accepts_bytes(&self, input: &[u8]) -> bool {
+        assert_eq!(self.group_size, 2, "bytes are groups of two symbols");
+        let groups: Vec<usize> = input.iter().map(|&byte| byte as usize).collect();
+        self.accepts(&groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Table;
+
+    fn nibbles(bytes: &[u8]) -> Vec<u8> {
+        bytes
+            .iter()
+            .flat_map(|&byte| [byte >> 4, byte & 0x0F])
+            .collect()
+    }
+
+    #[test]
+    fn bytes_are_read_as_nibble_pairs() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program).minimized();
+            let grouped = table.group_symbols(2);
+            assert_eq!(grouped.alphabet_size(), 256);
+            assert_eq!(grouped.state_count(), table.state_count());
+
+            assert_eq!(grouped.accepts_bytes(&[]), table.accepts(&[]));
+            for first in 0..=255 {
+                assert_eq!(
+                    grouped.accepts_bytes(&[first]),
+                    table.accepts(&nibbles(&[first]))
+                );
+                for second in 0..=255 {
+                    let bytes = [first, second];
+                    assert_eq!(
+                        grouped.accepts_bytes(&bytes),
+                        table.accepts(&nibbles(&bytes))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn groups_of_one_and_three() {
+        let table = crate::tests::exactly(&[1, 2, 3]);
+        let single = table.group_symbols(1);
+        assert_eq!(single.alphabet_size(), 16);
+        assert!(single.accepts(&[1, 2, 3]));
+        assert!(!single.accepts(&[1, 2]));
+
+        let triple = table.group_symbols(3);
+        assert_eq!(triple.alphabet_size(), 4096);
+        assert!(triple.accepts(&[0x123]));
+        assert!(!triple.accepts(&[0x321]));
+        assert!(!triple.accepts(&[0x123, 0x123]));
+    }
+
+    #[test]
+    fn missing_transitions_reject() {
+        let mut edges = [0; 16];
+        edges[5] = Table::MISSING;
+        let grouped = Table::from_rows(vec![(true, edges)]).group_symbols(2);
+        assert!(grouped.accepts_bytes(&[0x11, 0x44]));
+        assert!(!grouped.accepts_bytes(&[0x15]));
+        assert!(!grouped.accepts_bytes(&[0x51, 0x11]));
+        assert_eq!(grouped.transition(0, 0x50), Table::MISSING);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one symbol")]
+    fn empty_groups_are_rejected() {
+        crate::tests::exactly(&[]).group_symbols(0);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{Instruction, Program};
+
+/// Head excursion of a stretch of code relative to where it starts: the lowest and highest
+/// offsets visited and the offset it ends at.
+#[derive(Clone, Copy)]
+struct Excursion {
+    min: isize,
+    max: isize,
+    net: isize,
+}
+
+impl Program {
+    /// The number of cells the head can ever visit, if a simple static analysis can bound it:
+    /// every loop body must have zero net head movement and the brackets must be balanced.
+    ///
+    /// The bound ignores tape wrap-around, so it only describes the program's real behaviour
+    /// when it is strictly less than the cell count.
+    pub fn head_bound(&self) -> Option<usize> {
+        // one excursion per open loop, the outermost level first
+        let mut stack = vec![Excursion {
+            min: 0,
+            max: 0,
+            net: 0,
+        }];
+        for instruction in &self.instructions {
+            let current = stack.last_mut().unwrap();
+            match instruction {
+                Instruction::MoveLeft => {
+                    current.net -= 1;
+                    current.min = current.min.min(current.net);
+                }
+                Instruction::MoveRight => {
+                    current.net += 1;
+                    current.max = current.max.max(current.net);
+                }
+                Instruction::StartLoop => stack.push(Excursion {
+                    min: 0,
+                    max: 0,
+                    net: 0,
+                }),
+                Instruction::EndLoop => {
+                    let body = stack.pop().unwrap();
+                    if body.net != 0 {
+                        return None;
+                    }
+                    let outer = stack.last_mut()?;
+                    outer.min = outer.min.min(outer.net + body.min);
+                    outer.max = outer.max.max(outer.net + body.max);
+                }
+                _ => {}
+            }
+        }
+
+        let [whole] = stack[..] else {
+            return None;
+        };
+        Some((whole.max - whole.min + 1) as usize)
+    }
+
+    /// This program on the smallest tape that behaves identically, per
+    /// [`Program::head_bound`], or `None` if no smaller tape is known to.
+    pub(crate) fn shrunk_tape(&self) -> Option<Program> {
+        let bound = self.head_bound()?;
+        if bound >= self.cell_count.get() {
+            return None;
+        }
+        Some(Program {
+            cell_count: bound.try_into().ok()?,
+            instructions: self.instructions.clone(),
+            spans: self.spans.clone(),
+        })
+    }
+}
+
+impl Program {
+    /// An upper bound on the number of states [`crate::Table::build`] can discover: every tape
+    /// content, head position and position after a `,`. Uses the smaller tape from
+    /// [`Program::head_bound`] when there is one, and saturates instead of overflowing.
+    pub fn state_space_bound(&self) -> u128 {
+        let cells = self
+            .head_bound()
+            .filter(|&bound| bound < self.cell_count.get())
+            .unwrap_or(self.cell_count.get());
+        let reads = self
+            .instructions
+            .iter()
+            .filter(|&&instruction| instruction == Instruction::Read)
+            .count();
+
+        let contents = u32::try_from(cells)
+            .ok()
+            .and_then(|cells| 16u128.checked_pow(cells))
+            .unwrap_or(u128::MAX);
+        contents
+            .saturating_mul(cells as u128)
+            .saturating_mul(reads as u128 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{BuildOptions, Program, Table};
+
+    fn bound(text: &str) -> Option<usize> {
+        Program::new(text, NonZeroUsize::new(64).unwrap()).head_bound()
+    }
+
+    #[test]
+    fn head_bounds() {
+        assert_eq!(bound(",[.,]"), Some(1));
+        assert_eq!(bound(",>,[-<+>]<[.,]"), Some(2));
+        // moving left first still covers two cells
+        assert_eq!(bound("<,[.>+<]"), Some(2));
+        assert_eq!(bound(",[>]"), None);
+        assert_eq!(bound(",]"), None);
+        assert_eq!(bound(",["), None);
+    }
+
+    #[test]
+    fn shrinking_only_applies_below_the_cell_count() {
+        let program = Program::new(",>,[-<+>]<[.,]", NonZeroUsize::new(2).unwrap());
+        assert!(program.shrunk_tape().is_none());
+        let program = Program::new(",>,[-<+>]<[.,]", NonZeroUsize::new(3).unwrap());
+        assert_eq!(program.shrunk_tape().unwrap().cell_count.get(), 2);
+    }
+
+    #[test]
+    fn shrunk_tape_builds_the_same_table() {
+        // tracking tape wrap-around turns the shrinking off
+        let full_tape = BuildOptions {
+            track_tape_wrap: true,
+            ..Default::default()
+        };
+        for text in [",>,[-<+>]<[.,]", "<,[.>+<]", "+[>,,.<]"] {
+            let program = Program::new(text, NonZeroUsize::new(5).unwrap());
+            let (full, _) = Table::build_with(&program, &full_tape).unwrap();
+            assert!(Table::build(&program).rows().eq(full.rows()));
+        }
+    }
+
+    #[test]
+    fn large_tape_with_a_tiny_bound() {
+        let program = Program::new(",>,[-<+>]<[.,]", NonZeroUsize::new(4096).unwrap());
+        let bound = program.state_space_bound();
+        assert_eq!(bound, 16 * 16 * 2 * 4);
+        assert!(Table::build(&program).state_count() as u128 <= bound);
+    }
+
+    fn state_space_bound(text: &str, cells: usize) -> u128 {
+        Program::new(text, NonZeroUsize::new(cells).unwrap()).state_space_bound()
+    }
+
+    #[test]
+    fn state_space_bounds() {
+        // tape contents, head positions and positions after a `,`
+        assert_eq!(state_space_bound(",[.,]", 1), 16 * 3);
+        assert_eq!(state_space_bound(",[>,]", 8), 16u128.pow(8) * 8 * 3);
+        assert_eq!(state_space_bound("+[>+]", 2), 16 * 16 * 2);
+        // the head bound only counts when it is below the cell count
+        assert_eq!(state_space_bound(",>,[-<+>]<[.,]", 2), 16 * 16 * 2 * 4);
+        assert_eq!(state_space_bound(",>,[-<+>]<[.,]", 3), 16 * 16 * 2 * 4);
+    }
+
+    #[test]
+    fn state_space_bound_saturates() {
+        assert_eq!(state_space_bound(",[>,]", 31), u128::MAX);
+        assert_eq!(state_space_bound(",[>,]", 10_000), u128::MAX);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::Table;
+
+/// The finitely many strings on which a [hyper-minimized](Table::hyper_minimize) table
+/// disagrees with the original.
+#[derive(Clone, Debug)]
+pub struct FiniteDifference {
+    /// Minimal table accepting exactly the disagreements.
+    table: Table,
+}
+
+impl FiniteDifference {
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.shortest_accepted().is_none()
+    }
+
+    pub fn contains(&self, input: &[u8]) -> bool {
+        self.table.accepts(input)
+    }
+
+    /// Every disagreement, shortest first and then in lexicographic order. There can be up
+    /// to 16 to the power of the state count of [`FiniteDifference::table`].
+    pub fn strings(&self) -> Vec<Vec<u8>> {
+        // a finite language's strings are shorter than its minimal table's state count
+        self.table.accepted_strings(self.table.state_count())
+    }
+}
+
+impl Table {
+    /// The smallest table whose language differs from this one's on only finitely many
+    /// strings, together with those strings. Unlike [`Table::minimize`], the language
+    /// changes, so this is for drawing rather than for deciding anything.
+    ///
+    /// States whose languages differ finitely are almost-equivalent. Those reached by only
+    /// finitely many strings are merged into an almost-equivalent state, preferring one
+    /// reached by infinitely many. Finding them takes time and memory quadratic in the
+    /// number of states.
+    pub fn hyper_minimize(&self) -> (Table, FiniteDifference) {
+        let minimal = self.canonical();
+        let blocks = minimal.almost_equivalence_blocks();
+        let kernel = minimal.kernel();
+
+        let mut representatives = vec![Table::MISSING; minimal.state_count()];
+        for state in (0..minimal.state_count()).filter(|&state| kernel[state]) {
+            representatives[blocks[state]] = state;
+        }
+        for state in 0..minimal.state_count() {
+            if representatives[blocks[state]] == Table::MISSING {
+                representatives[blocks[state]] = state;
+            }
+        }
+
+        let merged = Table::from_rows(
+            minimal
+                .rows()
+                .map(|(accepting, edges)| (*accepting, edges.map(|to| representatives[blocks[to]])))
+                .collect(),
+        );
+        let hyper = merged
+            .restrict_reachable(representatives[blocks[0]])
+            .canonical();
+        let difference = FiniteDifference {
+            table: self.symmetric_difference(&hyper).canonical(),
+        };
+        (hyper, difference)
+    }
+
+    /// For each state of a minimal complete table, the smallest state almost-equivalent to it.
+    ///
+    /// Two states are almost-equivalent unless the pair can reach a cycle of pairs of
+    /// distinct states, since in a minimal table every such pair is told apart by some
+    /// suffix, which can follow any number of turns around the cycle.
+    fn almost_equivalence_blocks(&self) -> Vec<usize> {
+        let state_count = self.state_count();
+        let pair_id = |a: usize, b: usize| {
+            let (low, high) = (a.min(b), a.max(b));
+            high * (high - 1) / 2 + low
+        };
+        let pair_count = state_count * state_count.saturating_sub(1) / 2;
+
+        // successors among pairs of distinct states, counted with multiplicity
+        let mut out_degree = vec![0usize; pair_count];
+        let mut predecessors = vec![Vec::new(); pair_count];
+        for high in 1..state_count {
+            for low in 0..high {
+                let id = pair_id(low, high);
+                for input in 0..16 {
+                    let (a, b) = (self.transition(low, input), self.transition(high, input));
+                    if a != b {
+                        out_degree[id] += 1;
+                        predecessors[pair_id(a, b)].push(id);
+                    }
+                }
+            }
+        }
+
+        // peel off the pairs with no path to a cycle, which are the almost-equivalent ones
+        let mut almost_equivalent = vec![false; pair_count];
+        let mut stack: Vec<usize> = (0..pair_count).filter(|&id| out_degree[id] == 0).collect();
+        while let Some(id) = stack.pop() {
+            almost_equivalent[id] = true;
+            for &predecessor in &predecessors[id] {
+                out_degree[predecessor] -= 1;
+                if out_degree[predecessor] == 0 {
+                    stack.push(predecessor);
+                }
+            }
+        }
+
+        (0..state_count)
+            .map(|state| {
+                (0..state)
+                    .find(|&other| almost_equivalent[pair_id(other, state)])
+                    .unwrap_or(state)
+            })
+            .collect()
+    }
+
+    /// Whether each state is reached by infinitely many strings, i.e. can be reached from a
+    /// cycle. Assumes every state is reachable.
+    fn kernel(&self) -> Vec<bool> {
+        let mut kernel = vec![false; self.state_count()];
+        let mut stack = Vec::new();
+        for component in self.sccs() {
+            if self.is_cyclic_component(&component) {
+                for state in component {
+                    kernel[state] = true;
+                    stack.push(state);
+                }
+            }
+        }
+        while let Some(state) = stack.pop() {
+            for &to in self.transitions(state) {
+                if !kernel[to] {
+                    kernel[to] = true;
+                    stack.push(to);
+                }
+            }
+        }
+        kernel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{exactly, short_inputs},
+        Table,
+    };
+
+    #[test]
+    fn finite_languages_collapse() {
+        let (hyper, difference) = exactly(&[1, 2]).hyper_minimize();
+        assert!(hyper.rows().eq([(false, [0; 16])].iter()));
+        assert_eq!(difference.strings(), [vec![1, 2]]);
+        assert!(difference.contains(&[1, 2]));
+        assert!(!difference.is_empty());
+    }
+
+    #[test]
+    fn only_the_start_is_merged() {
+        // everything but the empty string
+        let table = Table::from_rows(vec![(false, [1; 16]), (true, [1; 16])]);
+        let (hyper, difference) = table.hyper_minimize();
+        assert!(hyper.rows().eq([(true, [0; 16])].iter()));
+        assert_eq!(difference.strings(), [Vec::<u8>::new()]);
+    }
+
+    #[test]
+    fn cyclic_tables_are_left_alone() {
+        // the inputs without a 0
+        let mut start = [0; 16];
+        start[0] = 1;
+        let table = Table::from_rows(vec![(true, start), (false, [1; 16])]);
+        let (hyper, difference) = table.hyper_minimize();
+        assert!(hyper.rows().eq(table.canonical().rows()));
+        assert!(difference.is_empty());
+        assert!(difference.strings().is_empty());
+    }
+
+    #[test]
+    fn benchmarks_differ_finitely() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            let (hyper, difference) = table.hyper_minimize();
+            assert!(hyper.state_count() <= table.minimized().state_count());
+            for input in short_inputs(2) {
+                assert_eq!(
+                    difference.contains(&input),
+                    table.accepts(&input) != hyper.accepts(&input),
+                    "{input:?}"
+                );
+            }
+            // a finite language's minimal table has a rejecting sink and no other cycle
+            let table = difference.table();
+            for component in table.sccs() {
+                if table.is_cyclic_component(&component) {
+                    assert_eq!(component.len(), 1);
+                    assert!(!table.is_accepting(component[0]));
+                }
+            }
+        }
+    }
+}
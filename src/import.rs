@@ -0,0 +1,434 @@
+use std::{collections::HashMap, fmt::Display};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::{
+    json::{self, JsonError, Value},
+    Row, Table,
+};
+
+/// A problem with a hand-built or deserialized table. The last two are only reported as
+/// warnings by [`Table::validation_warnings`], since such tables are still usable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableDefect {
+    NoStates,
+    TargetOutOfRange {
+        state: usize,
+        input: u8,
+        target: usize,
+    },
+    StartOutOfRange {
+        start: usize,
+    },
+    WrongTransitionCount {
+        state: usize,
+        count: usize,
+    },
+    UnreachableState {
+        state: usize,
+    },
+    DuplicateRow {
+        state: usize,
+        duplicate_of: usize,
+    },
+    /// Two edges of a graph from the same state on the same input, see
+    /// [`Table::from_petgraph`].
+    ConflictingTransitions {
+        state: usize,
+        input: u8,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImportError {
+    Json(JsonError),
+    /// The JSON is well-formed but isn't shaped like the output of [`Table::to_json`].
+    Format(String),
+    Defects(Vec<TableDefect>),
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(error) => write!(f, "{error}"),
+            Self::Format(message) => write!(f, "unexpected table format: {message}"),
+            Self::Defects(defects) => {
+                write!(f, "invalid table:")?;
+                for defect in defects {
+                    write!(f, "\n  {defect}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Swaps state `start` with state 0, so that it becomes the start state.
+pub(crate) fn swap_start(rows: &mut [Row], start: usize) {
+    if start == 0 {
+        return;
+    }
+    let swap = |state: usize| match state {
+        0 => start,
+        _ if state == start => 0,
+        _ => state,
+    };
+    rows.swap(0, start);
+    for (_, edges) in rows.iter_mut() {
+        for edge in edges.iter_mut().filter(|edge| **edge != Table::MISSING) {
+            *edge = swap(*edge);
+        }
+    }
+}
+
+impl Display for TableDefect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoStates => write!(f, "table has no states"),
+            Self::TargetOutOfRange {
+                state,
+                input,
+                target,
+            } => write!(
+                f,
+                "transition from state {state} on {input:X} targets nonexistent state {target}"
+            ),
+            Self::StartOutOfRange { start } => {
+                write!(f, "start state {start} does not exist")
+            }
+            Self::WrongTransitionCount { state, count } => {
+                write!(f, "state {state} has {count} transitions instead of 16")
+            }
+            Self::UnreachableState { state } => {
+                write!(f, "state {state} is unreachable from the start")
+            }
+            Self::DuplicateRow {
+                state,
+                duplicate_of,
+            } => write!(f, "state {state} has the same row as state {duplicate_of}"),
+            Self::ConflictingTransitions { state, input } => {
+                write!(f, "state {state} has several transitions on {input:X}")
+            }
+        }
+    }
+}
+
+impl Table {
+    /// Assembles a table from `(accepting, transitions)` rows, where state 0 is the start state
+    /// and `None` marks a missing transition.
+    ///
+    /// Missing transitions are kept until [`Table::make_complete`] is called; analyses other than
+    /// [`Table::accepts`] and [`Table::is_complete`] expect a complete table.
+    pub fn from_parts(states: Vec<(bool, [Option<usize>; 16])>) -> Result<Self, Vec<TableDefect>> {
+        if states.is_empty() {
+            return Err(vec![TableDefect::NoStates]);
+        }
+
+        let mut defects = Vec::new();
+        let state_count = states.len();
+        let states = states
+            .into_iter()
+            .enumerate()
+            .map(|(state, (accepting, edges))| {
+                let mut row = [Self::MISSING; 16];
+                for (input, edge) in edges.into_iter().enumerate() {
+                    match edge {
+                        Some(target) if target >= state_count => {
+                            defects.push(TableDefect::TargetOutOfRange {
+                                state,
+                                input: input as u8,
+                                target,
+                            });
+                        }
+                        Some(target) => row[input] = target,
+                        None => {}
+                    }
+                }
+                (accepting, row)
+            })
+            .collect();
+
+        if defects.is_empty() {
+            Ok(Self::from_rows(states))
+        } else {
+            Err(defects)
+        }
+    }
+
+    /// Checks that the table has states and that every transition is either missing or in
+    /// range, which everything else relies on.
+    pub fn validate(&self) -> Result<(), Vec<TableDefect>> {
+        if self.state_count() == 0 {
+            return Err(vec![TableDefect::NoStates]);
+        }
+
+        let mut defects = Vec::new();
+        for (state, (_, edges)) in self.rows().enumerate() {
+            for (input, &target) in edges.iter().enumerate() {
+                if target != Self::MISSING && target >= self.state_count() {
+                    defects.push(TableDefect::TargetOutOfRange {
+                        state,
+                        input: input as u8,
+                        target,
+                    });
+                }
+            }
+        }
+
+        if defects.is_empty() {
+            Ok(())
+        } else {
+            Err(defects)
+        }
+    }
+
+    /// Unreachable states and states duplicating an earlier state's row, in state order.
+    /// Expects a table that passes [`Table::validate`].
+    pub fn validation_warnings(&self) -> Vec<TableDefect> {
+        let (reachable, _) = self.bfs_tree();
+        let mut first_with_row = HashMap::with_hasher(FxBuildHasher);
+        let mut warnings = Vec::new();
+        for (state, row) in self.rows().enumerate() {
+            if !reachable[state] {
+                warnings.push(TableDefect::UnreachableState { state });
+            }
+            let first = *first_with_row.entry(row).or_insert(state);
+            if first != state {
+                warnings.push(TableDefect::DuplicateRow {
+                    state,
+                    duplicate_of: first,
+                });
+            }
+        }
+        warnings
+    }
+
+    /// Reads the format written by [`Table::to_json`]. A `start` other than 0 is swapped with
+    /// state 0, and the result is checked with [`Table::validate`].
+    pub fn from_json(text: &str) -> Result<Self, ImportError> {
+        let value = json::parse(text).map_err(ImportError::Json)?;
+        let malformed = |message: &str| ImportError::Format(message.to_string());
+
+        let Some(Value::Array(states)) = value.get("states") else {
+            return Err(malformed("expected a `states` array"));
+        };
+        let start = match value.get("start") {
+            None => 0,
+            Some(start) => start
+                .as_usize()
+                .ok_or_else(|| malformed("`start` must be a state id"))?,
+        };
+
+        let mut defects = Vec::new();
+        let mut rows = Vec::with_capacity(states.len());
+        for (state, value) in states.iter().enumerate() {
+            let Some(&Value::Bool(accepting)) = value.get("accepting") else {
+                return Err(malformed("every state needs a boolean `accepting`"));
+            };
+            let Some(Value::Array(transitions)) = value.get("transitions") else {
+                return Err(malformed("every state needs a `transitions` array"));
+            };
+            if transitions.len() != 16 {
+                defects.push(TableDefect::WrongTransitionCount {
+                    state,
+                    count: transitions.len(),
+                });
+            }
+
+            let mut edges = [Self::MISSING; 16];
+            for (input, transition) in transitions.iter().take(16).enumerate() {
+                match transition {
+                    Value::Null => {}
+                    target => {
+                        let target = target
+                            .as_usize()
+                            .ok_or_else(|| malformed("transitions must be state ids or null"))?;
+                        if target >= states.len() {
+                            defects.push(TableDefect::TargetOutOfRange {
+                                state,
+                                input: input as u8,
+                                target,
+                            });
+                        } else {
+                            edges[input] = target;
+                        }
+                    }
+                }
+            }
+            rows.push((accepting, edges));
+        }
+
+        if rows.is_empty() {
+            defects.push(TableDefect::NoStates);
+        } else if start >= rows.len() {
+            defects.push(TableDefect::StartOutOfRange { start });
+        }
+        if !defects.is_empty() {
+            return Err(ImportError::Defects(defects));
+        }
+
+        swap_start(&mut rows, start);
+
+        let table = Self::from_rows(rows);
+        table.validate().map_err(ImportError::Defects)?;
+        Ok(table)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.rows
+            .iter()
+            .all(|(_, edges)| !edges.contains(&Self::MISSING))
+    }
+
+    /// Routes every missing transition to a fresh non-accepting sink state.
+    /// Does nothing if the table is already complete.
+    pub fn make_complete(&mut self) {
+        if self.is_complete() {
+            return;
+        }
+
+        let sink = self.state_count();
+        let rows = self.rows_mut();
+        for (_, edges) in rows.iter_mut() {
+            for edge in edges {
+                if *edge == Self::MISSING {
+                    *edge = sink;
+                }
+            }
+        }
+        rows.push((false, [sink; 16]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImportError, TableDefect};
+    use crate::Table;
+
+    /// Accepts `1*`, with every other transition missing.
+    const ONES: &str = r#"{"start": 0, "states": [{"accepting": true, "transitions":
+        [null, 0, null, null, null, null, null, null, null, null, null, null, null, null, null, null]}]}"#;
+
+    #[test]
+    fn completing_a_partial_import() {
+        let mut table = Table::from_json(ONES).unwrap();
+        assert!(!table.is_complete());
+        assert!(table.accepts(&[1, 1]));
+        assert!(!table.accepts(&[2]));
+
+        table.make_complete();
+        assert!(table.is_complete());
+        assert_eq!(table.state_count(), 2);
+        assert_eq!(table.sink_states(), vec![1]);
+        assert!(table.accepts(&[1, 1]));
+        assert!(!table.accepts(&[1, 2, 1]));
+
+        let before = table.clone();
+        table.make_complete();
+        assert!(table.rows().eq(before.rows()));
+    }
+
+    #[test]
+    fn complement_accepts_the_missing_symbols() {
+        let complement = Table::from_json(ONES).unwrap().complement();
+        assert!(complement.is_complete());
+        assert!(!complement.accepts(&[]));
+        assert!(!complement.accepts(&[1, 1]));
+        assert!(complement.accepts(&[0]));
+        assert!(complement.accepts(&[1, 2]));
+        assert!(complement.accepts(&[1, 0xF, 1]));
+    }
+
+    /// A single accepting state with these transitions.
+    fn one_state(start: usize, transitions: &[&str]) -> String {
+        format!(
+            r#"{{"start": {start}, "states": [{{"accepting": true, "transitions": [{}]}}]}}"#,
+            transitions.join(", ")
+        )
+    }
+
+    fn defects(text: &str) -> Vec<TableDefect> {
+        match Table::from_json(text) {
+            Err(ImportError::Defects(defects)) => defects,
+            other => panic!("expected defects, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn corrupted_json_is_refused() {
+        let mut transitions = ["0"; 16];
+        transitions[3] = "7";
+        transitions[9] = "1";
+        assert_eq!(
+            defects(&one_state(0, &transitions)),
+            [
+                TableDefect::TargetOutOfRange {
+                    state: 0,
+                    input: 3,
+                    target: 7
+                },
+                TableDefect::TargetOutOfRange {
+                    state: 0,
+                    input: 9,
+                    target: 1
+                },
+            ]
+        );
+        assert_eq!(
+            defects(&one_state(2, &["0"; 16])),
+            [TableDefect::StartOutOfRange { start: 2 }]
+        );
+        assert_eq!(
+            defects(&one_state(0, &["0"; 15])),
+            [TableDefect::WrongTransitionCount {
+                state: 0,
+                count: 15
+            }]
+        );
+        assert_eq!(defects(r#"{"states": []}"#), [TableDefect::NoStates]);
+        assert!(matches!(
+            Table::from_json(&one_state(0, &["true"; 16])),
+            Err(ImportError::Format(_))
+        ));
+    }
+
+    #[test]
+    fn hand_built_tables_are_validated() {
+        let bad = Table::from_rows(vec![(
+            true,
+            [0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        )]);
+        assert_eq!(
+            bad.validate(),
+            Err(vec![TableDefect::TargetOutOfRange {
+                state: 0,
+                input: 1,
+                target: 1
+            }])
+        );
+        assert_eq!(
+            Table::from_rows(vec![]).validate(),
+            Err(vec![TableDefect::NoStates])
+        );
+        assert!(Table::from_json(ONES).unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn unreachable_and_duplicate_states_are_warnings() {
+        let table = Table::from_rows(vec![(true, [0; 16]), (false, [1; 16]), (true, [0; 16])]);
+        assert!(table.validate().is_ok());
+        assert_eq!(
+            table.validation_warnings(),
+            [
+                TableDefect::UnreachableState { state: 1 },
+                TableDefect::UnreachableState { state: 2 },
+                TableDefect::DuplicateRow {
+                    state: 2,
+                    duplicate_of: 0
+                },
+            ]
+        );
+    }
+}
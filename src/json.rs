@@ -0,0 +1,312 @@
+use std::fmt::Display;
+
+/// Just enough JSON for reading tables back in.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonError {
+    /// Byte offset in the input.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid JSON at offset {}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl Value {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_usize(&self) -> Option<usize> {
+        match *self {
+            Self::Number(n) if n >= 0.0 && n.fract() == 0.0 && n < usize::MAX as f64 => {
+                Some(n as usize)
+            }
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn parse(text: &str) -> Result<Value, JsonError> {
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        offset: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.whitespace();
+    if parser.offset != parser.bytes.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    Ok(value)
+}
+
+/// How deeply arrays and objects may nest, so that hostile input can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    /// Arrays and objects currently open.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> JsonError {
+        JsonError {
+            offset: self.offset,
+            message: message.to_string(),
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.offset)
+            .is_some_and(|byte| byte.is_ascii_whitespace())
+        {
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonError> {
+        self.whitespace();
+        if self.bytes.get(self.offset) == Some(&byte) {
+            self.offset += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", byte as char)))
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, JsonError> {
+        if self.bytes[self.offset..].starts_with(word.as_bytes()) {
+            self.offset += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("unexpected character"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.whitespace();
+        match self.bytes.get(self.offset) {
+            None => Err(self.error("unexpected end of input")),
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.offset;
+                while self.bytes.get(self.offset).is_some_and(|byte| {
+                    matches!(byte, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                }) {
+                    self.offset += 1;
+                }
+                std::str::from_utf8(&self.bytes[start..self.offset])
+                    .ok()
+                    .and_then(|number| number.parse().ok())
+                    .map(Value::Number)
+                    .ok_or_else(|| JsonError {
+                        offset: start,
+                        message: "invalid number".to_string(),
+                    })
+            }
+            Some(_) => Err(self.error("unexpected character")),
+        }
+    }
+
+    /// Parses an array or object with `parse`, unless that would go deeper than [`MAX_DEPTH`].
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Value, JsonError>,
+    ) -> Result<Value, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    /// Reads an array starting at the opening bracket.
+    fn array(&mut self) -> Result<Value, JsonError> {
+        self.offset += 1;
+        let mut items = Vec::new();
+        self.whitespace();
+        if self.bytes.get(self.offset) == Some(&b']') {
+            self.offset += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.whitespace();
+            match self.bytes.get(self.offset) {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    /// Reads an object starting at the opening brace.
+    fn object(&mut self) -> Result<Value, JsonError> {
+        self.offset += 1;
+        let mut fields = Vec::new();
+        self.whitespace();
+        if self.bytes.get(self.offset) == Some(&b'}') {
+            self.offset += 1;
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            self.whitespace();
+            if self.bytes.get(self.offset) != Some(&b'"') {
+                return Err(self.error("expected a field name"));
+            }
+            let name = self.string()?;
+            self.expect(b':')?;
+            fields.push((name, self.value()?));
+            self.whitespace();
+            match self.bytes.get(self.offset) {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    return Ok(Value::Object(fields));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    /// Reads a string starting at the opening quote.
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.offset += 1;
+        let mut string = Vec::new();
+        loop {
+            match self.bytes.get(self.offset) {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => {
+                    self.offset += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    let escaped = match self.bytes.get(self.offset + 1) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'n') => '\n',
+                        Some(b't') => '\t',
+                        Some(b'r') => '\r',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'u') => {
+                            let code = self
+                                .bytes
+                                .get(self.offset + 2..self.offset + 6)
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid unicode escape"))?;
+                            self.offset += 4;
+                            code
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.offset += 2;
+                    string.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                Some(&byte) => {
+                    string.push(byte);
+                    self.offset += 1;
+                }
+            }
+        }
+        String::from_utf8(string).map_err(|_| self.error("invalid UTF-8"))
+    }
+}
+
+/// `value` as a JSON string literal.
+pub(crate) fn quoted(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            c if c < ' ' => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Value, MAX_DEPTH};
+
+    #[test]
+    fn parses_nested_values() {
+        let value = parse(r#" {"a": [1, true, null], "b": {"c": "d\n"}} "#).unwrap();
+        assert_eq!(
+            value.get("a"),
+            Some(&Value::Array(vec![
+                Value::Number(1.0),
+                Value::Bool(true),
+                Value::Null
+            ]))
+        );
+        assert_eq!(
+            value.get("b").and_then(|b| b.get("c")),
+            Some(&Value::String("d\n".to_string()))
+        );
+    }
+
+    #[test]
+    fn nesting_up_to_the_limit_is_fine() {
+        let text = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+        assert!(parse(&text).is_ok());
+        let text = r#"{"a":"#.repeat(MAX_DEPTH - 1) + "{}" + &"}".repeat(MAX_DEPTH - 1);
+        assert!(parse(&text).is_ok());
+    }
+
+    #[test]
+    fn deeper_nesting_is_an_error() {
+        let error = parse(&"[".repeat(100_000)).unwrap_err();
+        assert_eq!(error.offset, MAX_DEPTH);
+        assert_eq!(error.message, "nested too deeply");
+        let text = "[".repeat(MAX_DEPTH + 1) + &"]".repeat(MAX_DEPTH + 1);
+        assert!(parse(&text).is_err());
+    }
+}
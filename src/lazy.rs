@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use rustc_hash::FxBuildHasher;
+
+use crate::{InnerState, Program, RunStats, State, Table};
+
+/// A [`Table`] whose transitions are only computed when first asked for.
+///
+/// States are numbered in the order they are discovered, starting with the start state at 0.
+pub struct LazyTable<'p> {
+    program: &'p Program,
+    state_ids: HashMap<State, usize, FxBuildHasher>,
+    configurations: Vec<Option<InnerState>>,
+    states: Vec<(bool, [usize; 16])>,
+    seen_states: HashMap<InnerState, (), FxBuildHasher>,
+    stats: RunStats,
+}
+
+impl<'p> LazyTable<'p> {
+    pub fn new(program: &'p Program) -> Self {
+        let mut table = Self {
+            program,
+            state_ids: HashMap::with_hasher(FxBuildHasher),
+            configurations: Vec::new(),
+            states: Vec::new(),
+            seen_states: HashMap::with_hasher(FxBuildHasher),
+            stats: RunStats::default(),
+        };
+
+        let start = program.run_with_next_input(
+            program.initial_state(),
+            0,
+            &mut table.seen_states,
+            &mut table.stats,
+        );
+        table.seen_states.clear();
+        table.intern(start);
+        table
+    }
+
+    pub fn start(&self) -> usize {
+        0
+    }
+
+    /// Number of states discovered so far.
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.states[state].0
+    }
+
+    /// Only the low four bits of `input` are used, as in [`Table::transition`].
+    pub fn transition(&mut self, state: usize, input: u8) -> usize {
+        let input = input & 0x0F;
+        let cached = self.states[state].1[input as usize];
+        if cached != Table::MISSING {
+            return cached;
+        }
+
+        // halted states are created with all of their self-loops in place
+        let inner = self.configurations[state].clone().unwrap();
+        let next =
+            self.program
+                .run_with_next_input(inner, input, &mut self.seen_states, &mut self.stats);
+        self.seen_states.clear();
+
+        let next_id = self.intern(next);
+        self.states[state].1[input as usize] = next_id;
+        next_id
+    }
+
+    pub fn accepts(&mut self, input: &[u8]) -> bool {
+        let mut state = self.start();
+        for &symbol in input {
+            state = self.transition(state, symbol);
+        }
+        self.is_accepting(state)
+    }
+
+    /// The part of the automaton discovered so far. Transitions that were never computed are
+    /// left as [`Table::MISSING`], so the result is generally a partial table.
+    pub fn materialize(&self) -> Table {
+        Table::from_rows(self.states.clone())
+    }
+
+    fn intern(&mut self, state: State) -> usize {
+        if let Some(&id) = self.state_ids.get(&state) {
+            return id;
+        }
+
+        let id = self.states.len();
+        let edges = if state.inner.is_none() {
+            [id; 16]
+        } else {
+            [Table::MISSING; 16]
+        };
+        self.states.push((state.accepting, edges));
+        self.configurations.push(state.inner.clone());
+        self.state_ids.insert(state, id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LazyTable;
+    use crate::{tests::short_inputs, Table};
+
+    #[test]
+    fn walks_match_the_built_table() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            let mut lazy = LazyTable::new(&program);
+            // the lazy id of every table state met so far
+            let mut lazy_ids = vec![None; table.state_count()];
+            for input in short_inputs(3) {
+                let (mut state, mut lazy_state) = (0, lazy.start());
+                for &symbol in &input {
+                    state = table.transition(state, symbol);
+                    lazy_state = lazy.transition(lazy_state, symbol);
+                    assert_eq!(*lazy_ids[state].get_or_insert(lazy_state), lazy_state);
+                }
+                assert_eq!(lazy.accepts(&input), table.accepts(&input));
+            }
+            assert!(lazy.state_count() <= table.state_count());
+        }
+    }
+
+    #[test]
+    fn full_exploration_materializes_the_build() {
+        for program in crate::tests::benchmarks() {
+            let mut lazy = LazyTable::new(&program);
+            // breadth-first, like the build
+            let mut state = 0;
+            while state < lazy.state_count() {
+                for input in 0..16 {
+                    lazy.transition(state, input);
+                }
+                state += 1;
+            }
+            let materialized = lazy.materialize();
+            assert!(materialized.is_complete());
+            assert!(materialized.rows().eq(Table::build(&program).rows()));
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::Table;
+
+/// A condition on the length of a string, see [`Table::with_length_constraint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LengthConstraint {
+    Exactly(usize),
+    AtMost(usize),
+    /// Lengths `l` with `l % m == r % m`. `m` must not be zero.
+    Modulo {
+        m: usize,
+        r: usize,
+    },
+}
+
+impl LengthConstraint {
+    /// The automaton accepting every string whose length satisfies the constraint, counting
+    /// the symbols read so far and stopping in a rejecting sink once the count can't matter.
+    fn counter(self) -> Table {
+        let rows = match self {
+            Self::Exactly(n) => (0..n + 2)
+                .map(|count| (count == n, [(count + 1).min(n + 1); 16]))
+                .collect(),
+            Self::AtMost(n) => (0..n + 2)
+                .map(|count| (count <= n, [(count + 1).min(n + 1); 16]))
+                .collect(),
+            Self::Modulo { m, r } => {
+                assert_ne!(m, 0, "the modulus of a length constraint can't be zero");
+                (0..m)
+                    .map(|count| (count == r % m, [(count + 1) % m; 16]))
+                    .collect()
+            }
+        };
+        Table::from_rows(rows)
+    }
+}
+
+impl Table {
+    /// The automaton accepting the strings this one accepts whose length satisfies
+    /// `constraint`, as the product with a counter. The result isn't minimized.
+    ///
+    /// # Panics
+    ///
+    /// If `constraint` is a [`LengthConstraint::Modulo`] with `m` zero.
+    pub fn with_length_constraint(&self, constraint: LengthConstraint) -> Table {
+        self.product(&constraint.counter(), |a, b| a && b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::LengthConstraint;
+    use crate::{
+        tests::{benchmarks, exactly, short_inputs},
+        Cardinality, Table,
+    };
+
+    #[test]
+    fn exact_lengths_count_the_strings_of_that_length() {
+        for program in benchmarks() {
+            let table = Table::build(&program);
+            let of_length_3 = short_inputs(3)
+                .filter(|input| input.len() == 3 && table.accepts(input))
+                .count();
+            assert_eq!(
+                table
+                    .with_length_constraint(LengthConstraint::Exactly(3))
+                    .cardinality(),
+                Cardinality::Finite(BigUint::from(of_length_3)),
+                "{program:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn constrained_languages() {
+        let program = benchmarks().nth(3).unwrap();
+        let table = Table::build(&program);
+        let cases: [(LengthConstraint, fn(usize) -> bool); 4] = [
+            (LengthConstraint::Exactly(2), |len| len == 2),
+            (LengthConstraint::AtMost(2), |len| len <= 2),
+            (LengthConstraint::Modulo { m: 2, r: 3 }, |len| len % 2 == 1),
+            (LengthConstraint::Modulo { m: 1, r: 0 }, |_| true),
+        ];
+        for (constraint, holds) in cases {
+            let constrained = table.with_length_constraint(constraint);
+            for input in short_inputs(3) {
+                assert_eq!(
+                    constrained.accepts(&input),
+                    table.accepts(&input) && holds(input.len()),
+                    "{constraint:?} {input:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn constrained_tables_minimize() {
+        let word = exactly(&[1, 2]);
+        let constrained = word
+            .with_length_constraint(LengthConstraint::AtMost(5))
+            .canonical();
+        assert!(constrained.rows().eq(word.canonical().rows()));
+
+        let empty = word.with_length_constraint(LengthConstraint::Exactly(1));
+        assert_eq!(empty.cardinality(), Cardinality::Finite(BigUint::ZERO));
+        assert_eq!(empty.minimized().state_count(), 1);
+
+        let odd = Table::from_rows(vec![(true, [0; 16])])
+            .with_length_constraint(LengthConstraint::Modulo { m: 2, r: 1 });
+        assert_eq!(odd.cardinality(), Cardinality::Infinite);
+        assert_eq!(odd.minimized().state_count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus")]
+    fn zero_modulus() {
+        exactly(&[]).with_length_constraint(LengthConstraint::Modulo { m: 0, r: 0 });
+    }
+}
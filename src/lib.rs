@@ -1,13 +1,121 @@
 use std::{
-    collections::{hash_map::Entry, HashMap, HashSet},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::{Display, Write},
+    hash::{BuildHasher, RandomState},
     num::NonZeroUsize,
+    ops::ControlFlow,
+    time::{Duration, Instant},
 };
 
 use rustc_hash::FxBuildHasher;
 use smallvec::{smallvec, SmallVec};
 
-#[derive(Clone, Copy, Debug)]
+use merge::Merger;
+use observe::Observer;
+
+mod analysis;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod batch;
+mod binary;
+mod bounds;
+mod buchi;
+#[cfg(feature = "num")]
+mod cardinality;
+mod compiled;
+mod cost;
+mod coverage;
+mod dialect;
+mod dot;
+mod editor;
+mod equiv;
+mod explain;
+mod export;
+mod fuzzy;
+pub mod gen;
+#[cfg(feature = "petgraph")]
+mod graph;
+mod graph_stats;
+mod grouped;
+mod head;
+mod hyper;
+mod import;
+mod json;
+mod lazy;
+mod length;
+mod lint;
+mod matrix;
+mod memory;
+mod merge;
+mod monoid;
+mod names;
+mod navigate;
+mod nerode;
+mod nfa;
+mod normalize;
+mod observe;
+mod ops;
+mod pretty;
+mod program_stats;
+mod quotient;
+mod reduce;
+mod reference;
+mod regex;
+mod report;
+mod resume;
+mod rng;
+mod search;
+mod smt;
+mod sweep;
+mod synth;
+mod trace;
+pub mod verify;
+mod verilog;
+
+pub use analysis::{InfiniteLanguage, LengthSpectrum, Pump};
+pub use bounds::ReadBounds;
+pub use buchi::BuchiTable;
+#[cfg(feature = "num")]
+pub use cardinality::Cardinality;
+pub use compiled::CompiledTable;
+pub use cost::CostStats;
+pub use coverage::Coverage;
+pub use dialect::{Brainfuck, Counted, Dialect, Ook, ParseError, ProgramOptions};
+pub use dot::{Cluster, DotOptions, DotStyle, EdgeWeighting, SummaryOptions};
+pub use editor::TableEditor;
+pub use equiv::{EquivResult, Side};
+pub use explain::{Explanation, Verdict};
+pub use export::JsonOptions;
+#[cfg(feature = "petgraph")]
+pub use graph::{EdgeInfo, NodeInfo};
+pub use graph_stats::GraphStats;
+pub use grouped::GroupedTable;
+pub use hyper::FiniteDifference;
+pub use import::{ImportError, TableDefect};
+pub use json::JsonError;
+pub use lazy::LazyTable;
+pub use length::LengthConstraint;
+pub use lint::{Lint, LintKind};
+pub use matrix::{equiv_matrix, EquivMatrix, MatrixEntry};
+pub use memory::{BuildMemory, MemoryStats};
+pub use monoid::{Monoid, MonoidError};
+pub use names::NameMap;
+pub use navigate::{Configuration, Navigator};
+pub use nerode::NerodeClass;
+pub use nfa::{DeterminizeError, Nfa};
+pub use observe::DiscoveredState;
+pub use pretty::FormatOptions;
+pub use program_stats::ProgramStats;
+pub use quotient::{QuotientConflict, QuotientError};
+pub use reduce::{reduce, same_language, REDUCE_STATE_LIMIT};
+pub use regex::RegexMismatch;
+pub use report::{BuildReport, MinimizeReport};
+pub use resume::PartialBuild;
+pub use sweep::{sweep, sweep_with, SweepEntry, SweepResult};
+pub use synth::SynthesisError;
+pub use trace::TraceMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Instruction {
     MoveLeft,
     MoveRight,
@@ -33,24 +141,179 @@ impl Instruction {
             _ => None,
         }
     }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::MoveLeft => '<',
+            Self::MoveRight => '>',
+            Self::Increment => '+',
+            Self::Decrement => '-',
+            Self::StartLoop => '[',
+            Self::EndLoop => ']',
+            Self::Read => ',',
+            Self::Accept => '.',
+        }
+    }
+}
+
+/// Byte range of an instruction in the program text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Program {
     pub cell_count: NonZeroUsize,
     pub instructions: Vec<Instruction>,
+    /// Source location of each instruction, or empty if the program was not parsed from text.
+    pub spans: Vec<Span>,
 }
 
 impl Program {
     pub fn new(program_text: &str, cell_count: NonZeroUsize) -> Self {
-        let instructions = program_text
-            .chars()
-            .filter_map(Instruction::from_char)
-            .collect();
+        Self::parse(program_text, cell_count, &ProgramOptions::default())
+            .expect("the usual syntax has no errors")
+    }
 
-        Self {
-            cell_count,
-            instructions,
+    /// Runs the machine directly on `input`, without building a table. The verdict always
+    /// agrees with [`Table::accepts`] on the built table.
+    pub fn accepts(&self, input: &[u8]) -> Result<bool, RunError> {
+        if let Some(position) = input.iter().position(|&symbol| symbol > 0x0F) {
+            return Err(RunError::InvalidSymbol {
+                position,
+                symbol: input[position],
+            });
+        }
+
+        let mut seen_states = HashMap::with_hasher(FxBuildHasher);
+        let mut stats = RunStats::default();
+        let mut state =
+            self.run_with_next_input(self.initial_state(), 0, &mut seen_states, &mut stats);
+        for &symbol in input {
+            let Some(inner) = state.inner else {
+                break;
+            };
+            seen_states.clear();
+            state = self.run_with_next_input(inner, symbol, &mut seen_states, &mut stats);
+        }
+
+        Ok(state.accepting)
+    }
+
+    /// Searches breadth-first over machine configurations for an accepted input of length at
+    /// most `max_len`. The witness is the shortest one, and the smallest such in lexicographic
+    /// order.
+    pub fn find_accepted(&self, max_len: usize) -> Option<Vec<u8>> {
+        let mut seen_states = HashMap::with_hasher(FxBuildHasher);
+        let mut stats = RunStats::default();
+        let start = self.run_with_next_input(self.initial_state(), 0, &mut seen_states, &mut stats);
+        seen_states.clear();
+        if start.accepting {
+            return Some(Vec::new());
+        }
+
+        // (parent node, symbol read to get here) for every configuration reached
+        let mut nodes: Vec<(usize, u8)> = vec![(0, 0)];
+        let mut visited = HashSet::with_hasher(FxBuildHasher);
+        let mut frontier = VecDeque::new();
+        visited.insert(start.clone());
+        frontier.push_back((start, 0, 0));
+
+        while let Some((current, node, depth)) = frontier.pop_front() {
+            if depth == max_len {
+                break;
+            }
+            let Some(inner) = &current.inner else {
+                continue;
+            };
+            for input in 0..16 {
+                let next =
+                    self.run_with_next_input(inner.clone(), input, &mut seen_states, &mut stats);
+                seen_states.clear();
+                if visited.contains(&next) {
+                    continue;
+                }
+
+                nodes.push((node, input));
+                if next.accepting {
+                    let mut witness = Vec::with_capacity(depth + 1);
+                    let mut node = nodes.len() - 1;
+                    while node != 0 {
+                        let (parent, symbol) = nodes[node];
+                        witness.push(symbol);
+                        node = parent;
+                    }
+                    witness.reverse();
+                    return Some(witness);
+                }
+                visited.insert(next.clone());
+                frontier.push_back((next, nodes.len() - 1, depth + 1));
+            }
+        }
+
+        None
+    }
+
+    /// Searches breadth-first over machine configurations for the shortest input of length at
+    /// most `max_len` after which the machine loops forever without reading, the smallest such
+    /// in lexicographic order. Halting machines are not counted, even though both end up in the
+    /// dead state of a [`Table`].
+    pub fn shortest_diverging_input(&self, max_len: usize) -> Option<Vec<u8>> {
+        let mut seen_states = HashMap::with_hasher(FxBuildHasher);
+        let mut stats = RunStats::default();
+        let start = self.run_with_next_input(self.initial_state(), 0, &mut seen_states, &mut stats);
+        seen_states.clear();
+        if stats.divergences > 0 {
+            return Some(Vec::new());
+        }
+
+        // (parent node, symbol read to get here) for every configuration reached
+        let mut nodes: Vec<(usize, u8)> = vec![(0, 0)];
+        let mut visited = HashSet::with_hasher(FxBuildHasher);
+        let mut frontier = VecDeque::new();
+        visited.insert(start.clone());
+        frontier.push_back((start, 0, 0));
+
+        while let Some((current, node, depth)) = frontier.pop_front() {
+            if depth == max_len {
+                break;
+            }
+            let Some(inner) = &current.inner else {
+                continue;
+            };
+            for input in 0..16 {
+                let mut stats = RunStats::default();
+                let next =
+                    self.run_with_next_input(inner.clone(), input, &mut seen_states, &mut stats);
+                seen_states.clear();
+                if stats.divergences > 0 {
+                    let mut witness = vec![input];
+                    let mut node = node;
+                    while node != 0 {
+                        let (parent, symbol) = nodes[node];
+                        witness.push(symbol);
+                        node = parent;
+                    }
+                    witness.reverse();
+                    return Some(witness);
+                }
+                if visited.insert(next.clone()) {
+                    nodes.push((node, input));
+                    frontier.push_back((next, nodes.len() - 1, depth + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn initial_state(&self) -> InnerState {
+        InnerState {
+            cells: U4Vec(smallvec![0; self.cell_count.get().div_ceil(2)]),
+            head_position: 0,
+            instruction_position: 0,
         }
     }
 
@@ -58,15 +321,28 @@ impl Program {
         &self,
         mut state: InnerState,
         input: u8,
-        seen_states: &mut HashMap<InnerState, (), FxBuildHasher>,
+        seen_states: &mut HashMap<InnerState, (), impl BuildHasher>,
+        stats: &mut RunStats,
     ) -> State {
         state.cells.set(state.head_position, input);
         let mut accepting = false;
 
         'outer: while let Some(&intruction) = self.instructions.get(state.instruction_position) {
+            stats.steps += 1;
+            if let Some(executed) = stats.executed.get_mut(state.instruction_position) {
+                *executed = true;
+            }
+            if let Some(trace) = &mut stats.trace {
+                if trace.len() < stats.trace_limit {
+                    trace.push(state.instruction_position);
+                } else {
+                    stats.trace_truncated = true;
+                }
+            }
             match intruction {
                 Instruction::MoveLeft => {
                     if state.head_position == 0 {
+                        stats.record_tape_wrap(state.instruction_position);
                         state.head_position = self.cell_count.get() - 1;
                     } else {
                         state.head_position -= 1;
@@ -74,22 +350,25 @@ impl Program {
                 }
                 Instruction::MoveRight => {
                     if state.head_position == self.cell_count.get() - 1 {
+                        stats.record_tape_wrap(state.instruction_position);
                         state.head_position = 0;
                     } else {
                         state.head_position += 1;
                     }
                 }
                 Instruction::Increment => {
-                    state.cells.set(
-                        state.head_position,
-                        state.cells.get(state.head_position) + 1,
-                    );
+                    let value = state.cells.get(state.head_position);
+                    if value == 0x0F {
+                        stats.record_arithmetic_wrap(state.instruction_position, true);
+                    }
+                    state.cells.set(state.head_position, value + 1);
                 }
                 Instruction::Decrement => {
-                    state.cells.set(
-                        state.head_position,
-                        state.cells.get(state.head_position).wrapping_sub(1),
-                    );
+                    let value = state.cells.get(state.head_position);
+                    if value == 0 {
+                        stats.record_arithmetic_wrap(state.instruction_position, false);
+                    }
+                    state.cells.set(state.head_position, value.wrapping_sub(1));
                 }
                 Instruction::EndLoop => {
                     // unconditional!
@@ -140,7 +419,10 @@ impl Program {
                         }
                     } else {
                         match seen_states.entry(state.clone()) {
-                            Entry::Occupied(_) => break 'outer,
+                            Entry::Occupied(_) => {
+                                stats.divergences += 1;
+                                break 'outer;
+                            }
                             Entry::Vacant(slot) => slot.insert(()),
                         };
                     }
@@ -165,9 +447,39 @@ impl Program {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunError {
+    /// Input symbols are hexadecimal digits, so anything above `0xF` is rejected.
+    InvalidSymbol { position: usize, symbol: u8 },
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSymbol { position, symbol } => {
+                write!(
+                    f,
+                    "input symbol {symbol:#X} at position {position} is not a hex digit"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+/// Bytes of tape stored inline before spilling to the heap, 32 cells, or 64 with the
+/// `wide-tape` feature. Longer tapes are copied to the heap with every state, but a bigger
+/// inline tape makes every state bigger. Fixed rather than derived from the pointer width so
+/// that memory use is the same on every target.
+#[cfg(not(feature = "wide-tape"))]
+const U4VEC_INLINE_BYTES: usize = 16;
+#[cfg(feature = "wide-tape")]
+const U4VEC_INLINE_BYTES: usize = 32;
+
 #[repr(transparent)]
 #[derive(Eq, Hash, PartialEq, Clone, Debug)]
-struct U4Vec(SmallVec<u8, { std::mem::size_of::<usize>() * 2 }>);
+struct U4Vec(SmallVec<u8, U4VEC_INLINE_BYTES>);
 
 impl U4Vec {
     #[inline]
@@ -202,72 +514,558 @@ struct InnerState {
     instruction_position: usize,
 }
 
+impl InnerState {
+    /// Rotates the tape so that the head is on cell 0.
+    fn rotate_head_to_start(&mut self, cell_count: usize) {
+        if self.head_position == 0 {
+            return;
+        }
+        let mut cells = self.cells.clone();
+        for position in 0..cell_count {
+            cells.set(
+                position,
+                self.cells.get((position + self.head_position) % cell_count),
+            );
+        }
+        self.cells = cells;
+        self.head_position = 0;
+    }
+}
+
 #[derive(Eq, Hash, PartialEq, Clone, Debug)]
 struct State {
     inner: Option<InnerState>,
     accepting: bool,
 }
 
-#[derive(Debug)]
+#[derive(Default)]
+struct RunStats {
+    steps: u64,
+    divergences: u64,
+    /// Executed instruction positions, recorded only when present.
+    trace: Option<Vec<usize>>,
+    trace_limit: usize,
+    trace_truncated: bool,
+    /// Instructions run so far, tracked only when not empty.
+    executed: Vec<bool>,
+    /// Moves that wrapped around the tape, tracked only when `tape_wrap_sites` is not empty.
+    tape_wraps: u64,
+    tape_wrap_sites: Vec<bool>,
+    /// `+` on 15 and `-` on 0, tracked only when `arithmetic_wrap_sites` is not empty.
+    increment_wraps: u64,
+    decrement_wraps: u64,
+    arithmetic_wrap_sites: Vec<bool>,
+}
+
+impl RunStats {
+    fn record_tape_wrap(&mut self, position: usize) {
+        if let Some(site) = self.tape_wrap_sites.get_mut(position) {
+            *site = true;
+            self.tape_wraps += 1;
+        }
+    }
+
+    fn record_arithmetic_wrap(&mut self, position: usize, increment: bool) {
+        if let Some(site) = self.arithmetic_wrap_sites.get_mut(position) {
+            *site = true;
+            if increment {
+                self.increment_wraps += 1;
+            } else {
+                self.decrement_wraps += 1;
+            }
+        }
+    }
+}
+
+/// Positions of the set flags.
+fn positions(flags: &[bool]) -> Vec<usize> {
+    flags
+        .iter()
+        .enumerate()
+        .filter_map(|(position, &flag)| if flag { Some(position) } else { None })
+        .collect()
+}
+
+/// Debug builds cross-check [`Table::minimize`] against [`Table::minimize_reference`] on
+/// tables with at most this many states.
+#[cfg(debug_assertions)]
+const REFERENCE_CHECK_STATES: usize = 512;
+
+/// Order in which [`Table::build`] explores newly discovered states.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ExploreOrder {
+    /// Ids are assigned breadth-first from the start state, trying inputs `0` to `F` in order.
+    #[default]
+    Bfs,
+    /// Ids are assigned in discovery order while exploring the most recently discovered state
+    /// first. This is the numbering produced by bfa 0.1 and will be removed in a later release.
+    Dfs,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BuildOptions {
+    pub order: ExploreOrder,
+    /// Give up once more states than this have been discovered.
+    pub max_states: Option<usize>,
+    /// Give up once the build has run for this long.
+    pub time_limit: Option<Duration>,
+    /// Cap on the instructions recorded per transition by [`Table::build_traced_with`].
+    pub trace_limit: Option<usize>,
+    /// Count the moves that wrap around the tape, see [`BuildReport::tape_wraps`].
+    pub track_tape_wrap: bool,
+    /// Count the `+` and `-` that wrap around, see [`BuildReport::increment_wraps`].
+    pub track_arithmetic_wrap: bool,
+    /// Treat configurations that are rotations of each other around the tape as the same
+    /// state, which can divide the state count by up to the cell count. The tape is a ring
+    /// and no instruction sees the absolute head position, so rotating the tape with the head
+    /// never changes what the machine does and the language stays the same. Ignored with
+    /// `track_tape_wrap`, since the wrap counts depend on where the ends of the tape are, and
+    /// by [`Table::build_observed`], which shows each configuration as the machine has it.
+    pub rotation_symmetry: bool,
+    pub hasher: StateHasher,
+    /// Every time this many more states have been discovered, merge the equivalent states
+    /// among those whose every successor, recursively, has been expanded, and forget the
+    /// configurations of the states merged away. Fewer configurations and rows are held at
+    /// once, and the table handed back leaves less work for [`Table::minimize`], but a
+    /// forgotten configuration is explored again if it's reached again, so more states can
+    /// be explored in all. Depth-first exploration settles states much sooner than
+    /// breadth-first, which settles little before the end. The language, and so the
+    /// minimized table up to numbering, is the same. Ignored by [`Table::build_traced_with`]
+    /// and [`Table::build_observed`], whose ids must stay fixed.
+    pub merge_every: Option<usize>,
+}
+
+/// The hash function for the maps of configurations kept while building.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StateHasher {
+    /// FxHash, which is the fastest but easy to find collisions for, so a program crafted
+    /// to collide can make every lookup linear in the number of states.
+    #[default]
+    Fx,
+    /// The standard library's SipHash with random keys, which resists such programs at the
+    /// cost of a slower build. Use this for programs from untrusted sources.
+    Random,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    StateLimit {
+        limit: usize,
+    },
+    TimeLimit {
+        limit: Duration,
+    },
+    /// The callback given to [`Table::build_observed`] asked to stop, after `states` states
+    /// had been discovered.
+    Aborted {
+        states: usize,
+    },
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StateLimit { limit } => write!(f, "build exceeded the limit of {limit} states"),
+            Self::TimeLimit { limit } => write!(f, "build exceeded the time limit of {limit:?}"),
+            Self::Aborted { states } => write!(f, "build aborted after {states} states"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+type Row = (bool, [usize; 16]);
+
+/// Blocks of equivalent states found by [`Table::refine`].
+pub(crate) struct Refinement {
+    /// Block of each state.
+    pub(crate) partition: Vec<usize>,
+    /// Smallest state of each block.
+    pub(crate) reps: Vec<usize>,
+    pub(crate) iterations: usize,
+}
+
+#[derive(Clone, Debug)]
 pub struct Table {
-    states: Vec<(bool, [usize; 16])>,
+    /// Distinct `(accepting, transitions)` rows.
+    rows: Vec<Row>,
+    /// Row of each state, or empty when every state owns the row with its own index.
+    row_ids: Vec<usize>,
+    /// Interpreter steps of each transition, if the table came from a build.
+    costs: Vec<[u32; 16]>,
 }
 
 impl Table {
+    /// Placeholder target for a transition that an imported partial table leaves undefined.
+    pub const MISSING: usize = usize::MAX;
+
+    /// State ids are numbered as described by [`ExploreOrder::Bfs`], with the start state at 0.
+    /// The numbering only depends on the exploration order, never on hashing, so it's the same
+    /// on every target.
     pub fn build(program: &Program) -> Self {
-        let mut state_ids = HashMap::with_hasher(FxBuildHasher);
-        let mut table = Self { states: vec![] };
-        let mut exploration_stack: Vec<State> = Vec::new();
+        Self::build_with_report(program).0
+    }
 
-        let mut seen_states = HashMap::with_hasher(FxBuildHasher);
+    pub fn build_with_report(program: &Program) -> (Self, BuildReport) {
+        Self::build_with(program, &BuildOptions::default()).expect("no limits are set by default")
+    }
 
-        let start = program.run_with_next_input(
-            InnerState {
-                cells: U4Vec(smallvec![0; program.cell_count.get().div_ceil(2)]),
-                head_position: 0,
-                instruction_position: 0,
+    pub fn build_with(
+        program: &Program,
+        options: &BuildOptions,
+    ) -> Result<(Self, BuildReport), BuildError> {
+        Self::explore(program, options, None, None).map(|(table, report, _)| (table, report))
+    }
+
+    /// Shared by the builders: also reports which states are halted machines, records the
+    /// instructions executed by each transition into `traces` if given, and passes each new
+    /// state to `on_discovered` if given.
+    pub(crate) fn explore(
+        program: &Program,
+        options: &BuildOptions,
+        traces: Option<&mut TraceMap>,
+        on_discovered: Option<Observer<'_>>,
+    ) -> Result<(Self, BuildReport, Vec<bool>), BuildError> {
+        match options.hasher {
+            StateHasher::Fx => {
+                Self::explore_hashed::<FxBuildHasher>(program, options, traces, on_discovered)
+            }
+            StateHasher::Random => {
+                Self::explore_hashed::<RandomState>(program, options, traces, on_discovered)
+            }
+        }
+    }
+
+    fn explore_hashed<S: BuildHasher + Default>(
+        program: &Program,
+        options: &BuildOptions,
+        mut traces: Option<&mut TraceMap>,
+        mut on_discovered: Option<Observer<'_>>,
+    ) -> Result<(Self, BuildReport, Vec<bool>), BuildError> {
+        let started = Instant::now();
+        let cell_count = program.cell_count.get();
+        // a tape the head can't cover gives the same table, with fewer cells to copy and hash,
+        // but tracking wrap-around needs the real tape since the shrunk one wraps sooner, and
+        // observers are shown the configurations on the real tape
+        let observed = on_discovered.is_some();
+        let shrunk = if options.track_tape_wrap || observed {
+            None
+        } else {
+            program.shrunk_tape()
+        };
+        let program = shrunk.as_ref().unwrap_or(program);
+        let mut merger = options
+            .merge_every
+            .filter(|_| traces.is_none() && on_discovered.is_none())
+            .map(Merger::new);
+        let mut state_ids = HashMap::with_hasher(S::default());
+        let mut table = Self {
+            rows: vec![],
+            row_ids: vec![],
+            costs: vec![],
+        };
+        let mut row_pool: HashMap<Row, usize, S> = HashMap::with_hasher(S::default());
+        let mut frontier: VecDeque<State> = VecDeque::new();
+        let mut peak_frontier = 0;
+        let mut memory = BuildMemory::default();
+        let (mut state_heap, mut frontier_heap) = (0, 0);
+        let mut expanded = 0;
+
+        let mut seen_states = HashMap::with_hasher(S::default());
+        let mut stats = RunStats {
+            executed: vec![false; program.instructions.len()],
+            tape_wrap_sites: if options.track_tape_wrap {
+                vec![false; program.instructions.len()]
+            } else {
+                Vec::new()
+            },
+            arithmetic_wrap_sites: if options.track_arithmetic_wrap {
+                vec![false; program.instructions.len()]
+            } else {
+                Vec::new()
             },
+            ..RunStats::default()
+        };
+
+        let rotate = options.rotation_symmetry && !options.track_tape_wrap && !observed;
+        let canonical = |mut state: State| {
+            if let (true, Some(inner)) = (rotate, &mut state.inner) {
+                inner.rotate_head_to_start(program.cell_count.get());
+            }
+            state
+        };
+
+        let start = canonical(program.run_with_next_input(
+            program.initial_state(),
             0,
             &mut seen_states,
-        );
+            &mut stats,
+        ));
         seen_states.clear();
 
-        exploration_stack.push(start.clone());
-        table.states.push((start.accepting, [0; 16]));
+        let mut discover = |id: usize, state: &State| match on_discovered.as_deref_mut() {
+            Some(on_discovered) => {
+                let state = DiscoveredState { state, cell_count };
+                match on_discovered(id, &state) {
+                    ControlFlow::Continue(()) => Ok(()),
+                    ControlFlow::Break(()) => Err(BuildError::Aborted { states: id + 1 }),
+                }
+            }
+            None => Ok(()),
+        };
+        discover(0, &start)?;
+        if let Some(merger) = &mut merger {
+            merger.discovered();
+        }
+        state_heap += start.heap_bytes();
+        frontier_heap += start.heap_bytes();
+        frontier.push_back(start.clone());
+        table.row_ids.push(0);
+        table.costs.push([0; 16]);
+        let mut halted = vec![start.inner.is_none()];
         state_ids.insert(start, 0);
 
-        while let Some(current) = exploration_stack.pop() {
+        while let Some(current) = match options.order {
+            ExploreOrder::Bfs => frontier.pop_front(),
+            ExploreOrder::Dfs => frontier.pop_back(),
+        } {
+            if let Some(limit) = options.time_limit {
+                if started.elapsed() > limit {
+                    return Err(BuildError::TimeLimit { limit });
+                }
+            }
+
+            frontier_heap -= current.heap_bytes();
             let current_id = *state_ids.get(&current).unwrap();
-            if current.inner.is_none() {
-                table.states[current_id] = (current.accepting, [current_id; 16]);
-                continue;
+            let mut edges = [current_id; 16];
+            if let Some(inner) = &current.inner {
+                for input in 0..16 {
+                    if traces.is_some() {
+                        stats.trace = Some(Vec::new());
+                        stats.trace_limit = options.trace_limit.unwrap_or(usize::MAX);
+                        stats.trace_truncated = false;
+                    }
+                    let steps_before = stats.steps;
+                    let next = canonical(program.run_with_next_input(
+                        inner.clone(),
+                        input,
+                        &mut seen_states,
+                        &mut stats,
+                    ));
+                    seen_states.clear();
+                    table.costs[current_id][input as usize] =
+                        u32::try_from(stats.steps - steps_before).unwrap_or(u32::MAX);
+                    if let Some(traces) = traces.as_deref_mut() {
+                        let trace = stats.trace.take().unwrap_or_default();
+                        traces.record(current_id, input, trace, stats.trace_truncated);
+                    }
+                    edges[input as usize] = match state_ids.entry(next) {
+                        Entry::Occupied(entry) => *entry.get(),
+                        Entry::Vacant(entry) => {
+                            let next_id = table.row_ids.len();
+                            discover(next_id, entry.key())?;
+                            if let Some(merger) = &mut merger {
+                                merger.discovered();
+                            }
+                            table.row_ids.push(0);
+                            table.costs.push([0; 16]);
+                            halted.push(entry.key().inner.is_none());
+                            state_heap += entry.key().heap_bytes();
+                            frontier_heap += entry.key().heap_bytes();
+                            frontier.push_back(entry.key().clone());
+                            *entry.insert(next_id)
+                        }
+                    };
+                }
             }
-            for input in 0..16 {
-                let next = program.run_with_next_input(
-                    current.inner.as_ref().unwrap().clone(),
-                    input,
-                    &mut seen_states,
+            if let Some(limit) = options.max_states {
+                if table.row_ids.len() > limit {
+                    return Err(BuildError::StateLimit { limit });
+                }
+            }
+
+            let row = (current.accepting, edges);
+            match &mut merger {
+                Some(merger) => merger.expanded(current_id, row, &mut state_ids, &mut state_heap),
+                None => {
+                    table.row_ids[current_id] = *row_pool.entry(row).or_insert_with(|| {
+                        table.rows.push(row);
+                        table.rows.len() - 1
+                    });
+                }
+            }
+            peak_frontier = peak_frontier.max(frontier.len());
+            expanded += 1;
+            if expanded % BuildMemory::SAMPLE_INTERVAL == 0 {
+                memory.sample(
+                    &state_ids,
+                    state_heap,
+                    &row_pool,
+                    merger.as_ref(),
+                    &frontier,
+                    frontier_heap,
                 );
-                seen_states.clear();
-                let next_id = state_ids.entry(next.clone()).or_insert_with(|| {
-                    table.states.push((next.accepting, [0; 16]));
-                    exploration_stack.push(next);
-                    table.states.len() - 1
-                });
-                table.states[current_id].1[input as usize] = *next_id;
             }
         }
+        memory.sample(
+            &state_ids,
+            state_heap,
+            &row_pool,
+            merger.as_ref(),
+            &frontier,
+            frontier_heap,
+        );
 
-        table
+        let states_explored = table.row_ids.len();
+        if let Some(merger) = merger {
+            (table, halted) = merger.finish(table.costs, halted);
+        }
+        let report = BuildReport {
+            states_explored,
+            unique_rows: table.rows.len(),
+            interpreter_steps: stats.steps,
+            divergences: stats.divergences,
+            peak_frontier,
+            peak_memory: Some(memory),
+            build_time: started.elapsed(),
+            minimize: None,
+            tape_wraps: options.track_tape_wrap.then_some(stats.tape_wraps),
+            tape_wrap_sites: positions(&stats.tape_wrap_sites),
+            increment_wraps: options
+                .track_arithmetic_wrap
+                .then_some(stats.increment_wraps),
+            decrement_wraps: options
+                .track_arithmetic_wrap
+                .then_some(stats.decrement_wraps),
+            arithmetic_wrap_sites: positions(&stats.arithmetic_wrap_sites),
+            executed: stats.executed,
+        };
+        Ok((table, report, halted))
+    }
+
+    pub(crate) fn from_rows(rows: Vec<Row>) -> Self {
+        Self {
+            rows,
+            row_ids: Vec::new(),
+            costs: Vec::new(),
+        }
+    }
+
+    fn row(&self, state: usize) -> &Row {
+        if self.row_ids.is_empty() {
+            &self.rows[state]
+        } else {
+            &self.rows[self.row_ids[state]]
+        }
+    }
+
+    /// Every state's row, in state order.
+    pub(crate) fn rows(&self) -> impl Iterator<Item = &Row> + '_ {
+        (0..self.state_count()).map(|state| self.row(state))
+    }
+
+    /// Gives every state its own row so that rows can be edited per state.
+    pub(crate) fn rows_mut(&mut self) -> &mut Vec<Row> {
+        if !self.row_ids.is_empty() {
+            self.rows = self.row_ids.iter().map(|&row| self.rows[row]).collect();
+            self.row_ids = Vec::new();
+        }
+        &mut self.rows
+    }
+
+    pub fn state_count(&self) -> usize {
+        if self.row_ids.is_empty() {
+            self.rows.len()
+        } else {
+            self.row_ids.len()
+        }
     }
 
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.row(state).0
+    }
+
+    pub fn transitions(&self, state: usize) -> &[usize; 16] {
+        &self.row(state).1
+    }
+
+    pub fn transition(&self, state: usize, input: u8) -> usize {
+        self.row(state).1[(input & 0x0F) as usize]
+    }
+
+    /// Runs the input from the start state; only the low four bits of each symbol are used.
+    /// A missing transition rejects the input.
+    pub fn accepts(&self, input: &[u8]) -> bool {
+        let mut state = 0;
+        for &symbol in input {
+            state = self.transition(state, symbol);
+            if state == Self::MISSING {
+                return false;
+            }
+        }
+        self.is_accepting(state)
+    }
+
+    /// Partially defined tables are completed with [`Table::make_complete`] first.
     pub fn minimize(&mut self) {
-        let mut partition: Vec<usize> = vec![0; self.states.len()];
+        self.minimize_with_report();
+    }
+
+    pub fn minimize_with_report(&mut self) -> MinimizeReport {
+        let started = Instant::now();
+        self.make_complete();
+        let blocks_before = self.state_count();
+        // the reference run, like the check below, only exists in debug builds
+        #[cfg(debug_assertions)]
+        let reference = (blocks_before <= REFERENCE_CHECK_STATES)
+            .then(|| self.minimize_reference().state_count());
+        let refinement = self.refine();
+        *self = self.collapse(&refinement);
+        #[cfg(debug_assertions)]
+        if let Some(reference) = reference {
+            debug_assert_eq!(
+                self.state_count(),
+                reference,
+                "minimize disagrees with minimize_reference"
+            );
+        }
+
+        MinimizeReport {
+            blocks_before,
+            blocks_after: self.state_count(),
+            refinement_iterations: refinement.iterations,
+            minimize_time: started.elapsed(),
+        }
+    }
+
+    /// Like [`Table::minimize`], but leaves `self` untouched.
+    pub fn minimized(&self) -> Table {
+        if !self.is_complete() {
+            let mut complete = self.clone();
+            complete.make_complete();
+            return complete.minimized();
+        }
+        self.collapse(&self.refine())
+    }
+
+    /// For each state, the state of [`Table::minimized`] it's merged into.
+    pub fn minimize_mapping(&self) -> Vec<usize> {
+        let mut partition = self.completed().refine().partition;
+        // completing may have added a sink, which isn't one of this table's states
+        partition.truncate(self.state_count());
+        partition
+    }
+
+    /// Splits the states of a complete table into blocks of equivalent states. Block 0 holds the
+    /// start state and every block is represented by its smallest state.
+    pub(crate) fn refine(&self) -> Refinement {
+        let mut refinement_iterations = 0;
+        let mut partition: Vec<usize> = vec![0; self.state_count()];
         let mut partition_reps = vec![0];
 
-        let initial_accepting = self.states[0].0;
+        let initial_accepting = self.is_accepting(0);
         let mut seen_different = false;
-        for (id, (accepting, _)) in self.states.iter().enumerate() {
+        for (id, (accepting, _)) in self.rows().enumerate() {
             if *accepting != initial_accepting {
                 partition[id] = 1;
                 if !seen_different {
@@ -278,34 +1076,52 @@ impl Table {
         }
 
         let mut queue: Vec<usize> = Vec::new();
+        // how many times each block is in the queue, which may hold duplicates
+        let mut queued = vec![0usize; self.state_count() + 1];
         queue.push(0);
+        queued[0] += 1;
         if seen_different {
             queue.push(1);
+            queued[1] += 1;
         }
 
+        // scratch space reused across iterations
+        let mut in_splitter = vec![false; self.state_count()];
+        let mut in_preimage = vec![false; self.state_count()];
+        let mut intersection = Vec::new();
+        let mut remainder = Vec::new();
+
         while let Some(current) = queue.pop() {
+            queued[current] -= 1;
+            refinement_iterations += 1;
+            // the splitter as it was when popped, since it may be split by its own preimages
+            for (state, &id) in partition.iter().enumerate() {
+                in_splitter[state] = id == current;
+            }
             for input in 0..16 {
-                let preimage: HashSet<usize, FxBuildHasher> = self
-                    .states
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, (_, trans))| partition[trans[input]] == current)
-                    .map(|(i, _)| i)
-                    .collect();
+                for (state, (_, trans)) in self.rows().enumerate() {
+                    in_preimage[state] = in_splitter[trans[input]];
+                }
 
                 for part in 0..partition_reps.len() {
-                    let (intersection, remainder): (Vec<usize>, Vec<usize>) = partition
-                        .iter()
-                        .enumerate()
-                        .filter_map(|(state, &id)| if id == part { Some(state) } else { None })
-                        .partition(|state| preimage.contains(state));
+                    intersection.clear();
+                    remainder.clear();
+                    for (state, &id) in partition.iter().enumerate() {
+                        if id == part {
+                            if in_preimage[state] {
+                                intersection.push(state);
+                            } else {
+                                remainder.push(state);
+                            }
+                        }
+                    }
 
                     if intersection.is_empty() || remainder.is_empty() {
                         continue;
                     }
 
-                    let lower;
-                    let higher;
+                    let lower: &Vec<usize>;
+                    let higher: &Vec<usize>;
                     let inter_id;
                     let remain_id;
 
@@ -321,80 +1137,412 @@ impl Table {
                         remain_id = part;
                     }
 
+                    let new_id = partition_reps.len();
                     for &state in higher {
-                        partition[state] = partition_reps.len();
+                        partition[state] = new_id;
                     }
 
                     partition_reps.push(higher[0]);
                     partition_reps[part] = lower[0];
 
-                    if queue.contains(&inter_id) {
-                        queue.push(remain_id);
+                    // a queued block still has to split by both halves, `part` now naming the
+                    // lower one; otherwise the smaller half is enough
+                    let pushed = if queued[part] > 0 {
+                        new_id
                     } else if intersection.len() <= remainder.len() {
-                        queue.push(inter_id);
+                        inter_id
                     } else {
-                        queue.push(remain_id);
-                    }
+                        remain_id
+                    };
+                    queue.push(pushed);
+                    queued[pushed] += 1;
                 }
             }
         }
 
-        let mut new_states = Vec::with_capacity(partition_reps.len());
-        for old_id in partition_reps {
-            for edge in &mut self.states[old_id].1 {
-                *edge = partition[*edge];
+        Refinement {
+            partition,
+            reps: partition_reps,
+            iterations: refinement_iterations,
+        }
+    }
+
+    /// Merges every block of the refinement into its representative.
+    pub(crate) fn collapse(&self, refinement: &Refinement) -> Table {
+        let mut rows: Vec<Row> = refinement
+            .reps
+            .iter()
+            .map(|&old_id| {
+                let (accepting, edges) = *self.row(old_id);
+                (accepting, edges.map(|edge| refinement.partition[edge]))
+            })
+            .collect();
+        rows.shrink_to_fit();
+        Table::from_rows(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{
+        verify, BuildOptions, EquivResult, ExploreOrder, Program, RunError, StateHasher, Table,
+        U4VEC_INLINE_BYTES,
+    };
+
+    /// The programs and cell counts of the benchmarks, for tests across the crate.
+    pub(crate) const BENCHMARKS: &[(&str, usize)] = &[
+        ("+[>,,.<]", 2),
+        (",>,[-<->]<[>.,<]", 2),
+        ("+[>,]+[[.,]+]", 3),
+        (">+[>.,[>]<<]", 3),
+        ("+[>.,[<->[-]]<[,]+]", 2),
+        (",>>+[.[,<<[->+>-<<]>[-<+>]>]+]", 3),
+        (",[-[-]]]", 1),
+    ];
+
+    pub(crate) fn benchmarks() -> impl Iterator<Item = Program> {
+        BENCHMARKS
+            .iter()
+            .map(|&(text, cells)| Program::new(text, NonZeroUsize::new(cells).unwrap()))
+    }
+
+    /// Programs, cell counts and minimal state counts that minimization once got wrong, along
+    /// with inputs they were wrong on.
+    const REGRESSIONS: &[(&str, usize, usize, &[u8])] = &[
+        (".,>>+[.[,<<[->+>-<<]>[-<+>]>]+]", 3, 33, &[2, 2]),
+        (",>>+[.[,<<[->+>-<<]>[-<+>]>]+]", 3, 33, &[2, 2]),
+        ("-<,[-<+],+[,-[+[.,<]..],]", 2, 12, &[0, 0, 1, 1, 0]),
+    ];
+
+    /// Accepts the inputs without a 0, which lead to the rejecting sink 1.
+    pub(crate) fn no_zeros() -> Table {
+        let mut start = [0; 16];
+        start[0] = 1;
+        Table::from_rows(vec![(true, start), (false, [1; 16])])
+    }
+
+    /// Accepts exactly `word`, with a rejecting sink after the last state.
+    pub(crate) fn exactly(word: &[u8]) -> Table {
+        let sink = word.len() + 1;
+        let mut rows: Vec<_> = (0..=word.len())
+            .map(|state| {
+                let mut edges = [sink; 16];
+                if let Some(&symbol) = word.get(state) {
+                    edges[symbol as usize] = state + 1;
+                }
+                (state == word.len(), edges)
+            })
+            .collect();
+        rows.push((false, [sink; 16]));
+        Table::from_rows(rows)
+    }
+
+    /// Every input of length at most `max_len`, shortest first and then in lexicographic order.
+    pub(crate) fn short_inputs(max_len: usize) -> impl Iterator<Item = Vec<u8>> {
+        (0..=max_len).flat_map(|len| {
+            (0..1usize << (4 * len)).map(move |n| {
+                (0..len)
+                    .map(|i| ((n >> (4 * (len - 1 - i))) & 0x0F) as u8)
+                    .collect()
+            })
+        })
+    }
+
+    fn minimized(program: &Program, order: ExploreOrder) -> Table {
+        let options = BuildOptions {
+            order,
+            ..BuildOptions::default()
+        };
+        let (mut table, _) = Table::build_with(program, &options).unwrap();
+        table.minimize();
+        table
+    }
+
+    #[test]
+    fn minimize_regressions() {
+        for &(program_text, cells, states, input) in REGRESSIONS {
+            let program = Program::new(program_text, NonZeroUsize::new(cells).unwrap());
+            for order in [ExploreOrder::Bfs, ExploreOrder::Dfs] {
+                let table = minimized(&program, order);
+                assert_eq!(table.state_count(), states, "{program_text} with {order:?}");
+                assert_eq!(
+                    table.accepts(input),
+                    verify::interpret(&program, input),
+                    "{program_text} with {order:?}"
+                );
+                verify::exhaustive_check(&program, &table, 3).unwrap();
             }
-            new_states.push(self.states[old_id]);
         }
+    }
 
-        new_states.shrink_to_fit();
-        self.states = new_states;
+    #[test]
+    fn minimized_dot_is_locked() {
+        let minimized = |text: &str, cells: usize| {
+            Table::build(&Program::new(text, NonZeroUsize::new(cells).unwrap()))
+                .minimized()
+                .dot()
+        };
+        // block ids follow the order in which the blocks were split off
+        assert_eq!(
+            minimized("+[>,,.<]", 2),
+            "digraph G {\n    0 -> 2 [label=\"0-F\"];\n    1 -> 2 [label=\"0-F\"];\n    \
+             2 -> 1 [label=\"0-F\"];\n    1[peripheries=2];\n}\n"
+        );
+        assert_eq!(
+            minimized("+[>.,[<->[-]]<[,]+]", 2),
+            "digraph G {\n    0 -> 0 [label=\"1-F\"];\n    0 -> 1 [label=\"0\"];\n    \
+             1 -> 0 [label=\"0\"];\n    1 -> 1 [label=\"1-F\"];\n    0[peripheries=2];\n}\n"
+        );
     }
 
-    pub fn dot(&self) -> String {
-        let mut output = "digraph G {\n".to_string();
+    #[test]
+    fn readme_example_matches_first_and_last_symbol() {
+        let program = Program::new(
+            ".,>>+[.[,<<[->+>-<<]>[-<+>]>]+]",
+            NonZeroUsize::new(3).unwrap(),
+        );
+        let table = minimized(&program, ExploreOrder::default());
+        assert!(table.accepts(&[]));
+        assert!(table.accepts(&[2, 2]));
+        assert!(table.accepts(&[2, 1, 2]));
+        assert!(!table.accepts(&[2, 1]));
+    }
 
-        for (from, (_, edges)) in self.states.iter().enumerate() {
-            for maybe_to in 0..self.states.len() {
-                let mut empty = true;
-                let mut run_start = None;
-                let mut end_run = |run_start: &mut Option<usize>, input| {
-                    if run_start.is_some() {
-                        let start = run_start.take().unwrap();
-                        if empty {
-                            empty = false;
-                            write!(&mut output, "    {from} -> {maybe_to} [label=\"").unwrap();
-                        }
-                        if input - start < 4 {
-                            for n in start..input {
-                                write!(&mut output, "{n:X}").unwrap();
-                            }
-                        } else {
-                            write!(&mut output, "{start:X}-{:X}", input - 1).unwrap();
-                        }
-                    }
+    #[test]
+    fn state_ids_follow_the_exploration_order() {
+        let program = Program::new(",[->,<]", NonZeroUsize::new(2).unwrap());
+        let bfs = Table::build(&program);
+        let (dfs, _) = Table::build_with(
+            &program,
+            &BuildOptions {
+                order: ExploreOrder::Dfs,
+                ..BuildOptions::default()
+            },
+        )
+        .unwrap();
+        let first_reads: [usize; 16] = std::array::from_fn(|input| input + 1);
+        for table in [&bfs, &dfs] {
+            assert_eq!(table.state_count(), 227);
+            assert_eq!(table.transitions(0), &first_reads);
+            assert_eq!(table.transitions(1), &[1; 16]);
+            assert_eq!(table.transitions(2), &[1; 16]);
+        }
+        // reading 0 into the second cell leads back to state 2 and the other inputs to new
+        // states, which come right after the successors of state 0 breadth-first, but after
+        // those of states 4 to 16 depth-first
+        let expected: [usize; 16] = std::array::from_fn(|input| match input {
+            0 => 2,
+            _ => 16 + input,
+        });
+        assert_eq!(bfs.transitions(3), &expected);
+        let expected: [usize; 16] = std::array::from_fn(|input| match input {
+            0 => 2,
+            _ => 211 + input,
+        });
+        assert_eq!(dfs.transitions(3), &expected);
+    }
+
+    #[test]
+    fn exploration_orders_agree() {
+        for program in benchmarks() {
+            let build = |order| {
+                let options = BuildOptions {
+                    order,
+                    ..BuildOptions::default()
                 };
-                for (input, &to) in edges.iter().enumerate() {
-                    if to == maybe_to && run_start.is_none() {
-                        run_start = Some(input);
-                    } else if to != maybe_to {
-                        end_run(&mut run_start, input);
-                    }
-                }
-                end_run(&mut run_start, 16);
-                if !empty {
-                    writeln!(&mut output, "\"];").unwrap();
-                }
+                Table::build_with(&program, &options).unwrap().0
+            };
+            let (bfs, dfs) = (build(ExploreOrder::Bfs), build(ExploreOrder::Dfs));
+            assert_eq!(bfs.state_count(), dfs.state_count());
+            assert!(matches!(bfs.compare(&dfs), EquivResult::Equivalent { .. }));
+            assert!(bfs.canonical().rows().eq(dfs.canonical().rows()));
+        }
+    }
+
+    #[test]
+    fn rotation_symmetry_keeps_the_language() {
+        let build = |program: &Program, rotation_symmetry, track_tape_wrap| {
+            let options = BuildOptions {
+                rotation_symmetry,
+                track_tape_wrap,
+                ..BuildOptions::default()
+            };
+            Table::build_with(program, &options).unwrap()
+        };
+        for program in benchmarks() {
+            let (plain, plain_report) = build(&program, false, false);
+            let (rotated, rotated_report) = build(&program, true, false);
+            assert!(rotated_report.states_explored <= plain_report.states_explored);
+            assert!(plain.canonical().rows().eq(rotated.canonical().rows()));
+        }
+
+        // the head's position is all that tells most configurations apart
+        let program = Program::new(",[>,]", NonZeroUsize::new(2).unwrap());
+        assert_eq!(build(&program, false, false).1.states_explored, 467);
+        assert_eq!(build(&program, true, false).1.states_explored, 242);
+        assert_eq!(build(&program, true, true).1.states_explored, 467);
+    }
+
+    #[test]
+    fn tapes_spill_past_the_inline_bytes() {
+        let inline_cells = 2 * U4VEC_INLINE_BYTES;
+        let tape = |cells| Program::new("", NonZeroUsize::new(cells).unwrap()).initial_state();
+        assert!(!tape(inline_cells).cells.0.spilled());
+        let mut spilled = tape(inline_cells + 1);
+        assert!(spilled.cells.0.spilled());
+
+        for position in 0..=inline_cells {
+            spilled.cells.set(position, (position % 16) as u8);
+        }
+        // only the low four bits are kept
+        spilled.cells.set(3, 0x1F);
+        for position in 0..=inline_cells {
+            let expected = if position == 3 { 0xF } else { position % 16 };
+            assert_eq!(usize::from(spilled.cells.get(position)), expected);
+        }
+    }
+
+    #[test]
+    fn spilled_tapes_build_the_same_table() {
+        // tracking wrap-around keeps the whole tape, instead of the two cells the head visits
+        let options = BuildOptions {
+            track_tape_wrap: true,
+            ..BuildOptions::default()
+        };
+        let inline_cells = 2 * U4VEC_INLINE_BYTES;
+        let tables: Vec<Table> = [2, inline_cells, inline_cells + 1]
+            .into_iter()
+            .map(|cells| {
+                let program = Program::new(",>,[-<->]<[>.,<]", NonZeroUsize::new(cells).unwrap());
+                Table::build_with(&program, &options).unwrap().0.canonical()
+            })
+            .collect();
+        assert!(tables[0].rows().eq(tables[1].rows()));
+        assert!(tables[0].rows().eq(tables[2].rows()));
+    }
+
+    #[test]
+    fn state_ids_ignore_the_hasher() {
+        for order in [ExploreOrder::Bfs, ExploreOrder::Dfs] {
+            for program in benchmarks() {
+                let build = |hasher| {
+                    let options = BuildOptions {
+                        order,
+                        hasher,
+                        ..BuildOptions::default()
+                    };
+                    Table::build_with(&program, &options).unwrap()
+                };
+                let (fx, fx_report) = build(StateHasher::Fx);
+                let (random, random_report) = build(StateHasher::Random);
+                assert!(fx.rows().eq(random.rows()));
+                assert_eq!(fx.costs, random.costs);
+                assert_eq!(fx_report.states_explored, random_report.states_explored);
+                assert_eq!(fx_report.unique_rows, random_report.unique_rows);
+                assert_eq!(fx_report.interpreter_steps, random_report.interpreter_steps);
+                assert_eq!(fx_report.divergences, random_report.divergences);
             }
         }
+    }
 
-        for (id, (accepting, _)) in self.states.iter().enumerate() {
-            if *accepting {
-                writeln!(&mut output, "    {id}[peripheries=2];").unwrap();
+    #[test]
+    fn program_accepts_agrees_with_the_table() {
+        for program in benchmarks() {
+            let table = Table::build(&program);
+            for input in short_inputs(3) {
+                assert_eq!(
+                    program.accepts(&input),
+                    Ok(table.accepts(&input)),
+                    "{input:?}"
+                );
             }
         }
+    }
 
-        writeln!(&mut output, "}}").unwrap();
-        output
+    #[test]
+    fn program_accepts_rejects_invalid_symbols() {
+        let program = Program::new(",.", NonZeroUsize::new(1).unwrap());
+        assert_eq!(
+            program.accepts(&[1, 0x10]),
+            Err(RunError::InvalidSymbol {
+                position: 1,
+                symbol: 0x10
+            })
+        );
+    }
+
+    #[test]
+    fn find_accepted_at_various_depths() {
+        let cells = NonZeroUsize::new(1).unwrap();
+        assert_eq!(Program::new(".", cells).find_accepted(3), Some(vec![]));
+        // accepts after a nonzero symbol
+        assert_eq!(Program::new(",[.]", cells).find_accepted(3), Some(vec![1]));
+        let third = Program::new(",,,[.]", cells);
+        assert_eq!(third.find_accepted(3), Some(vec![0, 0, 1]));
+        assert_eq!(third.find_accepted(2), None);
+        assert_eq!(Program::new(",[-]", cells).find_accepted(4), None);
+    }
+
+    #[test]
+    fn shortest_diverging_input() {
+        let cells = NonZeroUsize::new(2).unwrap();
+        // loops forever after a 0 in the second position, halts otherwise
+        let program = Program::new(",>,<[-]+>[<[-]>[-]]<[]", cells);
+        assert_eq!(program.shortest_diverging_input(4), Some(vec![0, 0]));
+        assert_eq!(program.shortest_diverging_input(1), None);
+
+        assert_eq!(
+            Program::new("+[]", cells).shortest_diverging_input(0),
+            Some(vec![])
+        );
+        // halting is not diverging
+        assert_eq!(
+            Program::new(",[-]", cells).shortest_diverging_input(3),
+            None
+        );
+    }
+
+    #[test]
+    fn identical_rows_are_pooled() {
+        // every state that read a nonzero symbol has the same row as the start
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let (mut table, report) = Table::build_with_report(&program);
+        assert_eq!(report.states_explored, 17);
+        assert_eq!(report.unique_rows, 2);
+        assert_eq!(table.rows.len(), 2);
+
+        // giving every state its own row changes nothing about them
+        let pooled = table.clone();
+        table.rows_mut();
+        assert_eq!(table.rows.len(), 17);
+        assert!(table.rows().eq(pooled.rows()));
+        for input in short_inputs(3) {
+            assert_eq!(table.accepts(&input), pooled.accepts(&input));
+            assert_eq!(pooled.accepts(&input), verify::interpret(&program, &input));
+        }
+    }
+
+    #[test]
+    fn minimized_leaves_the_original_alone() {
+        for program in benchmarks() {
+            let table = Table::build(&program);
+            let before = table.clone();
+            let minimized = table.minimized();
+            assert!(table.rows().eq(before.rows()));
+            assert_eq!(
+                minimized.state_count(),
+                table.minimize_reference().state_count()
+            );
+            assert!(matches!(
+                minimized.compare(&table),
+                EquivResult::Equivalent { .. }
+            ));
+
+            let mut in_place = table.clone();
+            in_place.minimize();
+            assert!(in_place.rows().eq(minimized.rows()));
+        }
     }
 }
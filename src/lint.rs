@@ -0,0 +1,199 @@
+use std::fmt::Display;
+
+use crate::{Instruction, Program, Span};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintKind {
+    /// `+-`, `-+`, `<>` or `><`, which do nothing.
+    CancellingPair,
+    /// `[]`: since `]` always jumps back, this loops forever whenever the cell is nonzero.
+    EmptyLoop,
+    /// A loop right after `[-]` or `[+]`, which leaves the cell at zero, so it never runs.
+    LoopAfterClear,
+    /// `.` outside every loop before the first `,`, which always accepts the empty input.
+    AcceptBeforeRead,
+    /// Several `.` in a row, which are no different from one.
+    RepeatedAccept,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub span: Span,
+    pub message: String,
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.span.start)
+    }
+}
+
+impl Program {
+    /// Source range covering the instructions at `positions`, falling back to instruction
+    /// positions for programs without spans.
+    fn span_of(&self, positions: std::ops::Range<usize>) -> Span {
+        match (
+            self.spans.get(positions.start),
+            self.spans.get(positions.end - 1),
+        ) {
+            (Some(first), Some(last)) => Span {
+                start: first.start,
+                end: last.end,
+            },
+            _ => Span {
+                start: positions.start,
+                end: positions.end,
+            },
+        }
+    }
+
+    /// Static warnings about the instruction stream, in source order.
+    pub fn lint(&self) -> Vec<Lint> {
+        use Instruction::*;
+
+        let mut lints = Vec::new();
+        let mut lint = |kind, positions: std::ops::Range<usize>, message: String| {
+            lints.push(Lint {
+                kind,
+                span: self.span_of(positions),
+                message,
+            });
+        };
+
+        let first_read = self
+            .instructions
+            .iter()
+            .position(|instruction| matches!(instruction, Read))
+            .unwrap_or(self.instructions.len());
+        // loops entered and not yet left, since a `.` in a loop may never run
+        let mut depth = 0usize;
+        let mut position = 0;
+        while position < self.instructions.len() {
+            let window = &self.instructions[position..];
+            match window {
+                [a @ Increment, b @ Decrement, ..]
+                | [a @ Decrement, b @ Increment, ..]
+                | [a @ MoveLeft, b @ MoveRight, ..]
+                | [a @ MoveRight, b @ MoveLeft, ..] => lint(
+                    LintKind::CancellingPair,
+                    position..position + 2,
+                    format!("`{}{}` cancels out", a.to_char(), b.to_char()),
+                ),
+                [StartLoop, EndLoop, ..] => lint(
+                    LintKind::EmptyLoop,
+                    position..position + 2,
+                    "`[]` loops forever unless the cell is zero".to_string(),
+                ),
+                [StartLoop, Increment | Decrement, EndLoop, StartLoop, ..] => lint(
+                    LintKind::LoopAfterClear,
+                    position + 3..position + 4,
+                    "this loop follows a clear, so it never runs".to_string(),
+                ),
+                _ => {}
+            }
+            match window[0] {
+                StartLoop => depth += 1,
+                EndLoop => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+
+            if matches!(window[0], Accept) {
+                if position < first_read && depth == 0 {
+                    lint(
+                        LintKind::AcceptBeforeRead,
+                        position..position + 1,
+                        "`.` before the first `,` always accepts the empty input".to_string(),
+                    );
+                }
+                let run = window
+                    .iter()
+                    .take_while(|instruction| matches!(instruction, Accept))
+                    .count();
+                if run > 1 {
+                    lint(
+                        LintKind::RepeatedAccept,
+                        position..position + run,
+                        format!("{run} consecutive `.` act like one"),
+                    );
+                    position += run;
+                    continue;
+                }
+            }
+            position += 1;
+        }
+
+        lints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::LintKind::{self, *};
+    use crate::{Program, Span};
+
+    fn kinds(text: &str) -> Vec<LintKind> {
+        Program::new(text, NonZeroUsize::new(2).unwrap())
+            .lint()
+            .into_iter()
+            .map(|lint| lint.kind)
+            .collect()
+    }
+
+    #[test]
+    fn cancelling_pairs() {
+        let lints = Program::new(", +-", NonZeroUsize::new(1).unwrap()).lint();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, CancellingPair);
+        assert_eq!(lints[0].span, Span { start: 2, end: 4 });
+        assert_eq!(lints[0].to_string(), "`+-` cancels out at offset 2");
+        assert_eq!(kinds(",-+"), [CancellingPair]);
+        assert_eq!(kinds(",<>"), [CancellingPair]);
+        assert_eq!(kinds(",><"), [CancellingPair]);
+
+        assert!(kinds(",+>-<").is_empty());
+        assert!(kinds(",++>>").is_empty());
+    }
+
+    #[test]
+    fn empty_loops() {
+        assert_eq!(kinds(",[]"), [EmptyLoop]);
+        assert!(kinds(",[-]").is_empty());
+    }
+
+    #[test]
+    fn loops_after_a_clear() {
+        let lints = Program::new(",[-][.]", NonZeroUsize::new(1).unwrap()).lint();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, LoopAfterClear);
+        assert_eq!(lints[0].span, Span { start: 4, end: 5 });
+        assert_eq!(kinds(",[+][.]"), [LoopAfterClear]);
+
+        assert!(kinds(",[-]>[.]").is_empty());
+        assert!(kinds(",[>][.]").is_empty());
+    }
+
+    #[test]
+    fn accepts_before_the_first_read() {
+        assert_eq!(kinds(".,"), [AcceptBeforeRead]);
+        assert_eq!(kinds("."), [AcceptBeforeRead]);
+        assert!(kinds(",.").is_empty());
+        // the cell starts at zero, so the loop is skipped
+        assert!(kinds("[.],").is_empty());
+        assert_eq!(kinds("[-].,"), [AcceptBeforeRead]);
+    }
+
+    #[test]
+    fn repeated_accepts() {
+        let lints = Program::new(",...", NonZeroUsize::new(1).unwrap()).lint();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, RepeatedAccept);
+        assert_eq!(lints[0].span, Span { start: 1, end: 4 });
+        assert_eq!(lints[0].message, "3 consecutive `.` act like one");
+
+        assert!(kinds(",.,.").is_empty());
+        assert!(kinds(",.>.").is_empty());
+    }
+}
@@ -1,30 +1,1373 @@
-use std::{env, num::NonZeroUsize};
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    io::Write,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode, Stdio},
+    time::Duration,
+};
 
-use bfa::{Program, Table};
+use bfa::{
+    Brainfuck, BuildOptions, BuildReport, Counted, Dialect, DotOptions, DotStyle, EquivResult,
+    ExploreOrder, FormatOptions, MatrixEntry, Navigator, Ook, PartialBuild, Program,
+    ProgramOptions, RegexMismatch, Side, Table,
+};
 
-fn main() -> Result<(), String> {
+/// Search range and limits for `--cells auto`, the limits also applying to `sweep`.
+const AUTO_MAX_CELLS: usize = 8;
+const AUTO_STABLE_WINDOW: usize = 2;
+const AUTO_STATE_LIMIT: usize = 1_000_000;
+const AUTO_TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Builds whose [`Program::state_space_bound`] exceeds this need `--force`.
+const STATE_SPACE_THRESHOLD: u128 = 1_000_000_000;
+
+enum StatsFormat {
+    Text,
+    Json,
+}
+
+enum OutputFormat {
+    Dot,
+    Html,
+}
+
+fn main() -> Result<ExitCode, String> {
     let mut args = env::args();
-    if args.len() != 3 {
+    let bin = args.next().unwrap_or_default();
+    let args: Vec<String> = args.collect();
+
+    match args.first().map(String::as_str) {
+        Some("equiv") => equiv(&bin, &args[1..]),
+        Some("equiv-matrix") => equiv_matrix(&bin, &args[1..]),
+        Some("fmt") => fmt(&bin, &args[1..]),
+        Some("diff") => diff(&bin, &args[1..]),
+        Some("table") => table(&bin, &args[1..]),
+        Some("explore") => explore(&bin, &args[1..]),
+        Some("repl") => repl(&bin, &args[1..]),
+        Some("reduce") => reduce(&bin, &args[1..]),
+        Some("run") => run(&bin, &args[1..]),
+        Some("sweep") => sweep(&bin, &args[1..]),
+        Some("explain") => explain(&bin, &args[1..]),
+        _ => build(&bin, args),
+    }
+}
+
+fn parse_cell_count(text: &str) -> Result<NonZeroUsize, String> {
+    text.parse::<NonZeroUsize>()
+        .map_err(|e| format!("Invalid cell count: {e}"))
+}
+
+/// Disagreements listed each way by `diff`.
+const DIFF_WITNESSES: usize = 3;
+
+/// Source offsets of the instructions at `positions`, comma separated.
+fn offsets(program: &Program, positions: &[usize]) -> String {
+    let offsets: Vec<String> = positions
+        .iter()
+        .map(|&position| program.spans[position].start.to_string())
+        .collect();
+    offsets.join(", ")
+}
+
+fn hex(symbols: &[u8]) -> String {
+    symbols.iter().map(|symbol| format!("{symbol:X}")).collect()
+}
+
+fn build(bin: &str, args: Vec<String>) -> Result<ExitCode, String> {
+    let usage = format!(
+        "Usage: {bin} [--stats[=json]] [--lint] [--force] [--format dot|html] [--explore bfs|dfs] [--dialect bf|counted|ook] [--cache <dir>] [--no-cache] [--cache-clear] [--checkpoint <file>] [--resume <file>] [--out <file>] [--both --out-dir <dir>] [--watch] [--spec <regex>] [--cells <cell-count>|auto] [<cell-count>] <program>|--file <file>\n       {bin} equiv <cell-count> <program> <cell-count> <program>\n       {bin} equiv-matrix <manifest>\n       {bin} fmt [--normalize] [--compact] [--width <n>] [--group-runs] [--check] <cell-count> <program>|--file <file>\n       {bin} diff [--format text|dot] --cells <cell-count> <program> <program>\n       {bin} table --in <file> [--in-format json|bin] [--trim] [--minimize] [--partial] [--format dot|html|json|bin|smt2|verilog]\n       {bin} explore [--no-minimize] --cells <cell-count> <program>|--file <file>\n       {bin} repl [--no-minimize] [--configuration] --cells <cell-count> <program>|--file <file>\n       {bin} reduce --cells <cell-count> <program>|--file <file>\n       {bin} run [--trace] --cells <cell-count> <program>|--file <file> <input>\n       {bin} sweep [--jobs <n>] [--out <dir>] --cells <from>..<to> <file>\n       {bin} explain --classes --cells <cell-count> <program>|--file <file>"
+    );
+
+    let mut stats = None;
+    let mut lint = false;
+    let mut force = false;
+    let mut order = ExploreOrder::default();
+    let mut cache_dir = None;
+    let mut no_cache = false;
+    let mut cache_clear = false;
+    let mut checkpoints = Checkpoints::default();
+    let mut format = OutputFormat::Dot;
+    let mut cells = None;
+    let mut file = None;
+    let mut out = None;
+    let mut watch = false;
+    let mut spec = None;
+    let mut both = false;
+    let mut out_dir = None;
+    let mut dialect: &dyn Dialect = &Brainfuck;
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stats" => stats = Some(StatsFormat::Text),
+            "--stats=json" => stats = Some(StatsFormat::Json),
+            "--lint" => lint = true,
+            "--force" => force = true,
+            "--cache" => cache_dir = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            "--no-cache" => no_cache = true,
+            "--cache-clear" => cache_clear = true,
+            "--checkpoint" => {
+                checkpoints.save = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?))
+            }
+            "--resume" => {
+                checkpoints.resume = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?))
+            }
+            "--explore" => {
+                order = match args.next().as_deref() {
+                    Some("bfs") => ExploreOrder::Bfs,
+                    Some("dfs") => ExploreOrder::Dfs,
+                    Some(other) => return Err(format!("Unknown exploration order: {other}")),
+                    None => return Err(usage),
+                }
+            }
+            "--dialect" => {
+                dialect = match args.next().as_deref() {
+                    Some("bf") => &Brainfuck,
+                    Some("counted") => &Counted,
+                    Some("ook") => &Ook,
+                    Some(other) => return Err(format!("Unknown dialect: {other}")),
+                    None => return Err(usage),
+                }
+            }
+            "--cells" => cells = Some(args.next().ok_or_else(|| usage.clone())?),
+            "--file" => file = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            "--out" => out = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            "--watch" => watch = true,
+            "--both" => both = true,
+            "--out-dir" => out_dir = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            "--spec" => spec = Some(args.next().ok_or_else(|| usage.clone())?),
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("dot") => OutputFormat::Dot,
+                    Some("html") => OutputFormat::Html,
+                    Some(other) => return Err(format!("Unknown format: {other}")),
+                    None => return Err(usage),
+                }
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let program_text = match &file {
+        Some(file) => read_program(file)?,
+        None => positional.next_back().ok_or_else(|| usage.clone())?,
+    };
+    let program_text = program_text.as_str();
+    let cells = match (cells, positional.next()) {
+        (Some(cells), None) | (None, Some(cells)) => cells,
+        _ => return Err(usage),
+    };
+    if positional.next().is_some() {
+        return Err(usage);
+    }
+
+    if both != out_dir.is_some() || (both && (watch || out.is_some())) {
+        return Err("--both goes with --out-dir, instead of --out or --watch".to_string());
+    }
+    if watch && spec.is_some() {
+        return Err("--spec can't be combined with --watch".to_string());
+    }
+
+    if watch {
+        let (Some(file), Some(out)) = (file, out) else {
+            return Err("--watch needs both --file and --out".to_string());
+        };
+        let options = BuildOptions {
+            order,
+            ..BuildOptions::default()
+        };
+        return watch_program(
+            &file,
+            parse_cell_count(&cells)?,
+            dialect,
+            &options,
+            &format,
+            &out,
+        );
+    }
+
+    let parse = |cell_count| {
+        let options = ProgramOptions { dialect };
+        Program::parse(program_text, cell_count, &options)
+            .map_err(|e| format!("Invalid program: {e}"))
+    };
+    let program = if cells == "auto" {
+        let program = parse(NonZeroUsize::MIN)?;
+        let options = BuildOptions {
+            max_states: Some(AUTO_STATE_LIMIT),
+            time_limit: Some(AUTO_TIME_LIMIT),
+            ..BuildOptions::default()
+        };
+        let cell_count = program
+            .infer_cell_count_with(AUTO_MAX_CELLS, AUTO_STABLE_WINDOW, &options)
+            .ok_or_else(|| {
+                format!("The language doesn't stabilize within {AUTO_MAX_CELLS} cells")
+            })?;
+        eprintln!("using {cell_count} cells");
+        parse(cell_count)?
+    } else {
+        parse(parse_cell_count(&cells)?)?
+    };
+
+    if lint {
+        for lint in program.lint() {
+            eprintln!("warning: {lint}");
+        }
+    }
+
+    let bound = program.state_space_bound();
+    if bound > STATE_SPACE_THRESHOLD {
+        eprintln!("warning: the build may explore up to {bound} states");
+        if !force {
+            return Err("Refusing to start such a large build without --force".to_string());
+        }
+    }
+
+    let options = BuildOptions {
+        order,
+        track_tape_wrap: lint || stats.is_some(),
+        track_arithmetic_wrap: lint,
+        ..BuildOptions::default()
+    };
+
+    let cache_dir = cache_dir.filter(|_| !no_cache);
+    if let (Some(dir), true) = (&cache_dir, cache_clear) {
+        clear_cache(dir)?;
+    }
+    let cache_entry = cache_dir.as_deref().map(|dir| {
+        dir.join(format!(
+            "{:016x}.bfa",
+            cache_key(program_text, &program, &options)
+        ))
+    });
+
+    // a missing or unreadable entry just means building again, and the cache doesn't keep
+    // the table from before minimization that --both needs
+    let cached = cache_entry
+        .as_deref()
+        .filter(|_| !both)
+        .and_then(|entry| fs::read(entry).ok())
+        .and_then(|bytes| Table::from_bytes(&bytes).ok());
+    let (table, report, raw) = match cached {
+        Some(table) => (table, None, None),
+        None => {
+            let (mut table, mut report) = build_table(&program, &options, lint, &checkpoints)?;
+            let raw = both.then(|| table.clone());
+            report.minimize = Some(table.minimize_with_report());
+            if let Some(entry) = &cache_entry {
+                if let Err(e) = store_cache_entry(entry, &table) {
+                    eprintln!("warning: couldn't write the cache entry: {e}");
+                }
+            }
+            (table, Some(report), raw)
+        }
+    };
+
+    if let Some(spec) = &spec {
+        table.matches_regex(spec).map_err(|e| match e {
+            RegexMismatch::Different { .. } => format!("The program doesn't match the spec: {e}"),
+            e => format!("Invalid spec: {e}"),
+        })?;
+    }
+
+    match (raw, &out_dir) {
+        (Some(raw), Some(out_dir)) => write_both(&raw, &table, &format, out_dir)?,
+        _ => write_output(&render(&table, &format), out.as_deref())?,
+    }
+
+    match (stats, report) {
+        (Some(StatsFormat::Text), Some(report)) => eprintln!("{report}"),
+        (Some(StatsFormat::Json), Some(report)) => eprintln!("{}", report.to_json()),
+        (Some(StatsFormat::Text), None) => eprintln!("loaded from cache"),
+        (Some(StatsFormat::Json), None) => eprintln!("{{\"loaded_from_cache\":true}}"),
+        (None, _) => {}
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Writes the table from before minimization to `raw.<format>` in `out_dir` and the
+/// minimized one to `min.<format>`, drawn the same way, and lists the raw states merged into
+/// each minimized state in `mapping.txt`.
+fn write_both(
+    raw: &Table,
+    minimized: &Table,
+    format: &OutputFormat,
+    out_dir: &Path,
+) -> Result<(), String> {
+    fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Couldn't create {}: {e}", out_dir.display()))?;
+    let extension = match format {
+        OutputFormat::Dot => "dot",
+        OutputFormat::Html => "html",
+    };
+    for (name, table) in [("raw", raw), ("min", minimized)] {
+        write_output(
+            &render(table, format),
+            Some(&out_dir.join(format!("{name}.{extension}"))),
+        )?;
+    }
+
+    let mut blocks = vec![Vec::new(); minimized.state_count()];
+    for (state, block) in raw.minimize_mapping().into_iter().enumerate() {
+        blocks[block].push(state.to_string());
+    }
+    let mapping: String = blocks
+        .iter()
+        .enumerate()
+        .map(|(block, states)| format!("{block}: {}\n", states.join(" ")))
+        .collect();
+    let path = out_dir.join("mapping.txt");
+    write_atomically(&path, mapping.as_bytes())
+        .map_err(|e| format!("Couldn't write {}: {e}", path.display()))
+}
+
+/// Builds the table, printing the warnings the report turns up.
+fn build_table(
+    program: &Program,
+    options: &BuildOptions,
+    lint: bool,
+    checkpoints: &Checkpoints,
+) -> Result<(Table, BuildReport), String> {
+    let (table, report) = if checkpoints.save.is_none() && checkpoints.resume.is_none() {
+        Table::build_with(program, options).map_err(|e| e.to_string())?
+    } else {
+        build_resumable(program, options.order, checkpoints)?
+    };
+    if options.track_tape_wrap {
+        for position in report.unreached_instructions() {
+            let offset = program.spans[position].start;
+            eprintln!(
+                "warning: `{}` at offset {offset} is never executed",
+                program.instructions[position].to_char()
+            );
+        }
+        if let Some(tape_wraps @ 1..) = report.tape_wraps {
+            eprintln!(
+                "warning: head wrapped {tape_wraps} times (at offsets {}); consider more cells",
+                offsets(program, &report.tape_wrap_sites)
+            );
+        }
+    }
+    if lint {
+        let increments = report.increment_wraps.unwrap_or(0);
+        let decrements = report.decrement_wraps.unwrap_or(0);
+        if increments + decrements > 0 {
+            eprintln!(
+                "warning: {increments} `+` on 15 and {decrements} `-` on 0 wrapped around (at offsets {})",
+                offsets(program, &report.arithmetic_wrap_sites)
+            );
+        }
+    }
+    Ok((table, report))
+}
+
+/// Time between checkpoints of a build with `--checkpoint`.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct Checkpoints {
+    /// Where to save the build's progress every [`CHECKPOINT_INTERVAL`].
+    save: Option<PathBuf>,
+    /// A checkpoint to pick up from instead of starting over.
+    resume: Option<PathBuf>,
+}
+
+/// Builds in installments, saving the progress in between so that an interrupted build can
+/// be resumed. The checkpoint is removed once the build is done.
+fn build_resumable(
+    program: &Program,
+    order: ExploreOrder,
+    checkpoints: &Checkpoints,
+) -> Result<(Table, BuildReport), String> {
+    let mut build = match &checkpoints.resume {
+        Some(resume) => {
+            let bytes =
+                fs::read(resume).map_err(|e| format!("Couldn't read {}: {e}", resume.display()))?;
+            let build = PartialBuild::from_bytes(&bytes)
+                .map_err(|e| format!("Couldn't resume from {}: {e}", resume.display()))?;
+            if !build.is_for(program) {
+                return Err(format!(
+                    "{} is a checkpoint of a different program or cell count",
+                    resume.display()
+                ));
+            }
+            eprintln!("resuming with {} states", build.states_discovered());
+            build
+        }
+        None => PartialBuild::new(program, order),
+    };
+
+    if let Some(save) = &checkpoints.save {
+        while !build.run_for(CHECKPOINT_INTERVAL) {
+            write_atomically(save, &build.to_bytes())
+                .map_err(|e| format!("Couldn't write {}: {e}", save.display()))?;
+            eprintln!("checkpoint: {} states", build.states_discovered());
+        }
+    }
+    let built = build.finish();
+    if let Some(save) = &checkpoints.save {
+        // it could only be resumed into the table that was just built
+        let _ = fs::remove_file(save);
+    }
+    Ok(built)
+}
+
+/// Identifies a minimized table: the program, everything that affects the build and the
+/// version of bfa that built it.
+fn cache_key(program_text: &str, program: &Program, options: &BuildOptions) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    program_text.hash(&mut hasher);
+    // the same text means different programs in different dialects
+    program.to_string().hash(&mut hasher);
+    program.cell_count.hash(&mut hasher);
+    options.order.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn store_cache_entry(entry: &Path, table: &Table) -> std::io::Result<()> {
+    if let Some(dir) = entry.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write_atomically(entry, &table.to_bytes())
+}
+
+/// Writes and renames, so that an interrupted run or a concurrent reader never sees a
+/// truncated file.
+fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let partial = path.with_extension("partial");
+    fs::write(&partial, contents)?;
+    fs::rename(partial, path)
+}
+
+fn read_program(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {e}", path.display()))
+}
+
+fn render(table: &Table, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Dot => table.dot(),
+        OutputFormat::Html => table.to_html(),
+    }
+}
+
+/// Prints the rendered table, or writes it to `out`. A `.svg` output is laid out by
+/// Graphviz's `dot`, which has to be on the `PATH`.
+fn write_output(rendered: &str, out: Option<&Path>) -> Result<(), String> {
+    let Some(out) = out else {
+        println!("{rendered}");
+        return Ok(());
+    };
+    let contents = if out.extension().is_some_and(|extension| extension == "svg") {
+        graphviz_svg(rendered)?
+    } else {
+        rendered.as_bytes().to_vec()
+    };
+    write_atomically(out, &contents).map_err(|e| format!("Couldn't write {}: {e}", out.display()))
+}
+
+fn graphviz_svg(dot: &str) -> Result<Vec<u8>, String> {
+    let mut child = Command::new("dot")
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Couldn't run Graphviz: {e}"))?;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(dot.as_bytes())
+        .map_err(|e| format!("Couldn't run Graphviz: {e}"))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Couldn't run Graphviz: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("Graphviz failed with {}", output.status));
+    }
+    Ok(output.stdout)
+}
+
+/// Quiet period after a change to the program file before rebuilding, which folds an
+/// editor's burst of writes into one rebuild.
+#[cfg(feature = "watch")]
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Builds the program in `file`, writes the minimized table to `out` and returns a status
+/// line for the rebuild.
+#[cfg(feature = "watch")]
+fn rebuild(
+    file: &Path,
+    cell_count: NonZeroUsize,
+    dialect: &dyn Dialect,
+    options: &BuildOptions,
+    format: &OutputFormat,
+    out: &Path,
+) -> Result<String, String> {
+    let program = Program::parse(
+        &read_program(file)?,
+        cell_count,
+        &ProgramOptions { dialect },
+    )
+    .map_err(|e| format!("Invalid program: {e}"))?;
+    let (mut table, report) = Table::build_with(&program, options).map_err(|e| e.to_string())?;
+    let minimize = table.minimize_with_report();
+    write_output(&render(&table, format), Some(out))?;
+    Ok(format!(
+        "{} states ({} explored) in {:?}",
+        table.state_count(),
+        report.states_explored,
+        report.build_time + minimize.minimize_time
+    ))
+}
+
+/// Rebuilds whenever `file` changes, until interrupted. Build errors are reported and
+/// the previous output is left in place.
+#[cfg(feature = "watch")]
+fn watch_program(
+    file: &Path,
+    cell_count: NonZeroUsize,
+    dialect: &dyn Dialect,
+    options: &BuildOptions,
+    format: &OutputFormat,
+    out: &Path,
+) -> Result<ExitCode, String> {
+    use notify::{RecursiveMode, Watcher};
+
+    let report = || match rebuild(file, cell_count, dialect, options, format, out) {
+        Ok(status) => eprintln!("rebuilt: {status}"),
+        Err(e) => eprintln!("error: {e}"),
+    };
+
+    // editors often replace the file instead of writing to it, so watch its directory
+    let directory = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(sender).map_err(|e| format!("Couldn't watch: {e}"))?;
+    watcher
+        .watch(directory, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Couldn't watch {}: {e}", directory.display()))?;
+
+    report();
+    while let Ok(event) = receiver.recv() {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("warning: {e}");
+                continue;
+            }
+        };
+        let touches_file = event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == file.file_name());
+        if !touches_file || event.kind.is_access() {
+            continue;
+        }
+        while receiver.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+        report();
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch_program(
+    _file: &Path,
+    _cell_count: NonZeroUsize,
+    _dialect: &dyn Dialect,
+    _options: &BuildOptions,
+    _format: &OutputFormat,
+    _out: &Path,
+) -> Result<ExitCode, String> {
+    Err("bfa was built without the `watch` feature".to_string())
+}
+
+fn clear_cache(dir: &Path) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|extension| extension == "bfa") {
+            fs::remove_file(&path).map_err(|e| format!("Couldn't clear the cache: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Compares every pair of the programs listed in the manifest, one `<name> <cell-count>
+/// <program>` per line, skipping blank lines and those starting with `#`. Prints a grid with
+/// `=` for equivalent pairs, `x` for different ones and `?` where a build failed, then a
+/// witness for each different pair. Exits with 0 when all of them are equivalent and 1
+/// otherwise.
+fn equiv_matrix(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let [manifest] = args else {
+        return Err(format!("Usage: {bin} equiv-matrix <manifest>"));
+    };
+    let manifest = read_program(Path::new(manifest))?;
+
+    let mut names = Vec::new();
+    let mut programs = Vec::new();
+    for (number, line) in manifest.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.splitn(3, char::is_whitespace);
+        let (Some(name), Some(cells), Some(program_text)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(format!(
+                "Line {} of the manifest isn't `<name> <cell-count> <program>`",
+                number + 1
+            ));
+        };
+        names.push(name.to_string());
+        programs.push((program_text.to_string(), parse_cell_count(cells)?));
+    }
+
+    let matrix = bfa::equiv_matrix(&programs, &BuildOptions::default());
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    for (row, name) in names.iter().enumerate() {
+        let cells: Vec<&str> = (0..matrix.len())
+            .map(|column| match matrix.get(row, column) {
+                MatrixEntry::Equivalent => "=",
+                MatrixEntry::Different { .. } => "x",
+                MatrixEntry::BuildFailed => "?",
+            })
+            .collect();
+        println!("{name:width$} {}", cells.join(" "));
+    }
+
+    let mut all_equivalent = true;
+    for row in 0..matrix.len() {
+        if let Some(e) = matrix.build_error(row) {
+            all_equivalent = false;
+            println!("{}: {e}", names[row]);
+        }
+        for column in row + 1..matrix.len() {
+            if let MatrixEntry::Different {
+                witness,
+                accepted_by,
+            } = matrix.get(row, column)
+            {
+                all_equivalent = false;
+                let accepted_by = match accepted_by {
+                    Side::A => &names[row],
+                    Side::B => &names[column],
+                };
+                println!(
+                    "{} vs {}: \"{}\" is only accepted by {accepted_by}",
+                    names[row],
+                    names[column],
+                    hex(witness)
+                );
+            }
+        }
+    }
+    Ok(ExitCode::from(u8::from(!all_equivalent)))
+}
+
+/// Exits with 0 when the programs are equivalent and 1 when they differ.
+fn equiv(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let [cells_a, program_a, cells_b, program_b] = args else {
         return Err(format!(
-            "Usage: {} <cell-count> <program>",
-            args.next().unwrap_or_default()
+            "Usage: {bin} equiv <cell-count> <program> <cell-count> <program>"
         ));
+    };
+
+    let a = Program::new(program_a, parse_cell_count(cells_a)?);
+    let b = Program::new(program_b, parse_cell_count(cells_b)?);
+
+    match Program::equivalent(&a, &b).map_err(|e| e.to_string())? {
+        EquivResult::Equivalent { states_a, states_b } => {
+            println!("equivalent ({states_a} and {states_b} states)");
+            Ok(ExitCode::SUCCESS)
+        }
+        EquivResult::Different {
+            witness,
+            accepted_by,
+        } => {
+            let side = match accepted_by {
+                Side::A => "first",
+                Side::B => "second",
+            };
+            println!(
+                "different: \"{}\" is only accepted by the {side} program",
+                hex(&witness)
+            );
+            Ok(ExitCode::from(1))
+        }
     }
+}
 
-    args.next();
+/// Lays the program out by loop nesting, after bringing it to its canonical form with
+/// `--normalize`, or prints it on one line with `--compact`. With `--file` the file is
+/// rewritten in place, or with `--check` left alone and the exit code is 1 if it would change.
+fn fmt(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage = format!(
+        "Usage: {bin} fmt [--normalize] [--compact] [--width <n>] [--group-runs] [--check] <cell-count> <program>|--file <file>"
+    );
 
-    let cell_count = args
-        .next()
-        .unwrap()
-        .parse::<NonZeroUsize>()
-        .map_err(|e| format!("Invalid cell count: {e}"))?;
+    let mut normalize = false;
+    let mut compact = false;
+    let mut check = false;
+    let mut options = FormatOptions::default();
+    let mut file = None;
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--normalize" => normalize = true,
+            "--compact" => compact = true,
+            "--check" => check = true,
+            "--group-runs" => options.group_runs = true,
+            "--width" => {
+                let width = args.next().ok_or_else(|| usage.clone())?;
+                options.line_width = width
+                    .parse()
+                    .map_err(|e| format!("Invalid line width: {e}"))?;
+            }
+            "--file" => file = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            _ => positional.push(arg),
+        }
+    }
+    let (cells, program_text) = match (positional.as_slice(), &file) {
+        ([cells], Some(file)) => (cells, read_program(file)?),
+        ([cells, program_text], None) => (cells, program_text.to_string()),
+        _ => return Err(usage),
+    };
+    if check && file.is_none() {
+        return Err(format!("--check needs --file\n{usage}"));
+    }
+
+    let mut program = Program::new(&program_text, parse_cell_count(cells)?);
+    if normalize {
+        program = program.normalize();
+    }
+    let formatted = if compact {
+        format!("{program}\n")
+    } else {
+        program.format(&options)
+    };
+
+    match file {
+        None => print!("{formatted}"),
+        Some(file) if check => {
+            if formatted != program_text {
+                println!("{} is not formatted", file.display());
+                return Ok(ExitCode::from(1));
+            }
+        }
+        Some(file) if formatted != program_text => write_atomically(&file, formatted.as_bytes())
+            .map_err(|e| format!("Couldn't write {}: {e}", file.display()))?,
+        Some(_) => {}
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reports how two programs' languages differ, or draws their product with `--format dot`.
+/// Exits with 0 when the programs are equivalent and 1 when they differ.
+fn diff(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage =
+        format!("Usage: {bin} diff [--format text|dot] --cells <cell-count> <program> <program>");
 
-    let program_text = args.next().unwrap();
-    let program = Program::new(&program_text, cell_count);
+    let mut dot = false;
+    let mut cells = None;
+    let mut programs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cells" => cells = Some(args.next().ok_or_else(|| usage.clone())?),
+            "--format" => {
+                dot = match args.next().map(String::as_str) {
+                    Some("text") => false,
+                    Some("dot") => true,
+                    Some(other) => return Err(format!("Unknown format: {other}")),
+                    None => return Err(usage),
+                }
+            }
+            _ => programs.push(arg),
+        }
+    }
+    let (Some(cells), [program_a, program_b]) = (cells, programs.as_slice()) else {
+        return Err(usage);
+    };
+
+    let cell_count = parse_cell_count(cells)?;
+    let build = |program_text: &str| {
+        let (mut table, _) = Table::build_with(
+            &Program::new(program_text, cell_count),
+            &BuildOptions::default(),
+        )
+        .map_err(|e| e.to_string())?;
+        table.minimize();
+        Ok::<_, String>(table)
+    };
+    let a = build(program_a)?;
+    let b = build(program_b)?;
+    let difference = a.symmetric_difference(&b);
+    let equivalent = difference.shortest_accepted().is_none();
+
+    if dot {
+        // the accepting states of the product are exactly where the programs disagree
+        let options = DotOptions {
+            style: DotStyle {
+                accepting_fill: Some("red".to_string()),
+                ..DotStyle::default()
+            },
+            ..DotOptions::default()
+        };
+        println!("{}", difference.dot_with(&options));
+    } else {
+        println!("states: {} and {}", a.state_count(), b.state_count());
+        if equivalent {
+            println!("equivalent");
+        } else {
+            println!("different");
+            for (side, only) in [
+                ("first", a.intersection(&b.complement())),
+                ("second", b.intersection(&a.complement())),
+            ] {
+                let witnesses: Vec<String> = only
+                    .shortest_accepted_strings(DIFF_WITNESSES)
+                    .iter()
+                    .map(|witness| format!("\"{}\"", hex(witness)))
+                    .collect();
+                if !witnesses.is_empty() {
+                    println!("only accepted by the {side}: {}", witnesses.join(", "));
+                }
+            }
+        }
+    }
+
+    Ok(if equivalent {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+enum TableFormat {
+    Json,
+    Binary,
+}
+
+/// Works on an automaton file instead of a program: validates it, optionally trims and
+/// minimizes it, and writes it out in any of the export formats, leaving out the dead state
+/// with `--partial`.
+fn table(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage = format!(
+        "Usage: {bin} table --in <file> [--in-format json|bin] [--trim] [--minimize] [--partial] [--format dot|html|json|bin|smt2|verilog]"
+    );
+    let table_format = |name: Option<&str>| match name {
+        Some("json") => Ok(Some(TableFormat::Json)),
+        Some("bin") => Ok(Some(TableFormat::Binary)),
+        Some(other) => Err(format!("Unknown table format: {other}")),
+        None => Ok(None),
+    };
+
+    let mut input = None;
+    let mut input_format = None;
+    let mut trim = false;
+    let mut minimize = false;
+    let mut partial = false;
+    let mut format = "dot".to_string();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--in" => input = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            "--in-format" => {
+                input_format = table_format(Some(args.next().ok_or_else(|| usage.clone())?))?;
+            }
+            "--trim" => trim = true,
+            "--minimize" => minimize = true,
+            "--partial" => partial = true,
+            "--format" => format = args.next().ok_or_else(|| usage.clone())?.clone(),
+            _ => return Err(usage),
+        }
+    }
+    let Some(input) = input else {
+        return Err(usage);
+    };
+
+    let input_format = match input_format {
+        Some(input_format) => input_format,
+        None => table_format(input.extension().and_then(|extension| extension.to_str()))?
+            .ok_or_else(|| {
+                format!(
+                    "Can't tell the format of {} from its extension, use --in-format",
+                    input.display()
+                )
+            })?,
+    };
+    let bytes = fs::read(&input).map_err(|e| format!("Couldn't read {}: {e}", input.display()))?;
+    let mut table = match input_format {
+        TableFormat::Json => {
+            let text = String::from_utf8(bytes)
+                .map_err(|_| format!("{} isn't valid UTF-8", input.display()))?;
+            Table::from_json(&text)
+        }
+        TableFormat::Binary => Table::from_bytes(&bytes),
+    }
+    .map_err(|e| e.to_string())?;
+    for warning in table.validation_warnings() {
+        eprintln!("warning: {warning}");
+    }
+
+    if trim {
+        table = table.restrict_reachable(0);
+        table.trim_hopeless();
+    }
+    if minimize {
+        table.minimize();
+    }
+    if partial {
+        table = table.to_partial();
+    }
+
+    match format.as_str() {
+        "dot" => println!("{}", table.dot()),
+        "html" => println!("{}", table.to_html()),
+        "json" => println!("{}", table.to_json()),
+        "smt2" => print!("{}", table.to_smt2()),
+        "verilog" => {
+            let name = input
+                .file_stem()
+                .map_or("recognizer".into(), |stem| stem.to_string_lossy());
+            print!("{}", table.to_verilog(&name, None));
+        }
+        "bin" => std::io::stdout()
+            .write_all(&table.to_bytes())
+            .map_err(|e| format!("Couldn't write the table: {e}"))?,
+        other => return Err(format!("Unknown format: {other}")),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The program and table for `explore` and `repl`, and whether `--configuration` was given
+/// (only accepted when `configuration_flag` is set).
+fn interactive_table(
+    usage: String,
+    args: &[String],
+    configuration_flag: bool,
+) -> Result<(Program, Table, bool), String> {
+    let mut minimize = true;
+    let mut configuration = false;
+    let mut cells = None;
+    let mut file = None;
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-minimize" => minimize = false,
+            "--configuration" if configuration_flag => configuration = true,
+            "--cells" => cells = Some(args.next().ok_or_else(|| usage.clone())?),
+            "--file" => file = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+    let program_text = match (file, positional.as_slice()) {
+        (Some(file), []) => read_program(&file)?,
+        (None, [program_text]) => program_text.clone(),
+        _ => return Err(usage),
+    };
+    let Some(cells) = cells else {
+        return Err(usage);
+    };
 
+    let program = Program::new(&program_text, parse_cell_count(cells)?);
+    let (mut table, _) =
+        Table::build_with(&program, &BuildOptions::default()).map_err(|e| e.to_string())?;
+    if minimize {
+        table.minimize();
+    }
+    Ok((program, table, configuration))
+}
+
+/// Steps through the automaton in a terminal UI.
+fn explore(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage = format!(
+        "Usage: {bin} explore [--no-minimize] --cells <cell-count> <program>|--file <file>"
+    );
+    let (program, table, _) = interactive_table(usage, args, false)?;
+    run_explorer(&program, &table)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reads symbols and commands from stdin, one line at a time, and reports the state after
+/// each symbol. Bad lines are reported and skipped, so a transcript can be piped through.
+fn repl(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage = format!(
+        "Usage: {bin} repl [--no-minimize] [--configuration] --cells <cell-count> <program>|--file <file>"
+    );
+    let (program, table, show_configuration) = interactive_table(usage, args, true)?;
+
+    let mut navigator = Navigator::new(&table);
+    // the inputs that led to the current state, for its configuration
+    let mut inputs = Vec::new();
+    let describe = |navigator: &Navigator, inputs: &[u8]| {
+        let state = navigator.current();
+        let verdict = if table.is_accepting(state) {
+            "accepting"
+        } else {
+            "rejecting"
+        };
+        let mut line = format!("state {state} ({verdict})");
+        if show_configuration {
+            match program.configuration_after(inputs) {
+                Some(configuration) => line += &format!(": {configuration}"),
+                None => line += ": halted",
+            }
+        }
+        line
+    };
+
+    println!("{}", describe(&navigator, &inputs));
+    for line in std::io::stdin().lines() {
+        let line = line.map_err(|e| format!("Couldn't read stdin: {e}"))?;
+        match line.trim() {
+            "" => {}
+            "reset" => {
+                navigator = Navigator::new(&table);
+                inputs.clear();
+                println!("{}", describe(&navigator, &inputs));
+            }
+            "back" => {
+                if navigator.back() {
+                    inputs.pop();
+                    println!("{}", describe(&navigator, &inputs));
+                } else {
+                    println!("error: nothing to go back to");
+                }
+            }
+            "show" => println!("\"{}\" -> {}", hex(&inputs), describe(&navigator, &inputs)),
+            symbols => {
+                let symbols: Vec<char> = symbols.chars().filter(|c| !c.is_whitespace()).collect();
+                if let Some(bad) = symbols.iter().find(|c| !c.is_ascii_hexdigit()) {
+                    println!("error: `{bad}` is neither a hex digit nor a command");
+                    continue;
+                }
+                for symbol in symbols {
+                    let symbol = symbol.to_digit(16).unwrap() as u8;
+                    if navigator.follow(symbol) {
+                        inputs.push(symbol);
+                        println!("{symbol:X} -> {}", describe(&navigator, &inputs));
+                    } else {
+                        println!("error: no transition on {symbol:X}");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(feature = "tui")]
+fn run_explorer(program: &Program, table: &Table) -> Result<(), String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+    use ratatui::{
+        layout::{Constraint, Layout},
+        text::Line,
+        widgets::{Block, List, ListState, Paragraph},
+    };
+
+    let access_strings = table.access_strings();
+    let mut navigator = Navigator::new(table);
+    let mut list_state = ListState::default();
+    let mut terminal = ratatui::init();
+
+    let result = loop {
+        let current = navigator.current();
+        list_state.select(Some(current));
+        let drawn = terminal.draw(|frame| {
+            let [states_area, details_area] =
+                Layout::horizontal([Constraint::Length(16), Constraint::Min(0)])
+                    .areas(frame.area());
+            let [row_area, access_area, configuration_area, help_area] = Layout::vertical([
+                Constraint::Length(18),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(0),
+            ])
+            .areas(details_area);
+
+            let states = List::new((0..table.state_count()).map(|state| {
+                let marker = if table.is_accepting(state) { "*" } else { " " };
+                format!("{marker} {state}")
+            }))
+            .block(Block::bordered().title("States"))
+            .highlight_symbol("> ");
+            frame.render_stateful_widget(states, states_area, &mut list_state);
+
+            let row: Vec<Line> = (0..16)
+                .map(|input| match table.transition(current, input) {
+                    Table::MISSING => Line::from(format!("{input:X} -> missing")),
+                    to => Line::from(format!("{input:X} -> {to}")),
+                })
+                .collect();
+            let title = format!("Transitions of {current}");
+            frame.render_widget(
+                Paragraph::new(row).block(Block::bordered().title(title)),
+                row_area,
+            );
+
+            let access = access_strings[current].as_deref();
+            let access_text = access.map_or("unreachable".to_string(), |access| {
+                format!("\"{}\"", hex(access))
+            });
+            frame.render_widget(
+                Paragraph::new(access_text).block(Block::bordered().title("Access string")),
+                access_area,
+            );
+
+            let configuration = match access {
+                Some(access) => program
+                    .configuration_after(access)
+                    .map_or("halted".to_string(), |configuration| {
+                        configuration.to_string()
+                    }),
+                None => "unreachable".to_string(),
+            };
+            frame.render_widget(
+                Paragraph::new(configuration).block(Block::bordered().title("Configuration")),
+                configuration_area,
+            );
+
+            frame.render_widget(
+                Paragraph::new("0-F follow  backspace back  s start  up/down select  q quit"),
+                help_area,
+            );
+        });
+        if let Err(e) = drawn {
+            break Err(e);
+        }
+
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e),
+        };
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break Ok(()),
+            KeyCode::Char(c) if c.is_ascii_hexdigit() => {
+                navigator.follow(c.to_digit(16).unwrap() as u8);
+            }
+            KeyCode::Backspace | KeyCode::Left => {
+                navigator.back();
+            }
+            KeyCode::Char('s') | KeyCode::Home => navigator.jump_to_start(),
+            KeyCode::Up => navigator.select(current.saturating_sub(1)),
+            KeyCode::Down => navigator.select(current + 1),
+            _ => {}
+        }
+    };
+
+    ratatui::restore();
+    result.map_err(|e| format!("Terminal error: {e}"))
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_explorer(_program: &Program, _table: &Table) -> Result<(), String> {
+    Err("bfa was built without the `tui` feature".to_string())
+}
+
+/// Prints a smallest program found with the same language.
+fn reduce(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage = format!("Usage: {bin} reduce --cells <cell-count> <program>|--file <file>");
+    let (cells, program_text) = match args {
+        [flag, cells, flag_or_program @ ..] if flag == "--cells" => match flag_or_program {
+            [program_text] => (cells, program_text.clone()),
+            [file_flag, file] if file_flag == "--file" => (cells, read_program(Path::new(file))?),
+            _ => return Err(usage),
+        },
+        _ => return Err(usage),
+    };
+
+    let program = Program::new(&program_text, parse_cell_count(cells)?);
+    let reduced = bfa::reduce(&program, bfa::same_language(&program));
+    eprintln!(
+        "reduced from {} to {} instructions",
+        program.instructions.len(),
+        reduced.instructions.len()
+    );
+    println!("{reduced}");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Runs a hex string through the minimized table, printing the states it passes through
+/// with `--trace`. Exits with 0 when it's accepted and 1 when it's rejected.
+fn run(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage =
+        format!("Usage: {bin} run [--trace] --cells <cell-count> <program>|--file <file> <input>");
+
+    let mut trace = false;
+    let mut cells = None;
+    let mut file = None;
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trace" => trace = true,
+            "--cells" => cells = Some(args.next().ok_or_else(|| usage.clone())?),
+            "--file" => file = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            _ => positional.push(arg),
+        }
+    }
+    let (Some(cells), program_text, input) = (match (&file, positional.as_slice()) {
+        (Some(file), [input]) => (cells, read_program(file)?, input),
+        (None, [program_text, input]) => (cells, program_text.to_string(), input),
+        _ => return Err(usage),
+    }) else {
+        return Err(usage);
+    };
+    let input = input
+        .chars()
+        .map(|c| {
+            c.to_digit(16)
+                .map(|digit| digit as u8)
+                .ok_or_else(|| format!("`{c}` isn't a hex digit"))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    let program = Program::new(&program_text, parse_cell_count(cells)?);
     let mut table = Table::build(&program);
     table.minimize();
-    println!("{}", table.dot());
+    if trace {
+        print!("{}", table.trace_pretty(&input, None));
+    }
+    if table.accepts(&input) {
+        println!("accepted");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!("rejected");
+        Ok(ExitCode::from(1))
+    }
+}
 
-    Ok(())
+/// Prints the Myhill–Nerode classes of the program's language with `--classes`, the only
+/// mode so far.
+fn explain(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage =
+        format!("Usage: {bin} explain --classes --cells <cell-count> <program>|--file <file>");
+
+    let mut classes = false;
+    let mut cells = None;
+    let mut file = None;
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--classes" => classes = true,
+            "--cells" => cells = Some(args.next().ok_or_else(|| usage.clone())?),
+            "--file" => file = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            _ => positional.push(arg),
+        }
+    }
+    let (true, Some(cells), program_text) = (
+        classes,
+        cells,
+        match (&file, positional.as_slice()) {
+            (Some(file), []) => read_program(file)?,
+            (None, [program_text]) => program_text.to_string(),
+            _ => return Err(usage),
+        },
+    ) else {
+        return Err(usage);
+    };
+
+    let table = Table::build(&Program::new(&program_text, parse_cell_count(cells)?));
+    for class in table.nerode_classes() {
+        println!("{class}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Builds the program for each cell count in an inclusive range, printing how the minimized
+/// language changes, and writes each minimized automaton to `<dir>/cells-<n>.dot` with
+/// `--out`. Cell counts whose build hits a limit are marked as skipped. `--jobs` sets the
+/// number of threads with the `rayon` feature and is ignored without it.
+fn sweep(bin: &str, args: &[String]) -> Result<ExitCode, String> {
+    let usage =
+        format!("Usage: {bin} sweep [--jobs <n>] [--out <dir>] --cells <from>..<to> <file>");
+
+    let mut cells = None;
+    let mut jobs = 1;
+    let mut out = None;
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cells" => cells = Some(args.next().ok_or_else(|| usage.clone())?),
+            "--jobs" => {
+                jobs = args
+                    .next()
+                    .ok_or_else(|| usage.clone())?
+                    .parse::<usize>()
+                    .map_err(|e| format!("Invalid job count: {e}"))?
+            }
+            "--out" => out = Some(PathBuf::from(args.next().ok_or_else(|| usage.clone())?)),
+            _ => positional.push(arg),
+        }
+    }
+    let (Some(cells), [file]) = (cells, positional.as_slice()) else {
+        return Err(usage);
+    };
+    let (from, to) = cells
+        .split_once("..")
+        .unwrap_or((cells.as_str(), cells.as_str()));
+    let (from, to) = (parse_cell_count(from)?.get(), parse_cell_count(to)?.get());
+    let program_text = read_program(Path::new(file))?;
+
+    let options = BuildOptions {
+        max_states: Some(AUTO_STATE_LIMIT),
+        time_limit: Some(AUTO_TIME_LIMIT),
+        ..BuildOptions::default()
+    };
+    #[cfg(feature = "rayon")]
+    let results = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.max(1))
+        .build()
+        .map_err(|e| format!("Couldn't start {jobs} threads: {e}"))?
+        .install(|| bfa::sweep_with(&program_text, from..=to, &options));
+    #[cfg(not(feature = "rayon"))]
+    let results = {
+        let _ = jobs;
+        bfa::sweep_with(&program_text, from..=to, &options)
+    };
+
+    if let Some(out) = &out {
+        fs::create_dir_all(out).map_err(|e| format!("Couldn't create {}: {e}", out.display()))?;
+    }
+    println!("cells  states  minimized  fingerprint       same  time");
+    for (cell_count, result) in &results {
+        match result {
+            Ok((entry, report, table)) => {
+                println!(
+                    "{cell_count:<5}  {:<6}  {:<9}  {:016x}  {:<4}  {:?}",
+                    report.states_explored,
+                    entry.states,
+                    entry.fingerprint,
+                    if entry.same_as_previous { "yes" } else { "no" },
+                    report.build_time
+                );
+                if let Some(out) = &out {
+                    let path = out.join(format!("cells-{cell_count}.dot"));
+                    fs::write(&path, table.dot())
+                        .map_err(|e| format!("Couldn't write {}: {e}", path.display()))?;
+                }
+            }
+            Err(e) => println!("{cell_count:<5}  skipped (limit): {e}"),
+        }
+    }
+    Ok(ExitCode::SUCCESS)
 }
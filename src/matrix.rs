@@ -0,0 +1,176 @@
+use std::num::NonZeroUsize;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{BuildError, BuildOptions, EquivResult, Program, Side, Table};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MatrixEntry {
+    Equivalent,
+    /// `witness` is a shortest string accepted by exactly one of the pair, the row's program
+    /// being [`Side::A`].
+    Different {
+        witness: Vec<u8>,
+        accepted_by: Side,
+    },
+    /// At least one of the pair hit a limit, see [`EquivMatrix::build_error`].
+    BuildFailed,
+}
+
+/// How every pair of programs compares, see [`equiv_matrix`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EquivMatrix {
+    build_errors: Vec<Option<BuildError>>,
+    entries: Vec<MatrixEntry>,
+}
+
+impl EquivMatrix {
+    /// The number of programs.
+    pub fn len(&self) -> usize {
+        self.build_errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.build_errors.is_empty()
+    }
+
+    /// Program `row` against program `column`.
+    pub fn get(&self, row: usize, column: usize) -> &MatrixEntry {
+        &self.entries[row * self.len() + column]
+    }
+
+    pub fn build_error(&self, program: usize) -> Option<BuildError> {
+        self.build_errors[program]
+    }
+}
+
+/// Builds each program once under `options`, in parallel with the `rayon` feature, and
+/// compares every pair of languages. Pairs whose canonical tables are identical are
+/// equivalent without running the product.
+pub fn equiv_matrix(programs: &[(String, NonZeroUsize)], options: &BuildOptions) -> EquivMatrix {
+    let build = |(program_text, cell_count): &(String, NonZeroUsize)| {
+        Table::build_with(&Program::new(program_text, *cell_count), options).map(|(table, _)| {
+            let table = table.canonical();
+            let fingerprint = table.fingerprint();
+            (table, fingerprint)
+        })
+    };
+    #[cfg(feature = "rayon")]
+    let tables: Vec<_> = programs.par_iter().map(build).collect();
+    #[cfg(not(feature = "rayon"))]
+    let tables: Vec<_> = programs.iter().map(build).collect();
+
+    let count = programs.len();
+    let mut entries = vec![MatrixEntry::Equivalent; count * count];
+    for row in 0..count {
+        for column in row + 1..count {
+            let (entry, mirrored) = match (&tables[row], &tables[column]) {
+                (Ok((a, fingerprint_a)), Ok((b, fingerprint_b))) => {
+                    if fingerprint_a == fingerprint_b && a.rows().eq(b.rows()) {
+                        (MatrixEntry::Equivalent, MatrixEntry::Equivalent)
+                    } else {
+                        match a.compare(b) {
+                            EquivResult::Equivalent { .. } => {
+                                (MatrixEntry::Equivalent, MatrixEntry::Equivalent)
+                            }
+                            EquivResult::Different {
+                                witness,
+                                accepted_by,
+                            } => {
+                                let flipped = match accepted_by {
+                                    Side::A => Side::B,
+                                    Side::B => Side::A,
+                                };
+                                (
+                                    MatrixEntry::Different {
+                                        witness: witness.clone(),
+                                        accepted_by,
+                                    },
+                                    MatrixEntry::Different {
+                                        witness,
+                                        accepted_by: flipped,
+                                    },
+                                )
+                            }
+                        }
+                    }
+                }
+                _ => (MatrixEntry::BuildFailed, MatrixEntry::BuildFailed),
+            };
+            entries[row * count + column] = entry;
+            entries[column * count + row] = mirrored;
+        }
+        if tables[row].is_err() {
+            entries[row * count + row] = MatrixEntry::BuildFailed;
+        }
+    }
+
+    EquivMatrix {
+        build_errors: tables
+            .iter()
+            .map(|table| table.as_ref().err().copied())
+            .collect(),
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{BuildError, BuildOptions, Side};
+
+    use super::{equiv_matrix, MatrixEntry};
+
+    fn programs(list: &[(&str, usize)]) -> Vec<(String, NonZeroUsize)> {
+        list.iter()
+            .map(|&(text, cells)| (text.to_string(), NonZeroUsize::new(cells).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn pairs_compare_both_ways() {
+        // the inputs without a 0, twice, then those without a 0 or an F
+        let programs = programs(&[
+            (">+[>.,[>]<<]", 3),
+            ("+[.,]", 1),
+            (">+[>.,+[-[>]]<<]", 3),
+            // 467 states
+            (",[>,]", 2),
+        ]);
+        let options = BuildOptions {
+            max_states: Some(100),
+            ..BuildOptions::default()
+        };
+        let matrix = equiv_matrix(&programs, &options);
+        assert_eq!(matrix.len(), 4);
+
+        let different = |accepted_by| MatrixEntry::Different {
+            witness: vec![0xF],
+            accepted_by,
+        };
+        for program in 0..3 {
+            assert_eq!(matrix.get(program, program), &MatrixEntry::Equivalent);
+            assert_eq!(matrix.build_error(program), None);
+            assert_eq!(matrix.get(program, 3), &MatrixEntry::BuildFailed);
+            assert_eq!(matrix.get(3, program), &MatrixEntry::BuildFailed);
+        }
+        assert_eq!(matrix.get(0, 1), &MatrixEntry::Equivalent);
+        assert_eq!(matrix.get(0, 2), &different(Side::A));
+        assert_eq!(matrix.get(2, 0), &different(Side::B));
+        assert_eq!(matrix.get(1, 2), &different(Side::A));
+        assert_eq!(matrix.get(3, 3), &MatrixEntry::BuildFailed);
+        assert_eq!(
+            matrix.build_error(3),
+            Some(BuildError::StateLimit { limit: 100 })
+        );
+    }
+
+    #[test]
+    fn no_programs() {
+        let matrix = equiv_matrix(&[], &BuildOptions::default());
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.len(), 0);
+    }
+}
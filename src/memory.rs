@@ -0,0 +1,178 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    mem::size_of,
+};
+
+use crate::{merge::Merger, Row, State, Table};
+
+/// Heap bytes held by a [`Table`], from the capacities of its buffers. Allocator overhead
+/// is not included.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub rows: usize,
+    /// The state to row map, empty when every state owns its row.
+    pub row_ids: usize,
+    pub costs: usize,
+}
+
+impl MemoryStats {
+    pub fn total(&self) -> usize {
+        self.rows + self.row_ids + self.costs
+    }
+}
+
+/// Peak heap bytes of the build's own structures, from their capacities, sampled every
+/// [`BuildMemory::SAMPLE_INTERVAL`] expanded states and at the end. Hash maps count one
+/// control byte per slot on top of their entries, as the standard library's table uses, but
+/// not the slack between their capacity and bucket count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildMemory {
+    /// The map from every discovered state to its id, tapes included, less the states
+    /// [`BuildOptions::merge_every`] merged away.
+    ///
+    /// [`BuildOptions::merge_every`]: crate::BuildOptions::merge_every
+    pub states: usize,
+    /// The pool deduplicating transition rows, or the rows kept for merging.
+    pub rows: usize,
+    /// States waiting to be expanded, tapes included.
+    pub frontier: usize,
+}
+
+impl BuildMemory {
+    pub const SAMPLE_INTERVAL: usize = 1024;
+
+    pub fn total(&self) -> usize {
+        self.states + self.rows + self.frontier
+    }
+
+    /// Takes a sample, `state_heap` and `frontier_heap` being the tape bytes owned by the
+    /// states in `states` and `frontier`, keeping the larger of each part.
+    pub(crate) fn sample<S>(
+        &mut self,
+        states: &HashMap<State, usize, S>,
+        state_heap: usize,
+        rows: &HashMap<Row, usize, S>,
+        merger: Option<&Merger>,
+        frontier: &VecDeque<State>,
+        frontier_heap: usize,
+    ) {
+        self.states = self.states.max(map_bytes(states) + state_heap);
+        self.rows = self
+            .rows
+            .max(map_bytes(rows) + merger.map_or(0, Merger::row_bytes));
+        self.frontier = self
+            .frontier
+            .max(frontier.capacity() * size_of::<State>() + frontier_heap);
+    }
+}
+
+pub(crate) fn map_bytes<K, V, S>(map: &HashMap<K, V, S>) -> usize {
+    map.capacity() * (size_of::<(K, V)>() + 1)
+}
+
+impl State {
+    /// Bytes of tape that spilled out of line.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        match &self.inner {
+            Some(inner) if inner.cells.0.spilled() => inner.cells.0.capacity(),
+            _ => 0,
+        }
+    }
+}
+
+impl Table {
+    /// Heap bytes held by the table, from the capacities of its buffers.
+    pub fn memory_footprint(&self) -> MemoryStats {
+        MemoryStats {
+            rows: self.rows.capacity() * size_of::<Row>(),
+            row_ids: self.row_ids.capacity() * size_of::<usize>(),
+            costs: self.costs.capacity() * size_of::<[u32; 16]>(),
+        }
+    }
+}
+
+impl Display for BuildMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} bytes ({} states, {} rows, {} frontier)",
+            self.total(),
+            self.states,
+            self.rows,
+            self.frontier
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{mem::size_of, num::NonZeroUsize};
+
+    use crate::{BuildOptions, Program, Row, Table};
+
+    use super::{BuildMemory, MemoryStats};
+
+    #[test]
+    fn footprint_of_plain_rows() {
+        let table = Table::from_rows(vec![(false, [1; 16]), (true, [1; 16])]);
+        assert_eq!(
+            table.memory_footprint(),
+            MemoryStats {
+                rows: 2 * size_of::<Row>(),
+                row_ids: 0,
+                costs: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn built_tables_hold_row_ids_and_costs() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            let footprint = table.memory_footprint();
+            assert!(footprint.row_ids >= table.state_count() * size_of::<usize>());
+            assert!(footprint.costs >= table.state_count() * size_of::<[u32; 16]>());
+            assert_eq!(
+                footprint.total(),
+                footprint.rows + footprint.row_ids + footprint.costs
+            );
+
+            // minimizing drops the costs and gives every state its own row
+            let minimized = table.minimized().memory_footprint();
+            assert_eq!((minimized.row_ids, minimized.costs), (0, 0));
+        }
+    }
+
+    #[test]
+    fn build_peaks() {
+        let build = |cells, track_tape_wrap| {
+            let program = Program::new(",>,[-<->]<[>.,<]", NonZeroUsize::new(cells).unwrap());
+            let options = BuildOptions {
+                track_tape_wrap,
+                ..BuildOptions::default()
+            };
+            Table::build_with(&program, &options)
+                .unwrap()
+                .1
+                .peak_memory
+                .unwrap()
+        };
+        let small = build(2, false);
+        assert!(small.states > 0 && small.rows > 0 && small.frontier > 0);
+        // tapes past the inline bytes are counted too
+        let spilled = build(2 * crate::U4VEC_INLINE_BYTES + 1, true);
+        assert!(spilled.states > small.states);
+
+        let memory = BuildMemory {
+            states: 10,
+            rows: 20,
+            frontier: 3,
+        };
+        assert_eq!(memory.total(), 33);
+        assert_eq!(
+            memory.to_string(),
+            "33 bytes (10 states, 20 rows, 3 frontier)"
+        );
+    }
+}
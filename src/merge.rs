@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::{memory::map_bytes, Row, State, Table};
+
+/// Bookkeeping for [`BuildOptions::merge_every`]: the rows of the expanded states, split into
+/// those whose languages are known and those that can still reach an unexpanded state.
+///
+/// [`BuildOptions::merge_every`]: crate::BuildOptions::merge_every
+pub(crate) struct Merger {
+    every: usize,
+    next_merge: usize,
+    discovered: usize,
+    /// Rows of the expanded states that can reach an unexpanded state. Their edges never lead
+    /// to a merged state.
+    unsettled: HashMap<usize, Row, FxBuildHasher>,
+    /// Rows of the states that can't reach an unexpanded state and survived merging, no two of
+    /// which are equivalent. Their edges only lead to each other.
+    settled: HashMap<usize, Row, FxBuildHasher>,
+}
+
+impl Merger {
+    pub(crate) fn new(every: usize) -> Self {
+        Self {
+            every: every.max(1),
+            next_merge: every.max(1),
+            discovered: 0,
+            unsettled: HashMap::default(),
+            settled: HashMap::default(),
+        }
+    }
+
+    pub(crate) fn discovered(&mut self) {
+        self.discovered += 1;
+    }
+
+    /// Bytes held by the rows, per [`crate::BuildMemory`].
+    pub(crate) fn row_bytes(&self) -> usize {
+        map_bytes(&self.unsettled) + map_bytes(&self.settled)
+    }
+
+    /// Records the row of a newly expanded state, merging once enough states are known.
+    /// `state_heap` is the tape bytes owned by `state_ids`, reduced by those merged away.
+    pub(crate) fn expanded<S>(
+        &mut self,
+        id: usize,
+        row: Row,
+        state_ids: &mut HashMap<State, usize, S>,
+        state_heap: &mut usize,
+    ) {
+        self.unsettled.insert(id, row);
+        if self.discovered >= self.next_merge {
+            self.next_merge = self.discovered + self.every;
+            self.merge(state_ids, state_heap);
+        }
+    }
+
+    /// Settles the unsettled states that can no longer reach an unexpanded state, and merges
+    /// each into an equivalent settled state if there is one, or the smallest of those
+    /// equivalent to it. Only the newly settled states and the settled states they reach are
+    /// refined. The configurations of merged states are forgotten, so reaching one again
+    /// explores it again.
+    fn merge<S>(&mut self, state_ids: &mut HashMap<State, usize, S>, state_heap: &mut usize) {
+        // an edge to a state with no row leads to an unexpanded state
+        let mut predecessors: HashMap<usize, Vec<usize>, FxBuildHasher> = HashMap::default();
+        let mut reaching = HashSet::with_hasher(FxBuildHasher);
+        let mut stack = Vec::new();
+        for (&from, (_, edges)) in &self.unsettled {
+            for &to in edges {
+                if self.unsettled.contains_key(&to) {
+                    predecessors.entry(to).or_default().push(from);
+                } else if !self.settled.contains_key(&to) && reaching.insert(from) {
+                    stack.push(from);
+                }
+            }
+        }
+        while let Some(state) = stack.pop() {
+            for &from in predecessors.get(&state).into_iter().flatten() {
+                if reaching.insert(from) {
+                    stack.push(from);
+                }
+            }
+        }
+        let newly_settled: Vec<usize> = self
+            .unsettled
+            .keys()
+            .copied()
+            .filter(|state| !reaching.contains(state))
+            .collect();
+        if newly_settled.is_empty() {
+            return;
+        }
+
+        // successors of settled states are settled, so this is complete
+        let mut members = newly_settled.clone();
+        let mut stack = newly_settled.clone();
+        let mut included: HashSet<usize, FxBuildHasher> = newly_settled.iter().copied().collect();
+        while let Some(state) = stack.pop() {
+            let (_, edges) = self
+                .unsettled
+                .get(&state)
+                .unwrap_or_else(|| &self.settled[&state]);
+            for &to in edges {
+                if included.insert(to) {
+                    members.push(to);
+                    stack.push(to);
+                }
+            }
+        }
+        // numbered in id order, so that each block is represented by its smallest state
+        members.sort_unstable();
+        let local_ids: HashMap<usize, usize, FxBuildHasher> = members
+            .iter()
+            .enumerate()
+            .map(|(local, &state)| (state, local))
+            .collect();
+        let refinement = Table::from_rows(
+            members
+                .iter()
+                .map(|state| {
+                    let (accepting, edges) = self
+                        .unsettled
+                        .get(state)
+                        .unwrap_or_else(|| &self.settled[state]);
+                    (*accepting, edges.map(|to| local_ids[&to]))
+                })
+                .collect(),
+        )
+        .refine();
+
+        let mut alias: HashMap<usize, usize, FxBuildHasher> = HashMap::default();
+        for (local, &block) in refinement.partition.iter().enumerate() {
+            let representative = members[refinement.reps[block]];
+            if representative != members[local] {
+                alias.insert(members[local], representative);
+            }
+        }
+        // a settled state only leads to states settled no later, unless one of those was
+        // merged into a newly settled state with a smaller id
+        let redirect_settled = alias.keys().any(|state| self.settled.contains_key(state));
+        for state in newly_settled {
+            let row = self.unsettled.remove(&state).unwrap();
+            if !alias.contains_key(&state) {
+                self.settled.insert(state, row);
+            }
+        }
+        if alias.is_empty() {
+            return;
+        }
+        self.settled.retain(|state, _| !alias.contains_key(state));
+        let redirect = |(_, edges): &mut Row| {
+            *edges = edges.map(|to| alias.get(&to).copied().unwrap_or(to));
+        };
+        self.unsettled.values_mut().for_each(redirect);
+        if redirect_settled {
+            self.settled.values_mut().for_each(redirect);
+        } else {
+            for state in &members {
+                if let Some(row) = self.settled.get_mut(state) {
+                    redirect(row);
+                }
+            }
+        }
+        state_ids.retain(|state, &mut id| {
+            let merged = alias.contains_key(&id);
+            if merged {
+                *state_heap -= state.heap_bytes();
+            }
+            !merged
+        });
+    }
+
+    /// The table of the states that weren't merged away, keeping their order, with
+    /// `costs` and `halted` cut down to match.
+    pub(crate) fn finish(self, costs: Vec<[u32; 16]>, halted: Vec<bool>) -> (Table, Vec<bool>) {
+        let mut rows = self.unsettled;
+        rows.extend(self.settled);
+        let mut kept: Vec<usize> = rows.keys().copied().collect();
+        kept.sort_unstable();
+        let mut new_ids = vec![0; costs.len()];
+        for (new_id, &state) in kept.iter().enumerate() {
+            new_ids[state] = new_id;
+        }
+
+        let mut table = Table {
+            rows: Vec::new(),
+            row_ids: Vec::with_capacity(kept.len()),
+            costs: kept.iter().map(|&state| costs[state]).collect(),
+        };
+        let mut row_pool: HashMap<Row, usize, FxBuildHasher> = HashMap::with_hasher(FxBuildHasher);
+        for state in &kept {
+            let (accepting, edges) = rows[state];
+            let row = (accepting, edges.map(|to| new_ids[to]));
+            table.row_ids.push(*row_pool.entry(row).or_insert_with(|| {
+                table.rows.push(row);
+                table.rows.len() - 1
+            }));
+        }
+        let halted = kept.iter().map(|&state| halted[state]).collect();
+        (table, halted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{verify, BuildOptions, EquivResult, ExploreOrder, Program, Table};
+
+    const PROGRAMS: &[(&str, usize)] = &[
+        ("+[>,,.<]", 2),
+        (",>,[-<->]<[>.,<]", 2),
+        ("+[>,]+[[.,]+]", 3),
+        (">+[>.,[>]<<]", 3),
+        ("+[>.,[<->[-]]<[,]+]", 2),
+        (",>>+[.[,<<[->+>-<<]>[-<+>]>]+]", 3),
+        (",[-[-]]]", 1),
+    ];
+
+    #[test]
+    fn merging_keeps_the_language() {
+        for &(program_text, cells) in PROGRAMS {
+            let program = Program::new(program_text, NonZeroUsize::new(cells).unwrap());
+            let plain = Table::build(&program);
+            for order in [ExploreOrder::Bfs, ExploreOrder::Dfs] {
+                for merge_every in [1, 7] {
+                    let options = BuildOptions {
+                        order,
+                        merge_every: Some(merge_every),
+                        ..BuildOptions::default()
+                    };
+                    let (merged, _) = Table::build_with(&program, &options).unwrap();
+                    assert!(
+                        matches!(plain.compare(&merged), EquivResult::Equivalent { .. }),
+                        "{program_text} with {order:?}, merging every {merge_every}"
+                    );
+                    assert!(merged.state_count() <= plain.state_count());
+                    verify::exhaustive_check(&program, &merged, 3).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn merging_lowers_peak_memory() {
+        // depth-first, each finished branch settles while the others are explored
+        let program = Program::new(",>,>,[.,]", NonZeroUsize::new(3).unwrap());
+        let build = |merge_every| {
+            let options = BuildOptions {
+                order: ExploreOrder::Dfs,
+                merge_every,
+                ..BuildOptions::default()
+            };
+            Table::build_with(&program, &options).unwrap()
+        };
+        let (plain, plain_report) = build(None);
+        let (merged, merged_report) = build(Some(16));
+        assert!(matches!(
+            plain.compare(&merged),
+            EquivResult::Equivalent { .. }
+        ));
+
+        let plain_memory = plain_report.peak_memory.unwrap();
+        let merged_memory = merged_report.peak_memory.unwrap();
+        assert!(
+            merged_memory.states * 4 < plain_memory.states,
+            "{merged_memory} against {plain_memory}"
+        );
+        assert!(
+            merged_memory.total() < plain_memory.total(),
+            "{merged_memory} against {plain_memory}"
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::{collections::HashMap, fmt::Display};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::Table;
+
+/// The transformations of a table's states induced by input strings, closed under composition.
+#[derive(Clone, Debug)]
+pub struct Monoid {
+    /// `transformations[i][state]` is where the `i`th element sends `state`.
+    transformations: Vec<Vec<usize>>,
+    /// A shortest input inducing each element, the identity (empty input) first.
+    words: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MonoidError {
+    /// The monoid has more than `limit` elements.
+    SizeLimit { limit: usize },
+}
+
+impl Display for MonoidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SizeLimit { limit } => {
+                write!(f, "the transition monoid has more than {limit} elements")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MonoidError {}
+
+impl Monoid {
+    pub fn len(&self) -> usize {
+        self.transformations.len()
+    }
+
+    /// Never true, the identity is always an element.
+    pub fn is_empty(&self) -> bool {
+        self.transformations.is_empty()
+    }
+
+    pub fn transformation(&self, element: usize) -> &[usize] {
+        &self.transformations[element]
+    }
+
+    pub fn word(&self, element: usize) -> &[u8] {
+        &self.words[element]
+    }
+
+    /// Whether every element satisfies `x^n = x^(n+1)` for some `n`, i.e. the monoid contains
+    /// no non-trivial group.
+    pub fn is_aperiodic(&self) -> bool {
+        self.transformations.iter().all(|element| {
+            let mut seen = HashMap::with_hasher(FxBuildHasher);
+            let mut power = element.clone();
+            let mut exponent = 1;
+            loop {
+                if let Some(first) = seen.insert(power.clone(), exponent) {
+                    return exponent - first == 1;
+                }
+                power = power.iter().map(|&state| element[state]).collect();
+                exponent += 1;
+            }
+        })
+    }
+}
+
+impl Table {
+    /// The transition monoid, explored breadth-first from the identity. Fails once it grows
+    /// beyond `limit` elements.
+    ///
+    /// Partial tables are completed first, so their missing transitions become a sink.
+    pub fn transition_monoid(&self, limit: usize) -> Result<Monoid, MonoidError> {
+        let table = self.completed();
+        let identity: Vec<usize> = (0..table.state_count()).collect();
+        let mut ids = HashMap::with_hasher(FxBuildHasher);
+        ids.insert(identity.clone(), 0);
+        let mut monoid = Monoid {
+            transformations: vec![identity],
+            words: vec![Vec::new()],
+        };
+
+        let mut next = 0;
+        while next < monoid.len() {
+            for input in 0..16 {
+                let extended: Vec<usize> = monoid.transformations[next]
+                    .iter()
+                    .map(|&state| table.transition(state, input))
+                    .collect();
+                if ids.contains_key(&extended) {
+                    continue;
+                }
+                if monoid.len() == limit {
+                    return Err(MonoidError::SizeLimit { limit });
+                }
+                ids.insert(extended.clone(), monoid.len());
+                let mut word = monoid.words[next].clone();
+                word.push(input);
+                monoid.transformations.push(extended);
+                monoid.words.push(word);
+            }
+            next += 1;
+        }
+
+        Ok(monoid)
+    }
+
+    /// Whether the language is star-free (expressible in first-order logic), which holds
+    /// exactly when the transition monoid of the minimal table is aperiodic.
+    pub fn is_star_free(&self, limit: usize) -> Result<bool, MonoidError> {
+        // unreachable states would contribute transformations of their own
+        let minimal = self.minimized().restrict_reachable(0);
+        Ok(minimal.transition_monoid(limit)?.is_aperiodic())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::MonoidError;
+    use crate::{Program, Table};
+
+    /// Accepts the strings with an even number of 1s.
+    fn even_ones() -> Table {
+        let mut even = [0; 16];
+        even[1] = 1;
+        let mut odd = [1; 16];
+        odd[1] = 0;
+        Table::from_rows(vec![(true, even), (false, odd)])
+    }
+
+    #[test]
+    fn aperiodic_language() {
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let table = Table::build(&program);
+        assert_eq!(table.is_star_free(100), Ok(true));
+        let monoid = table.minimized().transition_monoid(100).unwrap();
+        // the identity, and the constant map to the dead state
+        assert_eq!(monoid.len(), 2);
+        assert_eq!(monoid.word(1), &[0]);
+        assert_eq!(monoid.transformation(1), &[1, 1]);
+    }
+
+    #[test]
+    fn parity_is_not_star_free() {
+        let monoid = even_ones().transition_monoid(100).unwrap();
+        assert_eq!(monoid.len(), 2);
+        assert_eq!(monoid.transformation(0), &[0, 1]);
+        assert_eq!(monoid.transformation(1), &[1, 0]);
+        assert_eq!(monoid.word(1), &[1]);
+        assert!(!monoid.is_aperiodic());
+        assert_eq!(even_ones().is_star_free(100), Ok(false));
+    }
+
+    #[test]
+    fn size_limit() {
+        assert_eq!(
+            even_ones().transition_monoid(1).unwrap_err(),
+            MonoidError::SizeLimit { limit: 1 }
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Table;
+
+/// Names to show for states in place of their ids, see [`DotOptions::names`] and
+/// [`JsonOptions::names`]. Only the output changes, never the table.
+///
+/// [`DotOptions::names`]: crate::DotOptions::names
+/// [`JsonOptions::names`]: crate::JsonOptions::names
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NameMap {
+    names: Vec<String>,
+}
+
+impl NameMap {
+    /// Names by state id. States past the end keep their ids.
+    pub fn new(names: Vec<String>) -> Self {
+        Self { names }
+    }
+
+    /// Names every state of `table` with `name(id, accepting)`.
+    pub fn from_fn(table: &Table, name: impl Fn(usize, bool) -> String) -> Self {
+        Self::new(
+            (0..table.state_count())
+                .map(|state| name(state, table.is_accepting(state)))
+                .collect(),
+        )
+    }
+
+    /// Names every reachable state by its [access string](Table::access_strings) in hex, `ε`
+    /// for the start state. Unreachable states keep their ids.
+    pub fn access_strings(table: &Table) -> Self {
+        Self::new(
+            table
+                .access_strings()
+                .into_iter()
+                .enumerate()
+                .map(|(state, access)| match access {
+                    Some(access) if access.is_empty() => "ε".to_string(),
+                    Some(access) => access.iter().map(|symbol| format!("{symbol:X}")).collect(),
+                    None => state.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, state: usize) -> Option<&str> {
+        self.names.get(state).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{exactly, no_zeros},
+        DotOptions, JsonOptions, Table,
+    };
+
+    use super::NameMap;
+
+    fn named(names: &[&str]) -> NameMap {
+        NameMap::new(names.iter().map(|name| name.to_string()).collect())
+    }
+
+    #[test]
+    fn names_label_dot_states() {
+        let options = DotOptions {
+            names: Some(named(&["start", "say \"sink\"\non two lines"])),
+            ..DotOptions::default()
+        };
+        let dot = no_zeros().dot_with(&options);
+        assert!(dot.contains("    0[peripheries=2, label=\"start\"];\n"));
+        assert!(dot.contains("    1[label=\"say \\\"sink\\\" on two lines\"];\n"));
+        // edges still use the ids
+        assert!(dot.contains("    0 -> 1 [label=\"0\"];\n"));
+
+        // states past the end keep their ids
+        let options = DotOptions {
+            names: Some(named(&["start"])),
+            ..DotOptions::default()
+        };
+        let dot = no_zeros().dot_with(&options);
+        assert!(dot.contains("label=\"start\""));
+        assert!(!dot.contains("    1["));
+    }
+
+    #[test]
+    fn names_in_json_are_ignored_on_import() {
+        let table = no_zeros();
+        let options = JsonOptions {
+            names: Some(named(&["a\"b", "sink"])),
+        };
+        let json = table.to_json_with(&options);
+        assert!(
+            json.starts_with("{\"start\":0,\"states\":[{\"name\":\"a\\\"b\",\"accepting\":true,")
+        );
+        assert!(json.contains("{\"name\":\"sink\",\"accepting\":false,"));
+        assert!(Table::from_json(&json).unwrap().rows().eq(table.rows()));
+    }
+
+    #[test]
+    fn generated_names() {
+        let names = NameMap::access_strings(&exactly(&[1, 0xA]));
+        let expected = ["ε", "1", "1A", "0"];
+        for (state, name) in expected.iter().enumerate() {
+            assert_eq!(names.get(state), Some(*name));
+        }
+        assert_eq!(names.get(4), None);
+
+        // 1 can't be reached
+        let unreachable = Table::from_rows(vec![(false, [0; 16]), (true, [1; 16])]);
+        assert_eq!(NameMap::access_strings(&unreachable).get(1), Some("1"));
+
+        let names = NameMap::from_fn(&no_zeros(), |state, accepting| {
+            format!("{state}{}", if accepting { "+" } else { "-" })
+        });
+        assert_eq!(names, named(&["0+", "1-"]));
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashMap, fmt::Display};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::{InnerState, Program, RunStats, Table};
+
+/// The machine as it waits for its next read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Configuration {
+    pub cells: Vec<u8>,
+    pub head_position: usize,
+    /// Position just past the `,` waiting for input, whose value is written to the cell under
+    /// the head once it arrives.
+    pub instruction_position: usize,
+}
+
+impl Display for Configuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (position, cell) in self.cells.iter().enumerate() {
+            if position > 0 {
+                write!(f, " ")?;
+            }
+            if position == self.head_position {
+                write!(f, "[{cell:X}]")?;
+            } else {
+                write!(f, "{cell:X}")?;
+            }
+        }
+        write!(f, " at instruction {}", self.instruction_position)
+    }
+}
+
+impl Configuration {
+    /// Cells past the end of a shrunk tape, which the head never reaches, read as 0.
+    pub(crate) fn new(inner: &InnerState, cell_count: usize) -> Self {
+        Self {
+            cells: (0..cell_count)
+                .map(|position| {
+                    if position / 2 < inner.cells.0.len() {
+                        inner.cells.get(position)
+                    } else {
+                        0
+                    }
+                })
+                .collect(),
+            head_position: inner.head_position,
+            instruction_position: inner.instruction_position,
+        }
+    }
+}
+
+impl Program {
+    /// The configuration after reading all of `input`, or `None` if the machine halts or
+    /// diverges before then.
+    pub fn configuration_after(&self, input: &[u8]) -> Option<Configuration> {
+        let mut seen_states = HashMap::with_hasher(FxBuildHasher);
+        let mut stats = RunStats::default();
+        let mut state =
+            self.run_with_next_input(self.initial_state(), 0, &mut seen_states, &mut stats);
+        for &symbol in input {
+            seen_states.clear();
+            state =
+                self.run_with_next_input(state.inner?, symbol & 0x0F, &mut seen_states, &mut stats);
+        }
+
+        Some(Configuration::new(&state.inner?, self.cell_count.get()))
+    }
+}
+
+/// A cursor for stepping through a table by hand: the current state and the states it was
+/// reached from, so that moves can be undone.
+#[derive(Clone, Debug)]
+pub struct Navigator<'t> {
+    table: &'t Table,
+    current: usize,
+    history: Vec<usize>,
+}
+
+impl<'t> Navigator<'t> {
+    /// Starts at the start state with an empty history.
+    pub fn new(table: &'t Table) -> Self {
+        Self {
+            table,
+            current: 0,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn table(&self) -> &'t Table {
+        self.table
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// States that [`Navigator::back`] returns to, oldest first.
+    pub fn history(&self) -> &[usize] {
+        &self.history
+    }
+
+    /// Takes the transition on `input`. Returns `false`, and stays put, if it's missing.
+    pub fn follow(&mut self, input: u8) -> bool {
+        let to = self.table.transition(self.current, input);
+        if to == Table::MISSING {
+            return false;
+        }
+        self.jump(to);
+        true
+    }
+
+    /// Moves to `state`, remembering the current one. Out of range states are ignored.
+    pub fn jump(&mut self, state: usize) {
+        if state < self.table.state_count() {
+            self.history.push(self.current);
+            self.current = state;
+        }
+    }
+
+    pub fn jump_to_start(&mut self) {
+        self.jump(0);
+    }
+
+    /// Moves to `state` without touching the history, like moving a selection. Out of range
+    /// states are ignored.
+    pub fn select(&mut self, state: usize) {
+        if state < self.table.state_count() {
+            self.current = state;
+        }
+    }
+
+    /// Returns to the previous state. Returns `false` if the history is empty.
+    pub fn back(&mut self) -> bool {
+        match self.history.pop() {
+            Some(previous) => {
+                self.current = previous;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{tests::exactly, Program, Table};
+
+    use super::{Configuration, Navigator};
+
+    #[test]
+    fn configurations_between_reads() {
+        let program = Program::new(",>,", NonZeroUsize::new(2).unwrap());
+        let start = program.configuration_after(&[]).unwrap();
+        assert_eq!(
+            start,
+            Configuration {
+                cells: vec![0, 0],
+                head_position: 0,
+                instruction_position: 1,
+            }
+        );
+
+        let after_one = program.configuration_after(&[5]).unwrap();
+        assert_eq!(after_one.cells, [5, 0]);
+        assert_eq!(after_one.head_position, 1);
+        assert_eq!(after_one.instruction_position, 3);
+        assert_eq!(after_one.to_string(), "5 [0] at instruction 3");
+        // only the low four bits are read
+        assert_eq!(program.configuration_after(&[0x15]), Some(after_one));
+
+        // the machine halts after its second read
+        assert_eq!(program.configuration_after(&[5, 7]), None);
+    }
+
+    #[test]
+    fn following_and_going_back() {
+        let table = exactly(&[3, 4]);
+        let mut navigator = Navigator::new(&table);
+        assert!(navigator.follow(3));
+        assert!(navigator.follow(4));
+        assert_eq!(navigator.current(), 2);
+        assert!(navigator.table().is_accepting(navigator.current()));
+        assert!(navigator.follow(0));
+        assert_eq!(navigator.current(), 3);
+        assert_eq!(navigator.history(), [0, 1, 2]);
+
+        assert!(navigator.back());
+        assert_eq!(navigator.current(), 2);
+        navigator.jump_to_start();
+        assert_eq!(navigator.current(), 0);
+        assert_eq!(navigator.history(), [0, 1, 2]);
+
+        while navigator.back() {}
+        assert_eq!(navigator.current(), 0);
+        assert!(navigator.history().is_empty());
+    }
+
+    #[test]
+    fn moves_that_go_nowhere() {
+        let mut edges = [Table::MISSING; 16];
+        edges[1] = 1;
+        let table = Table::from_rows(vec![(false, edges), (true, [1; 16])]);
+        let mut navigator = Navigator::new(&table);
+        assert!(!navigator.follow(0));
+        navigator.jump(2);
+        assert_eq!(navigator.current(), 0);
+        assert!(navigator.history().is_empty());
+        assert!(!navigator.back());
+
+        // selecting doesn't leave anything to go back to
+        navigator.select(1);
+        assert_eq!(navigator.current(), 1);
+        navigator.select(7);
+        assert_eq!(navigator.current(), 1);
+        assert!(!navigator.back());
+
+        navigator.jump(0);
+        assert!(navigator.follow(1));
+        assert_eq!(navigator.history(), [1, 0]);
+    }
+}
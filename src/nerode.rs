@@ -0,0 +1,129 @@
+use std::fmt::Display;
+
+use crate::Table;
+
+/// Accepted strings listed for each class by [`Table::nerode_classes`].
+const NERODE_SAMPLES: usize = 3;
+
+/// A Myhill–Nerode class: the strings after which the same suffixes are accepted, which is
+/// a state of the minimal automaton.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NerodeClass {
+    /// The state of the canonical minimal table, numbered breadth-first from the start.
+    pub state: usize,
+    /// A shortest string in the class, the lexicographically smallest among those.
+    pub representative: Vec<u8>,
+    /// Whether the strings in the class are accepted.
+    pub accepting: bool,
+    /// Up to three shortest suffixes accepted after the class's strings, shortest first and
+    /// then in lexicographic order. Empty for the class of strings that can't be completed.
+    pub residual_samples: Vec<Vec<u8>>,
+}
+
+/// A string in hex, `ε` if it's empty.
+fn hex(string: &[u8]) -> String {
+    if string.is_empty() {
+        "ε".to_string()
+    } else {
+        string.iter().map(|symbol| format!("{symbol:X}")).collect()
+    }
+}
+
+impl Display for NerodeClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "class {}: {}", self.state, hex(&self.representative))?;
+        if self.accepting {
+            f.write_str(", accepting")?;
+        }
+        if self.residual_samples.is_empty() {
+            f.write_str(", never accepted")
+        } else {
+            let samples: Vec<String> = self.residual_samples.iter().map(|s| hex(s)).collect();
+            write!(f, ", accepts after {}", samples.join(" "))
+        }
+    }
+}
+
+impl Table {
+    /// Every equivalence class of the language, one per state of the canonical minimal table
+    /// and in its order, so the start state's class comes first.
+    pub fn nerode_classes(&self) -> Vec<NerodeClass> {
+        let table = self.canonical();
+        table
+            .access_strings()
+            .into_iter()
+            .enumerate()
+            .map(|(state, access)| NerodeClass {
+                state,
+                representative: access.expect("canonical tables only have reachable states"),
+                accepting: table.is_accepting(state),
+                residual_samples: table
+                    .restrict_reachable(state)
+                    .shortest_accepted_strings(NERODE_SAMPLES),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NerodeClass;
+    use crate::{tests::exactly, Table};
+
+    #[test]
+    fn classes_of_a_word() {
+        let classes = exactly(&[1, 2]).nerode_classes();
+        assert_eq!(
+            classes,
+            [
+                NerodeClass {
+                    state: 0,
+                    representative: vec![],
+                    accepting: false,
+                    residual_samples: vec![vec![1, 2]],
+                },
+                NerodeClass {
+                    state: 1,
+                    representative: vec![0],
+                    accepting: false,
+                    residual_samples: vec![],
+                },
+                NerodeClass {
+                    state: 2,
+                    representative: vec![1],
+                    accepting: false,
+                    residual_samples: vec![vec![2]],
+                },
+                NerodeClass {
+                    state: 3,
+                    representative: vec![1, 2],
+                    accepting: true,
+                    residual_samples: vec![vec![]],
+                },
+            ]
+        );
+        let lines: Vec<String> = classes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "class 0: ε, accepts after 12",
+                "class 1: 0, never accepted",
+                "class 2: 1, accepts after 2",
+                "class 3: 12, accepting, accepts after ε",
+            ]
+        );
+    }
+
+    #[test]
+    fn equivalent_states_share_a_class() {
+        // even lengths, with a redundant copy of the start state
+        let table = Table::from_rows(vec![(true, [1; 16]), (false, [2; 16]), (true, [1; 16])]);
+        let classes = table.nerode_classes();
+        assert_eq!(classes.len(), 2);
+        assert_eq!(
+            classes[0].to_string(),
+            "class 0: ε, accepting, accepts after ε 00 01"
+        );
+        assert_eq!(classes[1].to_string(), "class 1: 0, accepts after 0 1 2");
+    }
+}
@@ -0,0 +1,326 @@
+use std::{collections::HashMap, fmt::Display};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::Table;
+
+/// A nondeterministic automaton over the 16 input symbols, with epsilon transitions.
+///
+/// The combinators use the textbook epsilon constructions and never look at the languages
+/// involved, so [`Nfa::determinize`] is where the cost is paid.
+#[derive(Clone, Debug)]
+pub struct Nfa {
+    starts: Vec<usize>,
+    accepting: Vec<bool>,
+    transitions: Vec<[Vec<usize>; 16]>,
+    epsilon: Vec<Vec<usize>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeterminizeError {
+    /// The subset construction needed more than `limit` states.
+    StateLimit { limit: usize },
+}
+
+impl Display for DeterminizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StateLimit { limit } => {
+                write!(f, "determinization exceeded the limit of {limit} states")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeterminizeError {}
+
+impl From<&Table> for Nfa {
+    /// Missing transitions of a partial table simply have no target.
+    fn from(table: &Table) -> Self {
+        let mut nfa = Self::empty();
+        for (accepting, _) in table.rows() {
+            nfa.add_state(*accepting);
+        }
+        for (from, (_, edges)) in table.rows().enumerate() {
+            for (input, &to) in edges.iter().enumerate() {
+                if to != Table::MISSING {
+                    nfa.transitions[from][input].push(to);
+                }
+            }
+        }
+        nfa.starts.push(0);
+        nfa
+    }
+}
+
+impl Nfa {
+    fn empty() -> Self {
+        Self {
+            starts: Vec::new(),
+            accepting: Vec::new(),
+            transitions: Vec::new(),
+            epsilon: Vec::new(),
+        }
+    }
+
+    fn add_state(&mut self, accepting: bool) -> usize {
+        self.accepting.push(accepting);
+        self.transitions.push(std::array::from_fn(|_| Vec::new()));
+        self.epsilon.push(Vec::new());
+        self.accepting.len() - 1
+    }
+
+    /// Appends a copy of `other` and returns the id offset of its states.
+    fn append(&mut self, other: &Nfa) -> usize {
+        let offset = self.state_count();
+        self.accepting.extend_from_slice(&other.accepting);
+        self.transitions
+            .extend(other.transitions.iter().map(|edges| {
+                edges.clone().map(|targets| {
+                    targets
+                        .into_iter()
+                        .map(|to| to + offset)
+                        .collect::<Vec<_>>()
+                })
+            }));
+        self.epsilon.extend(
+            other
+                .epsilon
+                .iter()
+                .map(|targets| targets.iter().map(|to| to + offset).collect::<Vec<_>>()),
+        );
+        offset
+    }
+
+    /// An automaton without epsilon transitions starting at `start`.
+    pub(crate) fn from_parts(
+        accepting: Vec<bool>,
+        transitions: Vec<[Vec<usize>; 16]>,
+        start: usize,
+    ) -> Self {
+        Self {
+            starts: vec![start],
+            epsilon: vec![Vec::new(); accepting.len()],
+            accepting,
+            transitions,
+        }
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn union(&self, other: &Nfa) -> Nfa {
+        let mut nfa = self.clone();
+        let offset = nfa.append(other);
+        nfa.starts
+            .extend(other.starts.iter().map(|start| start + offset));
+        nfa
+    }
+
+    pub fn concat(&self, other: &Nfa) -> Nfa {
+        let mut nfa = self.clone();
+        let offset = nfa.append(other);
+        for (state, &accepting) in self.accepting.iter().enumerate() {
+            if accepting {
+                nfa.accepting[state] = false;
+                nfa.epsilon[state].extend(other.starts.iter().map(|start| start + offset));
+            }
+        }
+        nfa
+    }
+
+    /// Kleene star, through a fresh accepting start state.
+    pub fn star(&self) -> Nfa {
+        let mut nfa = self.clone();
+        let hub = nfa.add_state(true);
+        nfa.epsilon[hub] = self.starts.clone();
+        for (state, &accepting) in self.accepting.iter().enumerate() {
+            if accepting {
+                nfa.epsilon[state].push(hub);
+            }
+        }
+        nfa.starts = vec![hub];
+        nfa
+    }
+
+    /// The automaton of the reversed language.
+    pub fn reversed(&self) -> Nfa {
+        let mut nfa = Self::empty();
+        for state in 0..self.state_count() {
+            nfa.add_state(self.starts.contains(&state));
+        }
+        for from in 0..self.state_count() {
+            for (input, targets) in self.transitions[from].iter().enumerate() {
+                for &to in targets {
+                    nfa.transitions[to][input].push(from);
+                }
+            }
+            for &to in &self.epsilon[from] {
+                nfa.epsilon[to].push(from);
+            }
+        }
+        nfa.starts = (0..self.state_count())
+            .filter(|&state| self.accepting[state])
+            .collect();
+        nfa
+    }
+
+    /// Extends `states` with everything reachable through epsilon transitions, sorted.
+    fn close(&self, states: &mut Vec<usize>) {
+        let mut included = vec![false; self.state_count()];
+        let mut stack = Vec::new();
+        for &state in states.iter() {
+            if !included[state] {
+                included[state] = true;
+                stack.push(state);
+            }
+        }
+        while let Some(state) = stack.pop() {
+            for &to in &self.epsilon[state] {
+                if !included[to] {
+                    included[to] = true;
+                    stack.push(to);
+                }
+            }
+        }
+        states.clear();
+        states.extend((0..self.state_count()).filter(|&state| included[state]));
+    }
+
+    fn step(&self, states: &[usize], input: usize) -> Vec<usize> {
+        let mut next: Vec<usize> = states
+            .iter()
+            .flat_map(|&state| self.transitions[state][input].iter().copied())
+            .collect();
+        self.close(&mut next);
+        next
+    }
+
+    /// Only the low four bits of each symbol are used, as in [`Table::accepts`].
+    pub fn accepts(&self, input: &[u8]) -> bool {
+        let mut current = self.starts.clone();
+        self.close(&mut current);
+        for &symbol in input {
+            current = self.step(&current, (symbol & 0x0F) as usize);
+        }
+        current.iter().any(|&state| self.accepting[state])
+    }
+
+    /// Subset construction, giving up once more than `max_states` states are needed. The
+    /// result is complete and numbered breadth-first.
+    pub fn determinize(&self, max_states: usize) -> Result<Table, DeterminizeError> {
+        let mut start = self.starts.clone();
+        self.close(&mut start);
+
+        let mut subset_ids: HashMap<Vec<usize>, usize, FxBuildHasher> =
+            HashMap::with_hasher(FxBuildHasher);
+        let mut subsets = vec![start.clone()];
+        subset_ids.insert(start, 0);
+        let mut rows = Vec::new();
+
+        while rows.len() < subsets.len() {
+            let current = subsets[rows.len()].clone();
+            let mut edges = [0; 16];
+            for (input, edge) in edges.iter_mut().enumerate() {
+                let next = self.step(&current, input);
+                *edge = match subset_ids.get(&next) {
+                    Some(&id) => id,
+                    None => {
+                        if subsets.len() == max_states {
+                            return Err(DeterminizeError::StateLimit { limit: max_states });
+                        }
+                        subset_ids.insert(next.clone(), subsets.len());
+                        subsets.push(next);
+                        subsets.len() - 1
+                    }
+                };
+            }
+            let accepting = current.iter().any(|&state| self.accepting[state]);
+            rows.push((accepting, edges));
+        }
+
+        Ok(Table::from_rows(rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::{DeterminizeError, Nfa};
+    use crate::{
+        tests::{exactly, short_inputs},
+        EquivResult, Program, Table,
+    };
+
+    /// The inputs without a 0, from a benchmark program.
+    fn no_zeros() -> Nfa {
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        Nfa::from(&Table::build(&program).minimized())
+    }
+
+    /// Checks the determinized automaton against the NFA on every short input.
+    fn determinized(nfa: &Nfa) -> Table {
+        let table = nfa.determinize(1000).unwrap();
+        for input in short_inputs(3) {
+            assert_eq!(table.accepts(&input), nfa.accepts(&input), "{input:?}");
+        }
+        table
+    }
+
+    #[test]
+    fn concatenation() {
+        let nfa = no_zeros().concat(&Nfa::from(&exactly(&[0])));
+        let table = determinized(&nfa);
+        for accepted in [&[0][..], &[1, 2, 0]] {
+            assert!(table.accepts(accepted), "{accepted:?}");
+        }
+        for rejected in [&[][..], &[1, 0, 1], &[0, 0]] {
+            assert!(!table.accepts(rejected), "{rejected:?}");
+        }
+    }
+
+    #[test]
+    fn star() {
+        let table = determinized(&Nfa::from(&exactly(&[0, 1])).star());
+        for accepted in [&[][..], &[0, 1], &[0, 1, 0, 1]] {
+            assert!(table.accepts(accepted), "{accepted:?}");
+        }
+        for rejected in [&[0][..], &[0, 1, 0], &[1, 0]] {
+            assert!(!table.accepts(rejected), "{rejected:?}");
+        }
+    }
+
+    #[test]
+    fn union_and_reversal() {
+        let table = determinized(&no_zeros().union(&Nfa::from(&exactly(&[0]))));
+        assert!(table.accepts(&[0]));
+        assert!(table.accepts(&[1, 2]));
+        assert!(!table.accepts(&[0, 0]));
+
+        let table = determinized(&Nfa::from(&exactly(&[1, 2])).reversed());
+        assert!(table.accepts(&[2, 1]));
+        assert!(!table.accepts(&[1, 2]));
+    }
+
+    #[test]
+    fn determinizing_a_table_keeps_its_language() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program).minimized();
+            let determinized = Nfa::from(&table).determinize(100_000).unwrap();
+            assert!(matches!(
+                determinized.compare(&table),
+                EquivResult::Equivalent { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn determinize_respects_the_limit() {
+        assert_eq!(
+            Nfa::from(&exactly(&[0, 1])).determinize(2).unwrap_err(),
+            DeterminizeError::StateLimit { limit: 2 }
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt::Display;
+
+use crate::{Instruction, Program, Span};
+
+impl Display for Program {
+    /// The instructions alone, without the comments of the original text.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for instruction in &self.instructions {
+            write!(f, "{}", instruction.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+impl Program {
+    /// A canonical program with the same language:
+    ///
+    /// - runs of `+`/`-` and of `<`/`>` are replaced by their net effect, written with the
+    ///   shorter of the two directions (ties go to `+` and `>`), modulo 16 and the cell count;
+    /// - runs of `.` become a single `.`;
+    /// - everything after the last `,` or `.` is dropped, except what closes the loops still
+    ///   open there.
+    ///
+    /// Normalizing twice gives the same program. Spans refer to the [`Display`] text.
+    pub fn normalize(&self) -> Program {
+        // merging runs can make their neighbours adjacent, so repeat until nothing changes
+        let mut instructions = self.instructions.clone();
+        loop {
+            let next = normalize_pass(&instructions, self.cell_count.get());
+            if next == instructions {
+                break;
+            }
+            instructions = next;
+        }
+
+        let spans = (0..instructions.len())
+            .map(|start| Span {
+                start,
+                end: start + 1,
+            })
+            .collect();
+        Program {
+            cell_count: self.cell_count,
+            instructions,
+            spans,
+        }
+    }
+}
+
+fn normalize_pass(program: &[Instruction], cell_count: usize) -> Vec<Instruction> {
+    use Instruction::*;
+
+    let mut instructions = Vec::new();
+    let mut position = 0;
+    while position < program.len() {
+        let kind = run_kind(program[position]);
+        let run = program[position..]
+            .iter()
+            .take_while(|&&instruction| run_kind(instruction) == kind)
+            .count();
+        let window = &program[position..position + run];
+        match kind {
+            RunKind::Arithmetic => {
+                let net = net(window, Increment, 16);
+                push_net(&mut instructions, net, 16, Increment, Decrement);
+            }
+            RunKind::Movement => {
+                let net = net(window, MoveRight, cell_count);
+                push_net(&mut instructions, net, cell_count, MoveRight, MoveLeft);
+            }
+            RunKind::Accept => instructions.push(Accept),
+            RunKind::Other => instructions.extend_from_slice(window),
+        }
+        position += run;
+    }
+
+    if let Some(last_io) = instructions
+        .iter()
+        .rposition(|instruction| matches!(instruction, Read | Accept))
+    {
+        let open = instructions[..last_io]
+            .iter()
+            .fold(0usize, |depth, instruction| match instruction {
+                StartLoop => depth + 1,
+                EndLoop => depth.saturating_sub(1),
+                _ => depth,
+            });
+        let mut depth = open;
+        let mut end = last_io + 1;
+        while depth > 0 && end < instructions.len() {
+            match instructions[end] {
+                StartLoop => depth += 1,
+                EndLoop => depth -= 1,
+                _ => {}
+            }
+            end += 1;
+        }
+        if depth == 0 {
+            instructions.truncate(end);
+        }
+    } else {
+        // nothing can ever be accepted
+        instructions.clear();
+    }
+
+    instructions
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RunKind {
+    Arithmetic,
+    Movement,
+    Accept,
+    Other,
+}
+
+fn run_kind(instruction: Instruction) -> RunKind {
+    match instruction {
+        Instruction::Increment | Instruction::Decrement => RunKind::Arithmetic,
+        Instruction::MoveLeft | Instruction::MoveRight => RunKind::Movement,
+        Instruction::Accept => RunKind::Accept,
+        _ => RunKind::Other,
+    }
+}
+
+/// Net number of `forward` steps taken by the run, modulo `modulus`.
+fn net(run: &[Instruction], forward: Instruction, modulus: usize) -> usize {
+    let forward_count = run
+        .iter()
+        .filter(|&&instruction| instruction == forward)
+        .count();
+    let backward_count = run.len() - forward_count;
+    (forward_count % modulus + modulus - backward_count % modulus) % modulus
+}
+
+fn push_net(
+    instructions: &mut Vec<Instruction>,
+    net: usize,
+    modulus: usize,
+    forward: Instruction,
+    backward: Instruction,
+) {
+    if net * 2 <= modulus {
+        instructions.extend(std::iter::repeat_n(forward, net));
+    } else {
+        instructions.extend(std::iter::repeat_n(backward, modulus - net));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{EquivResult, Program, Table};
+
+    fn assert_same_language(a: &Program, b: &Program) {
+        assert!(matches!(
+            Table::build(a).compare(&Table::build(b)),
+            EquivResult::Equivalent { .. }
+        ));
+    }
+
+    #[test]
+    fn churn_collapses() {
+        let program = Program::new(
+            "++-- , >><< +++++++++++++++ [-..] >>> +-+",
+            NonZeroUsize::new(2).unwrap(),
+        );
+        let normalized = program.normalize();
+        assert_eq!(normalized.to_string(), ",-[-.]");
+        assert_eq!(normalized.spans[2].start, 2);
+        assert_same_language(&program, &normalized);
+        assert_eq!(normalized.normalize().to_string(), normalized.to_string());
+    }
+
+    #[test]
+    fn programs_that_never_accept_become_empty() {
+        let program = Program::new("+[>+]", NonZeroUsize::new(2).unwrap());
+        let normalized = program.normalize();
+        assert!(normalized.instructions.is_empty());
+        assert_same_language(&program, &normalized);
+    }
+
+    #[test]
+    fn benchmarks_keep_their_language() {
+        for program in crate::tests::benchmarks() {
+            let normalized = program.normalize();
+            assert_same_language(&program, &normalized);
+            assert_eq!(normalized.normalize().to_string(), normalized.to_string());
+        }
+    }
+}
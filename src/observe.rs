@@ -0,0 +1,145 @@
+use std::ops::ControlFlow;
+
+use crate::{BuildError, BuildOptions, BuildReport, Configuration, State, Table};
+
+/// A state as [`Table::build_observed`] discovers it.
+pub struct DiscoveredState<'a> {
+    pub(crate) state: &'a State,
+    pub(crate) cell_count: usize,
+}
+
+impl DiscoveredState<'_> {
+    pub fn is_accepting(&self) -> bool {
+        self.state.accepting
+    }
+
+    /// Whether the machine has halted, in which case every transition loops back.
+    pub fn is_halted(&self) -> bool {
+        self.state.inner.is_none()
+    }
+
+    /// The machine waiting for its next read, or `None` if it has halted. Computed on each
+    /// call, so only ask for it when needed.
+    pub fn configuration(&self) -> Option<Configuration> {
+        let inner = self.state.inner.as_ref()?;
+        Some(Configuration::new(inner, self.cell_count))
+    }
+}
+
+/// The callback [`Table::explore`] passes each new state to.
+pub(crate) type Observer<'a> = &'a mut dyn FnMut(usize, &DiscoveredState) -> ControlFlow<()>;
+
+impl Table {
+    /// Like [`Table::build_with`], but calls `on_state_discovered` with each new state's id,
+    /// exactly once per state and in id order, as soon as the build comes across it.
+    /// Returning [`ControlFlow::Break`] stops the build with [`BuildError::Aborted`].
+    ///
+    /// The states are explored on the program's whole tape and without
+    /// [`BuildOptions::rotation_symmetry`], so that their configurations are the machine's own.
+    pub fn build_observed(
+        program: &crate::Program,
+        options: &BuildOptions,
+        mut on_state_discovered: impl FnMut(usize, &DiscoveredState) -> ControlFlow<()>,
+    ) -> Result<(Self, BuildReport), BuildError> {
+        Self::explore(program, options, None, Some(&mut on_state_discovered))
+            .map(|(table, report, _)| (table, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, ops::ControlFlow};
+
+    use crate::{tests::short_inputs, BuildError, BuildOptions, Program, Table};
+
+    #[test]
+    fn every_state_is_observed_once_in_order() {
+        for program in crate::tests::benchmarks() {
+            let mut observed = Vec::new();
+            let (table, _) =
+                Table::build_observed(&program, &BuildOptions::default(), |id, state| {
+                    observed.push((id, state.is_accepting(), state.is_halted()));
+                    ControlFlow::Continue(())
+                })
+                .unwrap();
+
+            assert_eq!(observed.len(), table.state_count());
+            for (expected_id, &(id, accepting, halted)) in observed.iter().enumerate() {
+                assert_eq!(id, expected_id);
+                assert_eq!(accepting, table.is_accepting(id));
+                if halted {
+                    assert!((0..16).all(|input| table.transition(id, input) == id));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn configurations_match_the_program() {
+        let program = Program::new(",[.,]", NonZeroUsize::new(1).unwrap());
+        let mut configurations = Vec::new();
+        Table::build_observed(&program, &BuildOptions::default(), |_, state| {
+            configurations.push(state.configuration());
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+        assert_eq!(configurations.len(), 17);
+        assert_eq!(configurations[0], program.configuration_after(&[]));
+        // a 0 halts the machine straight away
+        assert_eq!(configurations[1], None);
+        assert_eq!(configurations[2], program.configuration_after(&[1]));
+    }
+
+    #[test]
+    fn configurations_on_a_wrapping_tape() {
+        // the head wraps to the last cell, which a shrunk or rotated tape would move
+        let program = Program::new("<,[.>+<,]", NonZeroUsize::new(5).unwrap());
+        assert!(program.shrunk_tape().is_some());
+        for rotation_symmetry in [false, true] {
+            let options = BuildOptions {
+                rotation_symmetry,
+                ..BuildOptions::default()
+            };
+            let mut configurations = Vec::new();
+            let (table, _) = Table::build_observed(&program, &options, |_, state| {
+                configurations.push(state.configuration());
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+
+            let start = configurations[0].as_ref().unwrap();
+            assert_eq!(start.head_position, 4);
+            assert_eq!(start.cells.len(), 5);
+            for input in short_inputs(2) {
+                let state = table.run_from(0, &input);
+                assert_eq!(
+                    configurations[state],
+                    program.configuration_after(&input),
+                    "{input:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn breaking_aborts_the_build() {
+        let program = Program::new(",[.,]", NonZeroUsize::new(1).unwrap());
+        let mut calls = 0;
+        let result = Table::build_observed(&program, &BuildOptions::default(), |id, _| {
+            calls += 1;
+            if id == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert!(matches!(result, Err(BuildError::Aborted { states: 4 })));
+        assert_eq!(calls, 4);
+
+        let result = Table::build_observed(&program, &BuildOptions::default(), |_, _| {
+            ControlFlow::Break(())
+        });
+        assert!(matches!(result, Err(BuildError::Aborted { states: 1 })));
+    }
+}
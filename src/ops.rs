@@ -0,0 +1,516 @@
+use std::{borrow::Cow, cmp::Ordering, collections::HashMap};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::Table;
+
+impl Table {
+    /// The automaton accepting exactly the strings this one rejects.
+    /// Partially defined tables are completed first, so missing transitions become accepted.
+    pub fn complement(&self) -> Table {
+        let mut table = Table {
+            rows: self.rows.clone(),
+            row_ids: self.row_ids.clone(),
+            costs: self.costs.clone(),
+        };
+        table.make_complete();
+        // every pooled row is used by some state, so flipping the pool flips every state
+        for (accepting, _) in &mut table.rows {
+            *accepting = !*accepting;
+        }
+        table
+    }
+}
+
+impl Table {
+    /// Reindexes the input columns, with `map[new_symbol] = old_symbol`: the result accepts `w`
+    /// exactly when this table accepts `w` with every symbol replaced through `map`. The map
+    /// doesn't need to be a permutation.
+    pub fn relabel_inputs(&self, map: [u8; 16]) -> Table {
+        Table {
+            rows: self
+                .rows
+                .iter()
+                .map(|(accepting, edges)| {
+                    (
+                        *accepting,
+                        map.map(|old_symbol| edges[(old_symbol & 0x0F) as usize]),
+                    )
+                })
+                .collect(),
+            row_ids: self.row_ids.clone(),
+            costs: self
+                .costs
+                .iter()
+                .map(|costs| map.map(|old_symbol| costs[(old_symbol & 0x0F) as usize]))
+                .collect(),
+        }
+    }
+}
+
+impl Table {
+    /// Reachable part of the product automaton, numbered breadth-first, accepting where
+    /// `combine` holds for the two component flags. Partial tables are completed first.
+    pub(crate) fn product(&self, other: &Table, combine: impl Fn(bool, bool) -> bool) -> Table {
+        let (left, right) = (self.completed(), other.completed());
+
+        let mut pair_ids = HashMap::with_hasher(FxBuildHasher);
+        let mut pairs = vec![(0, 0)];
+        pair_ids.insert((0, 0), 0);
+        let mut rows = Vec::new();
+
+        while rows.len() < pairs.len() {
+            let (a, b) = pairs[rows.len()];
+            let mut edges = [0; 16];
+            for (input, edge) in edges.iter_mut().enumerate() {
+                let next = (left.transitions(a)[input], right.transitions(b)[input]);
+                *edge = *pair_ids.entry(next).or_insert_with(|| {
+                    pairs.push(next);
+                    pairs.len() - 1
+                });
+            }
+            rows.push((combine(left.is_accepting(a), right.is_accepting(b)), edges));
+        }
+
+        Table::from_rows(rows)
+    }
+
+    /// Borrows complete tables as they are and completes a copy of partial ones.
+    pub(crate) fn completed(&self) -> Cow<'_, Table> {
+        if self.is_complete() {
+            Cow::Borrowed(self)
+        } else {
+            let mut table = self.clone();
+            table.make_complete();
+            Cow::Owned(table)
+        }
+    }
+
+    /// The partial view of this table: the states that can never reach acceptance are
+    /// dropped and the transitions into them become [`Table::MISSING`], so that a missing
+    /// transition means rejection. An empty language keeps a lone start state with no
+    /// transitions. [`Table::make_complete`] turns the result back into a complete table with
+    /// the same language, and doing both again gives the same partial table.
+    pub fn to_partial(&self) -> Table {
+        let mut trimmed = self.clone();
+        trimmed.trim_hopeless();
+        let co_reachable = trimmed.co_reachable();
+        if co_reachable.iter().all(|&live| live) {
+            return trimmed;
+        }
+        if !co_reachable[0] {
+            return Table::from_rows(vec![(false, [Table::MISSING; 16])]);
+        }
+
+        // trimming leaves exactly one dead state
+        let dead = co_reachable.iter().position(|&live| !live).unwrap();
+        let rows = trimmed
+            .rows()
+            .enumerate()
+            .filter(|&(state, _)| state != dead)
+            .map(|(_, &(accepting, edges))| {
+                (
+                    accepting,
+                    edges.map(|to| match to.cmp(&dead) {
+                        Ordering::Less => to,
+                        Ordering::Equal => Table::MISSING,
+                        Ordering::Greater => to - 1,
+                    }),
+                )
+            })
+            .collect();
+        Table::from_rows(rows)
+    }
+
+    pub fn intersection(&self, other: &Table) -> Table {
+        self.product(other, |a, b| a && b)
+    }
+
+    pub fn union(&self, other: &Table) -> Table {
+        self.product(other, |a, b| a || b)
+    }
+
+    /// The strings accepted by exactly one of the two tables.
+    pub fn symmetric_difference(&self, other: &Table) -> Table {
+        self.product(other, |a, b| a != b)
+    }
+
+    /// Strings of length at most `max_len` on which the two tables disagree, shortest first
+    /// and then in lexicographic order.
+    pub fn disagreements(&self, other: &Table, max_len: usize) -> Vec<Vec<u8>> {
+        self.symmetric_difference(other).accepted_strings(max_len)
+    }
+}
+
+impl Table {
+    /// The sub-automaton of states reachable from `from`, renumbered breadth-first so that
+    /// `from` becomes the start state. With `from = 0` this drops the unreachable states.
+    pub fn restrict_reachable(&self, from: usize) -> Table {
+        let mut new_ids = vec![Table::MISSING; self.state_count()];
+        let mut order = vec![from];
+        new_ids[from] = 0;
+
+        let mut next = 0;
+        while next < order.len() {
+            for &to in self.transitions(order[next]) {
+                if to != Table::MISSING && new_ids[to] == Table::MISSING {
+                    new_ids[to] = order.len();
+                    order.push(to);
+                }
+            }
+            next += 1;
+        }
+
+        let rows = order
+            .iter()
+            .map(|&old_id| {
+                let (accepting, edges) = *self.row(old_id);
+                (
+                    accepting,
+                    edges.map(|to| {
+                        if to == Table::MISSING {
+                            Table::MISSING
+                        } else {
+                            new_ids[to]
+                        }
+                    }),
+                )
+            })
+            .collect();
+        Table::from_rows(rows)
+    }
+
+    /// The state reached by reading `input` from `state`, or [`Table::MISSING`] if a missing
+    /// transition is taken on the way.
+    pub fn run_from(&self, state: usize, input: &[u8]) -> usize {
+        let mut state = state;
+        for &symbol in input {
+            state = self.transition(state, symbol);
+            if state == Table::MISSING {
+                break;
+            }
+        }
+        state
+    }
+
+    /// The state reached by reading `prefix` from the start, see [`Table::run_from`].
+    pub fn derivative_state(&self, prefix: &[u8]) -> usize {
+        self.run_from(0, prefix)
+    }
+
+    /// The automaton of `{ w : prefix·w is accepted }`: this table restarted from the state
+    /// `prefix` leads to.
+    pub fn derivative(&self, prefix: &[u8]) -> Table {
+        let table = self.completed();
+        table.restrict_reachable(table.derivative_state(prefix))
+    }
+
+    /// The automaton of `{ w : w·suffix is accepted }`: the same transitions, with the states
+    /// from which `suffix` leads to acceptance accepting. Partial tables are completed first.
+    pub fn right_quotient_str(&self, suffix: &[u8]) -> Table {
+        let table = self.completed();
+        Table::from_rows(
+            table
+                .rows()
+                .enumerate()
+                .map(|(state, &(_, edges))| {
+                    (table.is_accepting(table.run_from(state, suffix)), edges)
+                })
+                .collect(),
+        )
+    }
+
+    /// The automaton of `{ w : w·v is accepted for some v accepted by other }`: the same
+    /// transitions, with the states accepting whose residual language meets `other`'s.
+    ///
+    /// Every state is paired with `other`'s start state in one product, so that the pairs
+    /// shared between states are only explored once, and a state accepts if its pair can
+    /// reach a pair where both accept.
+    pub fn right_quotient(&self, other: &Table) -> Table {
+        let (left, right) = (self.completed(), other.completed());
+
+        let mut pair_ids = HashMap::with_hasher(FxBuildHasher);
+        let mut pairs: Vec<(usize, usize)> = (0..left.state_count()).map(|q| (q, 0)).collect();
+        for (id, &pair) in pairs.iter().enumerate() {
+            pair_ids.insert(pair, id);
+        }
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); pairs.len()];
+        let mut next = 0;
+        while next < pairs.len() {
+            let (a, b) = pairs[next];
+            for input in 0..16 {
+                let to = (left.transitions(a)[input], right.transitions(b)[input]);
+                let to_id = *pair_ids.entry(to).or_insert_with(|| {
+                    pairs.push(to);
+                    predecessors.push(Vec::new());
+                    pairs.len() - 1
+                });
+                predecessors[to_id].push(next);
+            }
+            next += 1;
+        }
+
+        let mut good: Vec<bool> = pairs
+            .iter()
+            .map(|&(a, b)| left.is_accepting(a) && right.is_accepting(b))
+            .collect();
+        let mut queue: Vec<usize> = (0..pairs.len()).filter(|&id| good[id]).collect();
+        while let Some(id) = queue.pop() {
+            for &from in &predecessors[id] {
+                if !good[from] {
+                    good[from] = true;
+                    queue.push(from);
+                }
+            }
+        }
+
+        Table::from_rows(
+            left.rows()
+                .enumerate()
+                .map(|(state, &(_, edges))| (good[state], edges))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{
+        tests::{exactly, short_inputs},
+        EquivResult, Program, Table,
+    };
+
+    #[test]
+    fn restricting_from_the_start() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program).minimized();
+            let restricted = table.restrict_reachable(0);
+            assert_eq!(restricted.state_count(), table.state_count());
+            assert!(matches!(
+                restricted.compare(&table),
+                EquivResult::Equivalent { .. }
+            ));
+        }
+
+        // 1 can't be reached
+        let table = Table::from_rows(vec![(true, [0; 16]), (false, [0; 16])]);
+        assert!(table
+            .restrict_reachable(0)
+            .rows()
+            .eq([(true, [0; 16])].iter()));
+    }
+
+    #[test]
+    fn restricting_from_a_mid_state() {
+        // the states of `exactly` are numbered by how much of the word they've read
+        let restricted = exactly(&[1, 2]).restrict_reachable(1);
+        let mut start = [1; 16];
+        start[2] = 2;
+        assert!(restricted
+            .rows()
+            .eq([(false, start), (false, [1; 16]), (true, [1; 16])].iter()));
+        assert!(restricted.accepts(&[2]));
+        assert!(!restricted.accepts(&[1, 2]));
+    }
+
+    #[test]
+    fn swapping_symbols() {
+        let mut map: [u8; 16] = std::array::from_fn(|symbol| symbol as u8);
+        map.swap(0, 1);
+        let swapped = exactly(&[0, 1]).relabel_inputs(map);
+        assert!(swapped.accepts(&[1, 0]));
+        assert!(!swapped.accepts(&[0, 1]));
+    }
+
+    #[test]
+    fn identity_relabeling_changes_nothing() {
+        let identity = std::array::from_fn(|symbol| symbol as u8);
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            let relabeled = table.relabel_inputs(identity);
+            assert!(relabeled.rows().eq(table.rows()));
+            assert_eq!(relabeled.cost_stats(), table.cost_stats());
+        }
+    }
+
+    #[test]
+    fn merging_symbols() {
+        // every symbol reads as 0
+        let relabeled = exactly(&[0, 0]).relabel_inputs([0; 16]);
+        assert!(relabeled.accepts(&[5, 0xF]));
+        assert!(!relabeled.accepts(&[5]));
+    }
+
+    /// Tables accepting the inputs without a 0, and those without a 0 or an F.
+    fn zero_and_f_free() -> (Table, Table) {
+        let cells = NonZeroUsize::new(3).unwrap();
+        let no_zeros = Table::build(&Program::new(">+[>.,[>]<<]", cells)).minimized();
+        let no_zeros_or_fs = Table::build(&Program::new(">+[>.,+[-[>]]<<]", cells)).minimized();
+        (no_zeros, no_zeros_or_fs)
+    }
+
+    #[test]
+    fn programs_differing_on_f() {
+        let (a, b) = zero_and_f_free();
+        let difference = a.symmetric_difference(&b);
+        assert_eq!(difference.shortest_accepted(), Some(vec![0xF]));
+
+        let disagreements = a.disagreements(&b, 2);
+        // one of length 1, and the 15 * 15 - 14 * 14 of length 2
+        assert_eq!(disagreements.len(), 30);
+        assert_eq!(disagreements[0], vec![0xF]);
+        assert_eq!(disagreements[1], vec![1, 0xF]);
+        for input in &disagreements {
+            assert!(input.contains(&0xF) && !input.contains(&0), "{input:?}");
+            assert!(a.accepts(input) && !b.accepts(input));
+        }
+        assert!(a.disagreements(&a, 3).is_empty());
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let (a, b) = zero_and_f_free();
+        let equivalent =
+            |x: &Table, y: &Table| matches!(x.compare(y), EquivResult::Equivalent { .. });
+        assert!(equivalent(&a.intersection(&b), &b));
+        assert!(equivalent(&a.union(&b), &a));
+        assert!(equivalent(
+            &a.intersection(&a.complement()),
+            &Table::from_rows(vec![(false, [0; 16])])
+        ));
+    }
+
+    #[test]
+    fn derivatives_consume_the_prefix() {
+        for program in crate::tests::benchmarks().take(4) {
+            let table = Table::build(&program).minimized();
+            for prefix in short_inputs(2) {
+                let derivative = table.derivative(&prefix);
+                assert_eq!(
+                    derivative.accepts(&[]),
+                    table.is_accepting(table.derivative_state(&prefix))
+                );
+                for suffix in short_inputs(2) {
+                    let whole = [&prefix[..], &suffix[..]].concat();
+                    assert_eq!(derivative.accepts(&suffix), table.accepts(&whole));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn derivatives_of_partial_tables() {
+        // a missing transition leads to the completing sink
+        let derivative = exactly(&[1, 2]).to_partial().derivative(&[2]);
+        assert!(derivative.is_complete());
+        assert_eq!(derivative.shortest_accepted(), None);
+        assert_eq!(
+            exactly(&[1, 2]).derivative(&[1]).shortest_accepted(),
+            Some(vec![2])
+        );
+    }
+
+    #[test]
+    fn partial_view_of_a_word() {
+        let edge = |input, to| {
+            let mut edges = [Table::MISSING; 16];
+            edges[input] = to;
+            edges
+        };
+        assert!(exactly(&[1, 2]).to_partial().rows().eq([
+            (false, edge(1, 1)),
+            (false, edge(2, 2)),
+            (true, [Table::MISSING; 16]),
+        ]
+        .iter()));
+
+        // nothing to drop, or nothing left
+        let universal = Table::from_rows(vec![(true, [0; 16])]);
+        assert!(universal.to_partial().rows().eq(universal.rows()));
+        let empty = Table::from_rows(vec![(false, [0; 16])]);
+        assert!(empty
+            .to_partial()
+            .rows()
+            .eq([(false, [Table::MISSING; 16])].iter()));
+    }
+
+    #[test]
+    fn partial_tables_round_trip() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program).minimized();
+            let partial = table.to_partial();
+            assert!(matches!(
+                partial.compare(&table),
+                EquivResult::Equivalent { .. }
+            ));
+            if partial.state_count() > 1 {
+                assert!(partial.co_reachable().iter().all(|&live| live));
+            }
+
+            let mut completed = partial.clone();
+            completed.make_complete();
+            assert!(completed.is_complete());
+            assert!(matches!(
+                completed.compare(&table),
+                EquivResult::Equivalent { .. }
+            ));
+            assert!(completed.to_partial().rows().eq(partial.rows()));
+        }
+    }
+
+    #[test]
+    fn right_quotients_by_strings() {
+        for program in crate::tests::benchmarks().take(4) {
+            let table = Table::build(&program).minimized();
+            for suffix in short_inputs(1) {
+                let quotient = table.right_quotient_str(&suffix);
+                for input in short_inputs(2) {
+                    let whole = [&input[..], &suffix[..]].concat();
+                    assert_eq!(quotient.accepts(&input), table.accepts(&whole));
+                }
+            }
+        }
+
+        // the missing transitions of a partial table lead nowhere
+        let quotient = exactly(&[1, 2]).to_partial().right_quotient_str(&[2]);
+        assert!(quotient.accepts(&[1]));
+        assert!(!quotient.accepts(&[]) && !quotient.accepts(&[1, 2]));
+    }
+
+    #[test]
+    fn right_quotients_by_languages() {
+        let equivalent =
+            |x: &Table, y: &Table| matches!(x.compare(y), EquivResult::Equivalent { .. });
+        for program in crate::tests::benchmarks().take(4) {
+            let table = Table::build(&program).minimized();
+            let suffixes: [&[u8]; 3] = [&[], &[0], &[1, 2]];
+            for suffix in suffixes {
+                assert!(equivalent(
+                    &table.right_quotient(&exactly(suffix)),
+                    &table.right_quotient_str(suffix)
+                ));
+            }
+
+            let either = exactly(&[1]).union(&exactly(&[2, 3]));
+            let quotient = table.right_quotient(&either);
+            for input in short_inputs(2) {
+                let with = |suffix: &[u8]| table.accepts(&[&input[..], suffix].concat());
+                assert_eq!(quotient.accepts(&input), with(&[1]) || with(&[2, 3]));
+            }
+
+            // nothing can follow, or anything can
+            let empty = Table::from_rows(vec![(false, [0; 16])]);
+            assert_eq!(table.right_quotient(&empty).shortest_accepted(), None);
+            let universal = Table::from_rows(vec![(true, [0; 16])]);
+            let prefixes = table.right_quotient(&universal);
+            for input in short_inputs(2) {
+                assert_eq!(
+                    prefixes.accepts(&input),
+                    table.derivative(&input).shortest_accepted().is_some()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{Instruction, Program};
+
+/// Layout settings for [`Program::format`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Spaces per level of loop nesting.
+    pub indent: usize,
+    /// Lines are broken before this many characters where possible. Runs and loops longer
+    /// than this are never split.
+    pub line_width: usize,
+    /// Separate runs of the same instruction with a space, e.g. `+++++ > ,`.
+    pub group_runs: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            line_width: 80,
+            group_runs: false,
+        }
+    }
+}
+
+struct Lines<'o> {
+    options: &'o FormatOptions,
+    text: String,
+    line: String,
+    depth: usize,
+}
+
+impl Lines<'_> {
+    fn push(&mut self, token: &str) {
+        let separator = usize::from(self.options.group_runs && !self.line.is_empty());
+        let width = self.depth * self.options.indent + self.line.len() + separator + token.len();
+        if !self.line.is_empty() && width > self.options.line_width {
+            self.flush();
+        } else if separator == 1 {
+            self.line.push(' ');
+        }
+        self.line.push_str(token);
+    }
+
+    fn flush(&mut self) {
+        if !self.line.is_empty() {
+            let indent = self.depth * self.options.indent;
+            self.text.extend(std::iter::repeat_n(' ', indent));
+            self.text.push_str(&self.line);
+            self.text.push('\n');
+            self.line.clear();
+        }
+    }
+}
+
+impl Program {
+    /// The instructions laid out over lines, with every loop containing another loop opening
+    /// and closing on lines of their own and its body indented. Innermost loops stay inline
+    /// when they fit.
+    ///
+    /// Only whitespace is added, so parsing the result gives the same instructions. Comments
+    /// in the original text are dropped.
+    pub fn format(&self, options: &FormatOptions) -> String {
+        let mut lines = Lines {
+            options,
+            text: String::new(),
+            line: String::new(),
+            depth: 0,
+        };
+        let instructions = &self.instructions;
+        let mut position = 0;
+        while position < instructions.len() {
+            let instruction = instructions[position];
+            match instruction {
+                Instruction::StartLoop => {
+                    let inner_end = instructions[position + 1..]
+                        .iter()
+                        .position(|i| matches!(i, Instruction::StartLoop | Instruction::EndLoop))
+                        .map(|offset| position + 1 + offset)
+                        .filter(|&end| instructions[end] == Instruction::EndLoop);
+                    let inline = inner_end
+                        .map(|end| {
+                            instructions[position..=end]
+                                .iter()
+                                .map(|i| i.to_char())
+                                .collect::<String>()
+                        })
+                        .filter(|text| {
+                            lines.depth * options.indent + text.len() <= options.line_width
+                        });
+                    if let (Some(end), Some(text)) = (inner_end, inline) {
+                        lines.push(&text);
+                        position = end + 1;
+                        continue;
+                    }
+                    lines.flush();
+                    lines.line.push('[');
+                    lines.flush();
+                    lines.depth += 1;
+                }
+                Instruction::EndLoop => {
+                    lines.flush();
+                    lines.depth = lines.depth.saturating_sub(1);
+                    lines.line.push(']');
+                    lines.flush();
+                }
+                _ => {
+                    let run = if options.group_runs {
+                        instructions[position..]
+                            .iter()
+                            .take_while(|&&i| i == instruction)
+                            .count()
+                    } else {
+                        1
+                    };
+                    let text: String = std::iter::repeat_n(instruction.to_char(), run).collect();
+                    lines.push(&text);
+                    position += run;
+                    continue;
+                }
+            }
+            position += 1;
+        }
+        lines.flush();
+        lines.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{
+        gen::{generate, GeneratorConfig},
+        Program,
+    };
+
+    use super::FormatOptions;
+
+    fn format(text: &str, options: &FormatOptions) -> String {
+        Program::new(text, NonZeroUsize::MIN).format(options)
+    }
+
+    #[test]
+    fn benchmark_layout() {
+        assert_eq!(
+            format(",>>+[.[,<<[->+>-<<]>[-<+>]>]+]", &FormatOptions::default()),
+            ",>>+\n\
+             [\n\
+            \x20   .\n\
+            \x20   [\n\
+            \x20       ,<<[->+>-<<]>[-<+>]>\n\
+            \x20   ]\n\
+            \x20   +\n\
+             ]\n"
+        );
+    }
+
+    #[test]
+    fn grouped_runs_and_narrow_lines() {
+        let grouped = FormatOptions {
+            group_runs: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(format(",>>+ comment", &grouped), ", >> +\n");
+
+        let narrow = FormatOptions {
+            line_width: 5,
+            ..FormatOptions::default()
+        };
+        assert_eq!(format("+++++++", &narrow), "+++++\n++\n");
+        // an innermost loop that doesn't fit gets lines of its own
+        assert_eq!(format("[-.-]", &narrow), "[-.-]\n");
+        assert_eq!(
+            format("[-.,-]", &narrow),
+            "[\n    -\n    .\n    ,\n    -\n]\n"
+        );
+    }
+
+    #[test]
+    fn formatting_keeps_the_instructions() {
+        let layouts = [
+            FormatOptions::default(),
+            FormatOptions {
+                indent: 1,
+                line_width: 3,
+                group_runs: true,
+            },
+            FormatOptions {
+                indent: 0,
+                line_width: 1,
+                group_runs: false,
+            },
+        ];
+        let generated = (0..50).map(|seed| {
+            generate(&GeneratorConfig {
+                len: 40,
+                seed,
+                ..GeneratorConfig::default()
+            })
+        });
+        let texts = crate::tests::BENCHMARKS
+            .iter()
+            .map(|&(text, _)| text.to_string())
+            .chain(generated);
+        for text in texts {
+            let program = Program::new(&text, NonZeroUsize::MIN);
+            for options in &layouts {
+                let formatted = program.format(options);
+                let reparsed = Program::new(&formatted, NonZeroUsize::MIN);
+                assert_eq!(reparsed.instructions, program.instructions);
+            }
+        }
+    }
+}
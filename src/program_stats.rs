@@ -0,0 +1,138 @@
+use crate::{Instruction, Program};
+
+/// Static metrics of a program's text, see [`Program::stats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramStats {
+    pub length: usize,
+    pub move_lefts: usize,
+    pub move_rights: usize,
+    pub increments: usize,
+    pub decrements: usize,
+    /// The number of `[`, which is the number of loops if the brackets are balanced.
+    pub loops: usize,
+    pub loop_ends: usize,
+    pub reads: usize,
+    pub accepts: usize,
+    /// Deepest nesting of `[`, ignoring any `]` without a matching `[`.
+    pub max_nesting: usize,
+    /// `>` minus `<` over the whole text.
+    pub net_head_displacement: isize,
+    /// Furthest the head gets from its start reading the text straight through, in either
+    /// direction, without following loops.
+    pub max_head_displacement: usize,
+}
+
+impl Program {
+    /// Counts instructions and measures nesting and head movement without running anything.
+    pub fn stats(&self) -> ProgramStats {
+        let mut stats = ProgramStats {
+            length: self.instructions.len(),
+            ..ProgramStats::default()
+        };
+        let mut nesting = 0;
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::MoveLeft => {
+                    stats.move_lefts += 1;
+                    stats.net_head_displacement -= 1;
+                }
+                Instruction::MoveRight => {
+                    stats.move_rights += 1;
+                    stats.net_head_displacement += 1;
+                }
+                Instruction::Increment => stats.increments += 1,
+                Instruction::Decrement => stats.decrements += 1,
+                Instruction::StartLoop => {
+                    stats.loops += 1;
+                    nesting += 1;
+                    stats.max_nesting = stats.max_nesting.max(nesting);
+                }
+                Instruction::EndLoop => {
+                    stats.loop_ends += 1;
+                    nesting = nesting.saturating_sub(1);
+                }
+                Instruction::Read => stats.reads += 1,
+                Instruction::Accept => stats.accepts += 1,
+            }
+            stats.max_head_displacement = stats
+                .max_head_displacement
+                .max(stats.net_head_displacement.unsigned_abs());
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::Program;
+
+    use super::ProgramStats;
+
+    fn stats(text: &str) -> ProgramStats {
+        Program::new(text, NonZeroUsize::MIN).stats()
+    }
+
+    #[test]
+    fn hand_counted_stats() {
+        assert_eq!(
+            stats(",>>+[.[,<<[->+>-<<]>[-<+>]>]+]"),
+            ProgramStats {
+                length: 30,
+                move_lefts: 5,
+                move_rights: 7,
+                increments: 4,
+                decrements: 3,
+                loops: 4,
+                loop_ends: 4,
+                reads: 2,
+                accepts: 1,
+                max_nesting: 3,
+                net_head_displacement: 2,
+                max_head_displacement: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn comments_and_empty_programs() {
+        assert_eq!(stats("no instructions here"), ProgramStats::default());
+        let commented = stats("read , then accept .");
+        assert_eq!(
+            (commented.length, commented.reads, commented.accepts),
+            (2, 1, 1)
+        );
+    }
+
+    #[test]
+    fn head_displacement_in_both_directions() {
+        let left = stats("<<<>");
+        assert_eq!(left.net_head_displacement, -2);
+        assert_eq!(left.max_head_displacement, 3);
+
+        let there_and_back = stats(">>><<<");
+        assert_eq!(there_and_back.net_head_displacement, 0);
+        assert_eq!(there_and_back.max_head_displacement, 3);
+    }
+
+    #[test]
+    fn unmatched_brackets() {
+        let extra_end = stats(",[-[-]]]");
+        assert_eq!(
+            (extra_end.loops, extra_end.loop_ends, extra_end.max_nesting),
+            (2, 3, 2)
+        );
+
+        // a stray `]` doesn't hide the nesting of what follows
+        let stray_ends = stats("]][[");
+        assert_eq!(
+            (
+                stray_ends.loops,
+                stray_ends.loop_ends,
+                stray_ends.max_nesting
+            ),
+            (2, 2, 2)
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::fmt::Display;
+
+use crate::{DeterminizeError, Nfa, Row, Table};
+
+/// Two states in the same block that the quotient can't merge: they disagree on acceptance
+/// if `input` is `None`, and otherwise on the block reached by `input`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuotientConflict {
+    pub a: usize,
+    pub b: usize,
+    pub input: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuotientError {
+    /// `classes` doesn't have one entry per state.
+    WrongLength {
+        expected: usize,
+        found: usize,
+    },
+    /// Every state whose block's first member disagrees with it, in state order.
+    Conflicts(Vec<QuotientConflict>),
+    Determinize(DeterminizeError),
+}
+
+impl Display for QuotientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongLength { expected, found } => {
+                write!(
+                    f,
+                    "expected a block for each of {expected} states, got {found}"
+                )
+            }
+            Self::Conflicts(conflicts) => {
+                write!(f, "the blocks aren't compatible with the transitions:")?;
+                for conflict in conflicts {
+                    match conflict.input {
+                        None => write!(
+                            f,
+                            "\n  states {} and {} differ in acceptance",
+                            conflict.a, conflict.b
+                        )?,
+                        Some(input) => write!(
+                            f,
+                            "\n  states {} and {} go to different blocks on {input:X}",
+                            conflict.a, conflict.b
+                        )?,
+                    }
+                }
+                Ok(())
+            }
+            Self::Determinize(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for QuotientError {}
+
+/// The block of each state, renumbered so that the start state's block is 0 and the others
+/// follow in increasing order of their ids in `classes`, and the number of blocks.
+fn block_numbering(classes: &[usize]) -> (Vec<usize>, usize) {
+    let mut ids: Vec<usize> = classes[1..]
+        .iter()
+        .copied()
+        .filter(|&id| id != classes[0])
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let blocks = classes
+        .iter()
+        .map(|&id| {
+            if id == classes[0] {
+                0
+            } else {
+                1 + ids.binary_search(&id).unwrap()
+            }
+        })
+        .collect();
+    (blocks, ids.len() + 1)
+}
+
+impl Table {
+    /// Merges the states into blocks, `classes[state]` being the block of each state. Every
+    /// member of a block must agree on acceptance and on the block each input leads to (or
+    /// on the transition being missing), and the conflicts are listed otherwise.
+    ///
+    /// The start state's block becomes state 0 and the others follow in increasing order of
+    /// their ids, so the partition found by minimization gives back the minimized table.
+    pub fn quotient(&self, classes: &[usize]) -> Result<Table, QuotientError> {
+        if !self.check_classes(classes)? {
+            return Ok(self.clone());
+        }
+        let (blocks, block_count) = block_numbering(classes);
+        let block_of = |to: usize| if to == Table::MISSING { to } else { blocks[to] };
+
+        // the first member of each block and its row
+        let mut rows: Vec<Option<(usize, Row)>> = vec![None; block_count];
+        let mut conflicts = Vec::new();
+        for (state, (accepting, edges)) in self.rows().enumerate() {
+            let row = (*accepting, edges.map(block_of));
+            let Some((first, (first_accepting, first_edges))) = rows[blocks[state]] else {
+                rows[blocks[state]] = Some((state, row));
+                continue;
+            };
+            let conflict = |input| QuotientConflict {
+                a: first,
+                b: state,
+                input,
+            };
+            if first_accepting != row.0 {
+                conflicts.push(conflict(None));
+            }
+            conflicts.extend(
+                (0..16)
+                    .filter(|&input| first_edges[input as usize] != row.1[input as usize])
+                    .map(|input| conflict(Some(input))),
+            );
+        }
+        if !conflicts.is_empty() {
+            return Err(QuotientError::Conflicts(conflicts));
+        }
+
+        Ok(Table::from_rows(
+            rows.into_iter().map(|row| row.unwrap().1).collect(),
+        ))
+    }
+
+    /// Like [`Table::quotient`], but members of a block may disagree: a block accepts if any
+    /// member does and goes everywhere its members go, and the resulting nondeterministic
+    /// automaton is determinized with at most `max_states` states. The language can only
+    /// grow.
+    pub fn quotient_union(
+        &self,
+        classes: &[usize],
+        max_states: usize,
+    ) -> Result<Table, QuotientError> {
+        if !self.check_classes(classes)? {
+            return Ok(self.clone());
+        }
+        let (blocks, block_count) = block_numbering(classes);
+
+        let mut accepting = vec![false; block_count];
+        let mut transitions: Vec<[Vec<usize>; 16]> =
+            vec![std::array::from_fn(|_| Vec::new()); block_count];
+        for (state, (state_accepting, edges)) in self.rows().enumerate() {
+            let block = blocks[state];
+            accepting[block] |= *state_accepting;
+            for (input, &to) in edges.iter().enumerate() {
+                if to != Table::MISSING && !transitions[block][input].contains(&blocks[to]) {
+                    transitions[block][input].push(blocks[to]);
+                }
+            }
+        }
+
+        Nfa::from_parts(accepting, transitions, 0)
+            .determinize(max_states)
+            .map_err(QuotientError::Determinize)
+    }
+
+    /// `Ok(true)` if there's anything to merge.
+    fn check_classes(&self, classes: &[usize]) -> Result<bool, QuotientError> {
+        if classes.len() != self.state_count() {
+            return Err(QuotientError::WrongLength {
+                expected: self.state_count(),
+                found: classes.len(),
+            });
+        }
+        Ok(!classes.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{exactly, short_inputs},
+        DeterminizeError, Table,
+    };
+
+    use super::{QuotientConflict, QuotientError};
+
+    #[test]
+    fn minimizing_partition_gives_the_minimized_table() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            let quotient = table.quotient(&table.minimize_mapping()).unwrap();
+            assert!(quotient.rows().eq(table.minimized().rows()));
+        }
+    }
+
+    #[test]
+    fn blocks_are_numbered_from_the_start() {
+        // 2 and 3 are both rejecting sinks
+        let mut start = [2; 16];
+        start[1] = 1;
+        let table = Table::from_rows(vec![
+            (false, start),
+            (true, [3; 16]),
+            (false, [2; 16]),
+            (false, [3; 16]),
+        ]);
+        let quotient = table.quotient(&[9, 4, 6, 6]).unwrap();
+        assert!(quotient
+            .rows()
+            .eq([(false, start), (true, [2; 16]), (false, [2; 16])].iter()));
+
+        let word = exactly(&[1, 2]);
+        assert!(word.quotient(&[0, 1, 2, 3]).unwrap().rows().eq(word.rows()));
+    }
+
+    #[test]
+    fn conflicts_are_listed() {
+        let error = exactly(&[1, 2]).quotient(&[0, 0, 1, 1]).unwrap_err();
+        assert_eq!(
+            error,
+            QuotientError::Conflicts(vec![
+                QuotientConflict {
+                    a: 0,
+                    b: 1,
+                    input: Some(1)
+                },
+                QuotientConflict {
+                    a: 2,
+                    b: 3,
+                    input: None
+                },
+            ])
+        );
+        assert_eq!(
+            error.to_string(),
+            "the blocks aren't compatible with the transitions:\n  \
+             states 0 and 1 go to different blocks on 1\n  \
+             states 2 and 3 differ in acceptance"
+        );
+        assert_eq!(
+            exactly(&[1]).quotient(&[0, 0]).unwrap_err(),
+            QuotientError::WrongLength {
+                expected: 3,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn union_quotients_only_grow_the_language() {
+        let word = exactly(&[1, 2]);
+        let merged = word.quotient_union(&[0, 0, 1, 1], 16).unwrap();
+        for input in short_inputs(3) {
+            if word.accepts(&input) {
+                assert!(merged.accepts(&input));
+            }
+        }
+        // the start block reaches the accepting block on a 2 through state 1
+        assert!(merged.accepts(&[2]));
+
+        assert_eq!(
+            word.quotient_union(&[0, 0, 1, 1], 1).unwrap_err(),
+            QuotientError::Determinize(DeterminizeError::StateLimit { limit: 1 })
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{BuildOptions, EquivResult, Instruction, Program, Table};
+
+/// Candidates whose build discovers more states than this fail [`same_language`].
+pub const REDUCE_STATE_LIMIT: usize = 1_000_000;
+
+/// Shrinks the program as long as `oracle` keeps holding for the result, and returns the
+/// smallest program found. The oracle is expected to hold for `program` itself.
+///
+/// Candidates come from deleting runs of instructions (longest first), unwrapping loops,
+/// and [`Program::normalize`], which merges runs. Deletions never split a loop, so
+/// brackets that were matched stay matched. The result has no spans.
+pub fn reduce(program: &Program, mut oracle: impl FnMut(&Program) -> bool) -> Program {
+    let candidate = |instructions: Vec<Instruction>| Program {
+        cell_count: program.cell_count,
+        instructions,
+        spans: Vec::new(),
+    };
+    let mut current = candidate(program.instructions.clone());
+
+    loop {
+        let mut improved = false;
+
+        let normalized = current.normalize();
+        if normalized.instructions.len() < current.instructions.len() {
+            let normalized = candidate(normalized.instructions);
+            if oracle(&normalized) {
+                current = normalized;
+                improved = true;
+            }
+        }
+
+        let mut chunk = current.instructions.len() / 2;
+        while chunk > 0 {
+            let mut start = 0;
+            while start + chunk <= current.instructions.len() {
+                let removed = &current.instructions[start..start + chunk];
+                if is_balanced(removed) {
+                    let mut instructions = current.instructions.clone();
+                    instructions.drain(start..start + chunk);
+                    let smaller = candidate(instructions);
+                    if oracle(&smaller) {
+                        current = smaller;
+                        improved = true;
+                        // the next run has moved to `start`
+                        continue;
+                    }
+                }
+                start += 1;
+            }
+            chunk /= 2;
+        }
+
+        let mut start = 0;
+        while start < current.instructions.len() {
+            if let Some(end) = matching_end(&current.instructions, start) {
+                let mut instructions = current.instructions.clone();
+                instructions.remove(end);
+                instructions.remove(start);
+                let unwrapped = candidate(instructions);
+                if oracle(&unwrapped) {
+                    current = unwrapped;
+                    improved = true;
+                    continue;
+                }
+            }
+            start += 1;
+        }
+
+        if !improved {
+            return current;
+        }
+    }
+}
+
+/// An oracle for [`reduce`] that holds for programs with the same language as `original`.
+pub fn same_language(original: &Program) -> impl FnMut(&Program) -> bool {
+    let options = BuildOptions {
+        max_states: Some(REDUCE_STATE_LIMIT),
+        ..BuildOptions::default()
+    };
+    let target = Table::build(original).minimized();
+    move |candidate| {
+        Table::build_with(candidate, &options).is_ok_and(|(table, _)| {
+            matches!(
+                target.compare(&table.minimized()),
+                EquivResult::Equivalent { .. }
+            )
+        })
+    }
+}
+
+/// Whether removing `instructions` leaves the brackets around them matched as they were.
+fn is_balanced(instructions: &[Instruction]) -> bool {
+    let mut depth = 0usize;
+    for instruction in instructions {
+        match instruction {
+            Instruction::StartLoop => depth += 1,
+            Instruction::EndLoop => match depth.checked_sub(1) {
+                Some(outer) => depth = outer,
+                None => return false,
+            },
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+/// Position of the `]` matching the `[` at `start`, if there is one.
+fn matching_end(instructions: &[Instruction], start: usize) -> Option<usize> {
+    if instructions[start] != Instruction::StartLoop {
+        return None;
+    }
+    let mut depth = 0;
+    for (position, instruction) in instructions.iter().enumerate().skip(start) {
+        match instruction {
+            Instruction::StartLoop => depth += 1,
+            Instruction::EndLoop => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(position);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{EquivResult, Instruction, Program, Table};
+
+    use super::{is_balanced, reduce, same_language};
+
+    #[test]
+    fn padding_is_removed() {
+        let program = Program::new("[-],+-[.,><]", NonZeroUsize::new(1).unwrap());
+        let reduced = reduce(&program, same_language(&program));
+        assert!(reduced.instructions.len() <= ",[.,]".len());
+        assert!(is_balanced(&reduced.instructions));
+        assert!(reduced.spans.is_empty());
+        assert!(matches!(
+            Table::build(&program)
+                .minimized()
+                .compare(&Table::build(&reduced).minimized()),
+            EquivResult::Equivalent { .. }
+        ));
+    }
+
+    #[test]
+    fn benchmarks_keep_their_language() {
+        for (text, cells) in [("+[>,,.<]", 2), (",>,[-<->]<[>.,<]", 2)] {
+            let program = Program::new(text, NonZeroUsize::new(cells).unwrap());
+            let reduced = reduce(&program, same_language(&program));
+            assert!(reduced.instructions.len() <= program.instructions.len());
+            assert!(same_language(&program)(&reduced));
+        }
+    }
+
+    #[test]
+    fn custom_oracles() {
+        let program = Program::new("+[>.,-.<]>>,", NonZeroUsize::new(2).unwrap());
+        let accepts = |program: &Program| {
+            program
+                .instructions
+                .iter()
+                .filter(|&&instruction| instruction == Instruction::Accept)
+                .count()
+        };
+        let reduced = reduce(&program, |candidate| accepts(candidate) >= 2);
+        assert_eq!(reduced.to_string(), "..");
+
+        // brackets are only ever removed in matching pairs
+        let reduced = reduce(&program, |candidate| {
+            candidate.instructions.contains(&Instruction::EndLoop)
+        });
+        assert_eq!(reduced.to_string(), "[]");
+
+        let reduced = reduce(&program, |_| false);
+        assert_eq!(reduced.instructions, program.instructions);
+    }
+
+    #[test]
+    fn balanced_runs() {
+        use Instruction::*;
+        assert!(is_balanced(&[]));
+        assert!(is_balanced(&[StartLoop, Read, EndLoop, Accept]));
+        assert!(!is_balanced(&[EndLoop, StartLoop]));
+        assert!(!is_balanced(&[StartLoop]));
+    }
+}
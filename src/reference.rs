@@ -0,0 +1,124 @@
+use crate::{Refinement, Table};
+
+impl Table {
+    /// The same result as [`Table::minimized`], computed with the textbook table-filling
+    /// algorithm in quadratic space: two states are distinguishable if exactly one accepts, or
+    /// if some input leads them to a distinguishable pair. Far too slow for large tables, this
+    /// is meant for cross-checking.
+    pub fn minimize_reference(&self) -> Table {
+        let table = self.completed();
+        let state_count = table.state_count();
+        let index = |a: usize, b: usize| a * state_count + b;
+
+        let mut distinguishable = vec![false; state_count * state_count];
+        for a in 0..state_count {
+            for b in 0..state_count {
+                distinguishable[index(a, b)] = table.is_accepting(a) != table.is_accepting(b);
+            }
+        }
+
+        let mut iterations = 0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            iterations += 1;
+            for a in 0..state_count {
+                for b in a + 1..state_count {
+                    if distinguishable[index(a, b)] {
+                        continue;
+                    }
+                    let split = (0..16).any(|input| {
+                        distinguishable
+                            [index(table.transition(a, input), table.transition(b, input))]
+                    });
+                    if split {
+                        distinguishable[index(a, b)] = true;
+                        distinguishable[index(b, a)] = true;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        // blocks are numbered in order of their smallest state
+        let mut partition = vec![usize::MAX; state_count];
+        let mut reps = Vec::new();
+        for state in 0..state_count {
+            if partition[state] != usize::MAX {
+                continue;
+            }
+            for other in state..state_count {
+                if !distinguishable[index(state, other)] {
+                    partition[other] = reps.len();
+                }
+            }
+            reps.push(state);
+        }
+
+        table.collapse(&Refinement {
+            partition,
+            reps,
+            iterations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{rng::Rng, Program, Table};
+
+    /// The benchmark programs, plus one the debug cross-check used to trip over.
+    const PROGRAMS: &[(&str, usize)] = &[
+        ("+[>,,.<]", 2),
+        (",>,[-<->]<[>.,<]", 2),
+        ("+[>,]+[[.,]+]", 3),
+        (">+[>.,[>]<<]", 3),
+        ("+[>.,[<->[-]]<[,]+]", 2),
+        (",>>+[.[,<<[->+>-<<]>[-<+>]>]+]", 3),
+        (",[-[-]]]", 1),
+        ("+.+-,.-[[.[>+,<>],-],,]", 1),
+    ];
+
+    /// Whether two minimal tables are the same up to renumbering.
+    fn isomorphic(a: &Table, b: &Table) -> bool {
+        a.state_count() == b.state_count()
+            && a.restrict_reachable(0)
+                .rows()
+                .eq(b.restrict_reachable(0).rows())
+    }
+
+    #[test]
+    fn agrees_on_programs() {
+        for &(program_text, cells) in PROGRAMS {
+            let program = Program::new(program_text, NonZeroUsize::new(cells).unwrap());
+            let table = Table::build(&program);
+            assert!(
+                isomorphic(&table.minimized(), &table.minimize_reference()),
+                "{program_text}"
+            );
+        }
+    }
+
+    #[test]
+    fn agrees_on_random_tables() {
+        let mut rng = Rng::new(149);
+        for _ in 0..500 {
+            let state_count = 1 + rng.below(14);
+            // few distinct targets per state, so that there is something to merge
+            let targets = 1 + rng.below(4);
+            let rows = (0..state_count)
+                .map(|_| {
+                    let edges: Vec<usize> = (0..targets).map(|_| rng.below(state_count)).collect();
+                    (
+                        rng.below(5) < 2,
+                        std::array::from_fn(|input| edges[input % targets]),
+                    )
+                })
+                .collect();
+            let table = Table::from_rows(rows);
+            assert!(isomorphic(&table.minimized(), &table.minimize_reference()));
+        }
+    }
+}
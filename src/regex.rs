@@ -0,0 +1,302 @@
+use std::{fmt::Display, iter::Peekable, str::CharIndices};
+
+use crate::{DeterminizeError, EquivResult, Nfa, Side, Table};
+
+/// Subset construction limit for compiling a specification.
+const REGEX_STATE_LIMIT: usize = 1_000_000;
+
+/// How deeply groups may nest, so that a hostile pattern can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Why [`Table::matches_regex`] failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegexMismatch {
+    /// The pattern is malformed at byte offset `position`.
+    Pattern {
+        position: usize,
+        message: &'static str,
+    },
+    Determinize(DeterminizeError),
+    /// `witness` is a shortest string accepted by exactly one of the table, [`Side::A`], and
+    /// the pattern, [`Side::B`].
+    Different {
+        witness: Vec<u8>,
+        accepted_by: Side,
+    },
+}
+
+impl Display for RegexMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pattern { position, message } => {
+                write!(f, "invalid pattern at offset {position}: {message}")
+            }
+            Self::Determinize(e) => write!(f, "couldn't compile the pattern: {e}"),
+            Self::Different {
+                witness,
+                accepted_by,
+            } => {
+                f.write_str("\"")?;
+                for symbol in witness {
+                    write!(f, "{symbol:X}")?;
+                }
+                let accepted_by = match accepted_by {
+                    Side::A => "the table",
+                    Side::B => "the pattern",
+                };
+                write!(f, "\" is only accepted by {accepted_by}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegexMismatch {}
+
+/// Recursive descent over a pattern, building the automaton of each part as it goes.
+struct Parser<'p> {
+    chars: Peekable<CharIndices<'p>>,
+    len: usize,
+    /// Groups currently open.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// The next character that isn't whitespace, and its offset.
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while let Some(&(_, c)) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+        self.chars.peek().copied()
+    }
+
+    fn error(&mut self, message: &'static str) -> RegexMismatch {
+        RegexMismatch::Pattern {
+            position: self.peek().map_or(self.len, |(position, _)| position),
+            message,
+        }
+    }
+
+    fn alternation(&mut self) -> Result<Nfa, RegexMismatch> {
+        let mut nfa = self.concatenation()?;
+        while let Some((_, '|')) = self.peek() {
+            self.chars.next();
+            nfa = nfa.union(&self.concatenation()?);
+        }
+        Ok(nfa)
+    }
+
+    fn concatenation(&mut self) -> Result<Nfa, RegexMismatch> {
+        let mut nfa = symbols(None);
+        while !matches!(self.peek(), None | Some((_, '|' | ')'))) {
+            nfa = nfa.concat(&self.repetition()?);
+        }
+        Ok(nfa)
+    }
+
+    fn repetition(&mut self) -> Result<Nfa, RegexMismatch> {
+        let mut nfa = self.atom()?;
+        while let Some((_, operator @ ('*' | '+' | '?'))) = self.peek() {
+            self.chars.next();
+            nfa = match operator {
+                '*' => nfa.star(),
+                '+' => nfa.concat(&nfa.star()),
+                _ => nfa.union(&symbols(None)),
+            };
+        }
+        Ok(nfa)
+    }
+
+    fn atom(&mut self) -> Result<Nfa, RegexMismatch> {
+        match self.peek() {
+            Some((_, '(')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("groups nested too deeply"));
+                }
+                self.chars.next();
+                self.depth += 1;
+                let nfa = self.alternation()?;
+                self.depth -= 1;
+                match self.peek() {
+                    Some((_, ')')) => {
+                        self.chars.next();
+                        Ok(nfa)
+                    }
+                    _ => Err(self.error("expected `)`")),
+                }
+            }
+            Some((_, '.')) => {
+                self.chars.next();
+                Ok(symbols(Some(u16::MAX)))
+            }
+            Some((_, '[')) => {
+                self.chars.next();
+                self.class().map(|set| symbols(Some(set)))
+            }
+            Some((_, c)) => match c.to_digit(16) {
+                Some(symbol) => {
+                    self.chars.next();
+                    Ok(symbols(Some(1 << symbol)))
+                }
+                None => Err(self.error("expected a hex digit, `.`, `[` or `(`")),
+            },
+            None => Err(self.error("expected a hex digit, `.`, `[` or `(`")),
+        }
+    }
+
+    /// The symbols of a bracketed class after its `[`, as a bit set.
+    fn class(&mut self) -> Result<u16, RegexMismatch> {
+        let negated = matches!(self.peek(), Some((_, '^')));
+        if negated {
+            self.chars.next();
+        }
+        let mut set = 0u16;
+        loop {
+            let low = match self.peek() {
+                Some((_, ']')) => {
+                    self.chars.next();
+                    return Ok(if negated { !set } else { set });
+                }
+                Some((_, c)) if c.is_ascii_hexdigit() => {
+                    self.chars.next();
+                    c.to_digit(16).unwrap()
+                }
+                _ => return Err(self.error("expected a hex digit or `]`")),
+            };
+            let high = match self.peek() {
+                Some((_, '-')) => {
+                    self.chars.next();
+                    match self.peek() {
+                        Some((_, c)) if c.is_ascii_hexdigit() => {
+                            self.chars.next();
+                            c.to_digit(16).unwrap()
+                        }
+                        _ => return Err(self.error("expected the end of the range")),
+                    }
+                }
+                _ => low,
+            };
+            if high < low {
+                return Err(self.error("the range is backwards"));
+            }
+            for symbol in low..=high {
+                set |= 1 << symbol;
+            }
+        }
+    }
+}
+
+/// The automaton of one symbol from `set`, or of the empty string for `None`.
+fn symbols(set: Option<u16>) -> Nfa {
+    match set {
+        None => Nfa::from_parts(vec![true], vec![std::array::from_fn(|_| Vec::new())], 0),
+        Some(set) => Nfa::from_parts(
+            vec![false, true],
+            vec![
+                std::array::from_fn(|input| {
+                    if set & (1 << input) != 0 {
+                        vec![1]
+                    } else {
+                        Vec::new()
+                    }
+                }),
+                std::array::from_fn(|_| Vec::new()),
+            ],
+            0,
+        ),
+    }
+}
+
+impl Table {
+    /// Checks that the language is exactly that of `pattern`, a regular expression over the
+    /// symbols written as hex digits. It has `.` for any symbol, classes like `[0-7A]` and
+    /// `[^F]`, grouping, `|`, `*`, `+` and `?`; whitespace is ignored and `()` matches the
+    /// empty string.
+    pub fn matches_regex(&self, pattern: &str) -> Result<(), RegexMismatch> {
+        let mut parser = Parser {
+            chars: pattern.char_indices().peekable(),
+            len: pattern.len(),
+            depth: 0,
+        };
+        let nfa = parser.alternation()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("unmatched `)`"));
+        }
+        let spec = nfa
+            .determinize(REGEX_STATE_LIMIT)
+            .map_err(RegexMismatch::Determinize)?;
+        match self.compare(&spec) {
+            EquivResult::Equivalent { .. } => Ok(()),
+            EquivResult::Different {
+                witness,
+                accepted_by,
+            } => Err(RegexMismatch::Different {
+                witness,
+                accepted_by,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RegexMismatch, MAX_DEPTH};
+    use crate::{tests::no_zeros, Side};
+
+    #[test]
+    fn matching_spec() {
+        assert_eq!(no_zeros().matches_regex("[^0]*"), Ok(()));
+        assert_eq!(no_zeros().matches_regex(" ( [1-F] | () )+ "), Ok(()));
+    }
+
+    #[test]
+    fn mismatch_has_a_shortest_witness() {
+        assert_eq!(
+            no_zeros().matches_regex("[^0]*0?"),
+            Err(RegexMismatch::Different {
+                witness: vec![0],
+                accepted_by: Side::B,
+            })
+        );
+        assert_eq!(
+            no_zeros().matches_regex("[^0]+"),
+            Err(RegexMismatch::Different {
+                witness: vec![],
+                accepted_by: Side::A,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert_eq!(
+            no_zeros().matches_regex("(1"),
+            Err(RegexMismatch::Pattern {
+                position: 2,
+                message: "expected `)`",
+            })
+        );
+        assert_eq!(
+            no_zeros().matches_regex("1)"),
+            Err(RegexMismatch::Pattern {
+                position: 1,
+                message: "unmatched `)`",
+            })
+        );
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let pattern = "(".repeat(MAX_DEPTH) + "[^0]*" + &")".repeat(MAX_DEPTH);
+        assert_eq!(no_zeros().matches_regex(&pattern), Ok(()));
+        assert_eq!(
+            no_zeros().matches_regex(&"(".repeat(100_000)),
+            Err(RegexMismatch::Pattern {
+                position: MAX_DEPTH,
+                message: "groups nested too deeply",
+            })
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::BuildMemory;
+
+#[derive(Clone, Debug)]
+pub struct BuildReport {
+    pub states_explored: usize,
+    /// Distinct transition rows among the explored states, which is what the table stores.
+    pub unique_rows: usize,
+    pub interpreter_steps: u64,
+    pub divergences: u64,
+    pub peak_frontier: usize,
+    /// Peak heap use of the build's structures, or `None` for a [`crate::PartialBuild`].
+    pub peak_memory: Option<BuildMemory>,
+    pub build_time: Duration,
+    /// Filled in by the caller once the table has been minimized.
+    pub minimize: Option<MinimizeReport>,
+    /// Moves that wrapped around the tape, if [`crate::BuildOptions::track_tape_wrap`] was set.
+    pub tape_wraps: Option<u64>,
+    /// Positions of the instructions responsible for [`BuildReport::tape_wraps`].
+    pub tape_wrap_sites: Vec<usize>,
+    /// `+` applied to 15, if [`crate::BuildOptions::track_arithmetic_wrap`] was set.
+    pub increment_wraps: Option<u64>,
+    /// `-` applied to 0, if [`crate::BuildOptions::track_arithmetic_wrap`] was set.
+    pub decrement_wraps: Option<u64>,
+    /// Positions of the instructions responsible for either kind of arithmetic wrap.
+    pub arithmetic_wrap_sites: Vec<usize>,
+    /// Whether each instruction ran at some point during the build.
+    pub(crate) executed: Vec<bool>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MinimizeReport {
+    pub blocks_before: usize,
+    pub blocks_after: usize,
+    pub refinement_iterations: usize,
+    pub minimize_time: Duration,
+}
+
+impl BuildReport {
+    /// Explored states per stored row.
+    pub fn dedup_ratio(&self) -> f64 {
+        self.states_explored as f64 / self.unique_rows as f64
+    }
+
+    /// Positions of the instructions that never ran. The build explores every reachable
+    /// configuration, so these are dead code for the program's cell count.
+    pub fn unreached_instructions(&self) -> Vec<usize> {
+        self.executed
+            .iter()
+            .enumerate()
+            .filter_map(|(position, &executed)| if executed { None } else { Some(position) })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut output = format!(
+            "{{\"states_explored\":{},\"unique_rows\":{},\"interpreter_steps\":{},\"divergences\":{},\"peak_frontier\":{},\"build_time_secs\":{}",
+            self.states_explored,
+            self.unique_rows,
+            self.interpreter_steps,
+            self.divergences,
+            self.peak_frontier,
+            self.build_time.as_secs_f64(),
+        );
+        if let Some(memory) = self.peak_memory {
+            output += &format!(
+                ",\"peak_memory\":{{\"states\":{},\"rows\":{},\"frontier\":{}}}",
+                memory.states, memory.rows, memory.frontier
+            );
+        }
+        if let Some(tape_wraps) = self.tape_wraps {
+            output += &format!(",\"tape_wraps\":{tape_wraps}");
+        }
+        if let (Some(increments), Some(decrements)) = (self.increment_wraps, self.decrement_wraps) {
+            output +=
+                &format!(",\"increment_wraps\":{increments},\"decrement_wraps\":{decrements}");
+        }
+        if let Some(minimize) = &self.minimize {
+            output += &format!(
+                ",\"minimize\":{{\"blocks_before\":{},\"blocks_after\":{},\"refinement_iterations\":{},\"minimize_time_secs\":{}}}",
+                minimize.blocks_before,
+                minimize.blocks_after,
+                minimize.refinement_iterations,
+                minimize.minimize_time.as_secs_f64(),
+            );
+        }
+        output.push('}');
+        output
+    }
+}
+
+impl Display for BuildReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "states explored:       {}", self.states_explored)?;
+        writeln!(
+            f,
+            "unique rows:           {} ({:.2} states per row)",
+            self.unique_rows,
+            self.dedup_ratio()
+        )?;
+        writeln!(f, "interpreter steps:     {}", self.interpreter_steps)?;
+        writeln!(f, "divergences:           {}", self.divergences)?;
+        writeln!(f, "peak frontier:         {}", self.peak_frontier)?;
+        if let Some(memory) = self.peak_memory {
+            writeln!(f, "peak memory:           {memory}")?;
+        }
+        write!(f, "build time:            {:?}", self.build_time)?;
+        if let Some(tape_wraps) = self.tape_wraps {
+            write!(f, "\ntape wraps:            {tape_wraps}")?;
+        }
+        if let (Some(increments), Some(decrements)) = (self.increment_wraps, self.decrement_wraps) {
+            write!(f, "\nincrement wraps:       {increments}")?;
+            write!(f, "\ndecrement wraps:       {decrements}")?;
+        }
+        if let Some(minimize) = &self.minimize {
+            write!(f, "\n{minimize}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for MinimizeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "blocks before:         {}", self.blocks_before)?;
+        writeln!(f, "blocks after:          {}", self.blocks_after)?;
+        writeln!(f, "refinement iterations: {}", self.refinement_iterations)?;
+        write!(f, "minimize time:         {:?}", self.minimize_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{json, BuildOptions, BuildReport, Program, Table};
+
+    fn report(text: &str, cells: usize, options: &BuildOptions) -> BuildReport {
+        let program = Program::new(text, NonZeroUsize::new(cells).unwrap());
+        Table::build_with(&program, options).unwrap().1
+    }
+
+    #[test]
+    fn tape_wraps_on_a_short_tape() {
+        let options = BuildOptions {
+            track_tape_wrap: true,
+            ..Default::default()
+        };
+        // the `>` runs once per input of the start state
+        let wrapped = report(",>.", 1, &options);
+        assert_eq!(wrapped.tape_wraps, Some(16));
+        assert_eq!(wrapped.tape_wrap_sites, [1]);
+
+        let roomy = report(",>.", 2, &options);
+        assert_eq!(roomy.tape_wraps, Some(0));
+        assert!(roomy.tape_wrap_sites.is_empty());
+
+        let untracked = report(",>.", 1, &BuildOptions::default());
+        assert_eq!(untracked.tape_wraps, None);
+        assert!(untracked.tape_wrap_sites.is_empty());
+    }
+
+    fn arithmetic_wraps(text: &str) -> (Option<u64>, Option<u64>, Vec<usize>) {
+        let options = BuildOptions {
+            track_arithmetic_wrap: true,
+            ..Default::default()
+        };
+        let report = report(text, 1, &options);
+        (
+            report.increment_wraps,
+            report.decrement_wraps,
+            report.arithmetic_wrap_sites,
+        )
+    }
+
+    #[test]
+    fn arithmetic_wraps() {
+        // only the sixteenth `+` wraps
+        assert_eq!(
+            arithmetic_wraps("++++++++++++++++,."),
+            (Some(1), Some(0), vec![15])
+        );
+        // only the input 0 wraps
+        assert_eq!(arithmetic_wraps(",-."), (Some(0), Some(1), vec![1]));
+        // a counter that stops at zero never wraps
+        assert_eq!(arithmetic_wraps(",[-]."), (Some(0), Some(0), vec![]));
+
+        let untracked = report(",-.", 1, &BuildOptions::default());
+        assert_eq!(untracked.decrement_wraps, None);
+    }
+
+    #[test]
+    fn loop_guarded_by_a_zero_cell_is_unreached() {
+        // the second cell is never written, so the loop is always skipped
+        let program = Program::new(", > [.,]", NonZeroUsize::new(2).unwrap());
+        let (_, report) = Table::build_with_report(&program);
+        assert_eq!(report.unreached_instructions(), [3, 4, 5]);
+        let offsets: Vec<usize> = report
+            .unreached_instructions()
+            .into_iter()
+            .map(|position| program.spans[position].start)
+            .collect();
+        assert_eq!(offsets, [5, 6, 7]);
+    }
+
+    #[test]
+    fn every_instruction_of_a_live_loop_runs() {
+        let program = Program::new(",[.,]", NonZeroUsize::new(1).unwrap());
+        let (_, report) = Table::build_with_report(&program);
+        assert!(report.unreached_instructions().is_empty());
+    }
+
+    #[test]
+    fn reports_are_consistent() {
+        for program in crate::tests::benchmarks() {
+            let (mut table, mut report) = Table::build_with_report(&program);
+            assert_eq!(report.states_explored, table.state_count());
+            assert!(report.unique_rows <= report.states_explored);
+            assert!(report.unique_rows > 0);
+            assert!(report.dedup_ratio() >= 1.0);
+            assert!(report.peak_memory.is_some());
+
+            let minimize = table.minimize_with_report();
+            assert_eq!(minimize.blocks_before, report.states_explored);
+            assert!(minimize.blocks_after <= minimize.blocks_before);
+            assert_eq!(minimize.blocks_after, table.state_count());
+            report.minimize = Some(minimize);
+
+            let value = json::parse(&report.to_json()).unwrap();
+            assert_eq!(
+                value.get("states_explored").and_then(json::Value::as_usize),
+                Some(report.states_explored)
+            );
+            assert_eq!(
+                value
+                    .get("minimize")
+                    .and_then(|minimize| minimize.get("blocks_after"))
+                    .and_then(json::Value::as_usize),
+                Some(table.state_count())
+            );
+            let text = report.to_string();
+            assert!(text.starts_with(&format!(
+                "states explored:       {}\n",
+                report.states_explored
+            )));
+            assert!(text.contains(&format!("blocks after:          {}\n", table.state_count())));
+        }
+    }
+}
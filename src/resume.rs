@@ -0,0 +1,506 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
+
+use rustc_hash::FxBuildHasher;
+
+use crate::{
+    BuildReport, ExploreOrder, ImportError, InnerState, Program, Row, RunStats, State, Table, U4Vec,
+};
+
+const MAGIC: &[u8; 4] = b"BFAP";
+const MISSING: u64 = u64::MAX;
+
+/// A build that runs in installments and can be saved between them, for builds too long to
+/// finish in one go.
+///
+/// States are numbered exactly as by [`Table::build_with`] with the same order, however the
+/// work is split up, so the finished table is the one an uninterrupted build gives. Limits,
+/// traces and wrap tracking from [`crate::BuildOptions`] aren't available here.
+pub struct PartialBuild {
+    /// The cell count the build was started with, which may be more than `program` uses.
+    cell_count: NonZeroUsize,
+    program: Program,
+    order: ExploreOrder,
+    /// Every state discovered so far, by id.
+    states: Vec<State>,
+    state_ids: HashMap<State, usize, FxBuildHasher>,
+    /// Like a build's table, except that `row_ids` of unexplored states are meaningless.
+    table: Table,
+    row_pool: HashMap<Row, usize, FxBuildHasher>,
+    /// Discovered states still to be explored.
+    frontier: VecDeque<usize>,
+    stats: RunStats,
+    peak_frontier: usize,
+    elapsed: Duration,
+}
+
+impl PartialBuild {
+    pub fn new(program: &Program, order: ExploreOrder) -> Self {
+        let cell_count = program.cell_count;
+        let program = program.shrunk_tape().unwrap_or_else(|| program.clone());
+        let mut stats = RunStats {
+            executed: vec![false; program.instructions.len()],
+            ..RunStats::default()
+        };
+        let mut seen_states = HashMap::with_hasher(FxBuildHasher);
+        let start =
+            program.run_with_next_input(program.initial_state(), 0, &mut seen_states, &mut stats);
+
+        let mut build = Self {
+            cell_count,
+            program,
+            order,
+            states: Vec::new(),
+            state_ids: HashMap::with_hasher(FxBuildHasher),
+            table: Table {
+                rows: Vec::new(),
+                row_ids: Vec::new(),
+                costs: Vec::new(),
+            },
+            row_pool: HashMap::with_hasher(FxBuildHasher),
+            frontier: VecDeque::new(),
+            stats,
+            peak_frontier: 0,
+            elapsed: Duration::ZERO,
+        };
+        build.intern(start);
+        build
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    pub fn states_discovered(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Whether the build was started for `program`.
+    pub fn is_for(&self, program: &Program) -> bool {
+        self.cell_count == program.cell_count && self.program.instructions == program.instructions
+    }
+
+    /// Explores up to `count` more states. Returns whether the build is finished.
+    pub fn run_for_states(&mut self, count: usize) -> bool {
+        let started = Instant::now();
+        for _ in 0..count {
+            if !self.step() {
+                break;
+            }
+        }
+        self.elapsed += started.elapsed();
+        self.is_finished()
+    }
+
+    /// Explores states until `duration` has passed, finishing the state in progress. Returns
+    /// whether the build is finished.
+    pub fn run_for(&mut self, duration: Duration) -> bool {
+        let started = Instant::now();
+        while started.elapsed() < duration && self.step() {}
+        self.elapsed += started.elapsed();
+        self.is_finished()
+    }
+
+    /// Runs the build to the end.
+    pub fn finish(mut self) -> (Table, BuildReport) {
+        self.run_for_states(usize::MAX);
+        let report = BuildReport {
+            states_explored: self.states.len(),
+            unique_rows: self.table.rows.len(),
+            interpreter_steps: self.stats.steps,
+            divergences: self.stats.divergences,
+            peak_frontier: self.peak_frontier,
+            peak_memory: None,
+            build_time: self.elapsed,
+            minimize: None,
+            tape_wraps: None,
+            tape_wrap_sites: Vec::new(),
+            increment_wraps: None,
+            decrement_wraps: None,
+            arithmetic_wrap_sites: Vec::new(),
+            executed: self.stats.executed,
+        };
+        (self.table, report)
+    }
+
+    /// Explores the next state of the frontier, the same way [`Table::build_with`] does.
+    /// Returns `false` if there was none.
+    fn step(&mut self) -> bool {
+        let Some(current_id) = (match self.order {
+            ExploreOrder::Bfs => self.frontier.pop_front(),
+            ExploreOrder::Dfs => self.frontier.pop_back(),
+        }) else {
+            return false;
+        };
+
+        let mut edges = [current_id; 16];
+        let current = self.states[current_id].clone();
+        if let Some(inner) = &current.inner {
+            let mut seen_states = HashMap::with_hasher(FxBuildHasher);
+            for input in 0..16 {
+                let steps_before = self.stats.steps;
+                let next = self.program.run_with_next_input(
+                    inner.clone(),
+                    input,
+                    &mut seen_states,
+                    &mut self.stats,
+                );
+                seen_states.clear();
+                self.table.costs[current_id][input as usize] =
+                    u32::try_from(self.stats.steps - steps_before).unwrap_or(u32::MAX);
+                edges[input as usize] = self.intern(next);
+            }
+        }
+
+        let row = (current.accepting, edges);
+        self.table.row_ids[current_id] = *self.row_pool.entry(row).or_insert_with(|| {
+            self.table.rows.push(row);
+            self.table.rows.len() - 1
+        });
+        self.peak_frontier = self.peak_frontier.max(self.frontier.len());
+        true
+    }
+
+    fn intern(&mut self, state: State) -> usize {
+        if let Some(&id) = self.state_ids.get(&state) {
+            return id;
+        }
+        let id = self.states.len();
+        self.state_ids.insert(state.clone(), id);
+        self.states.push(state);
+        self.table.row_ids.push(0);
+        self.table.costs.push([0; 16]);
+        self.frontier.push_back(id);
+        id
+    }
+
+    /// Saves the build, to be picked up again with [`PartialBuild::from_bytes`]. Numbers are
+    /// little-endian `u64`s.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        fn push(bytes: &mut Vec<u8>, value: u64) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut bytes = MAGIC.to_vec();
+        push(&mut bytes, self.cell_count.get() as u64);
+        push(&mut bytes, self.program.cell_count.get() as u64);
+        push(
+            &mut bytes,
+            match self.order {
+                ExploreOrder::Bfs => 0,
+                ExploreOrder::Dfs => 1,
+            },
+        );
+        push(&mut bytes, self.stats.steps);
+        push(&mut bytes, self.stats.divergences);
+        push(&mut bytes, self.peak_frontier as u64);
+        push(
+            &mut bytes,
+            self.elapsed.as_nanos().try_into().unwrap_or(u64::MAX),
+        );
+
+        let instructions = self.program.to_string();
+        push(&mut bytes, instructions.len() as u64);
+        bytes.extend_from_slice(instructions.as_bytes());
+        bytes.extend(
+            self.stats
+                .executed
+                .iter()
+                .map(|&executed| u8::from(executed)),
+        );
+
+        push(&mut bytes, self.states.len() as u64);
+        for (id, state) in self.states.iter().enumerate() {
+            push(&mut bytes, u64::from(state.accepting));
+            match &state.inner {
+                Some(inner) => {
+                    push(&mut bytes, 1);
+                    push(&mut bytes, inner.head_position as u64);
+                    push(&mut bytes, inner.instruction_position as u64);
+                    bytes.extend_from_slice(&inner.cells.0);
+                }
+                None => push(&mut bytes, 0),
+            }
+            push(&mut bytes, self.table.row_ids[id] as u64);
+            for &cost in &self.table.costs[id] {
+                push(&mut bytes, u64::from(cost));
+            }
+        }
+
+        push(&mut bytes, self.table.rows.len() as u64);
+        for (accepting, edges) in &self.table.rows {
+            push(&mut bytes, u64::from(*accepting));
+            for &to in edges {
+                push(
+                    &mut bytes,
+                    if to == Table::MISSING {
+                        MISSING
+                    } else {
+                        to as u64
+                    },
+                );
+            }
+        }
+
+        push(&mut bytes, self.frontier.len() as u64);
+        for &id in &self.frontier {
+            push(&mut bytes, id as u64);
+        }
+        bytes
+    }
+
+    /// Reads the format written by [`PartialBuild::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ImportError> {
+        let malformed = |message: &str| ImportError::Format(message.to_string());
+        let mut reader = Reader(
+            bytes
+                .strip_prefix(MAGIC)
+                .ok_or_else(|| malformed("missing BFAP header"))?,
+        );
+
+        let cell_count = reader.count()?;
+        let tape_length = reader.count()?;
+        let (Some(cell_count), Some(tape_length)) = (
+            NonZeroUsize::new(cell_count),
+            NonZeroUsize::new(tape_length),
+        ) else {
+            return Err(malformed("cell counts must be positive"));
+        };
+        let order = match reader.u64()? {
+            0 => ExploreOrder::Bfs,
+            1 => ExploreOrder::Dfs,
+            _ => return Err(malformed("unknown exploration order")),
+        };
+        let steps = reader.u64()?;
+        let divergences = reader.u64()?;
+        let peak_frontier = reader.count()?;
+        let elapsed = Duration::from_nanos(reader.u64()?);
+
+        let instruction_count = reader.count()?;
+        let instructions = std::str::from_utf8(reader.bytes(instruction_count)?)
+            .map_err(|_| malformed("instructions aren't valid UTF-8"))?;
+        let program = Program::new(instructions, tape_length);
+        if program.instructions.len() != instruction_count {
+            return Err(malformed("not a list of instructions"));
+        }
+        let executed: Vec<bool> = reader
+            .bytes(instruction_count)?
+            .iter()
+            .map(|&executed| executed != 0)
+            .collect();
+
+        let state_count = reader.count()?;
+        let mut states = Vec::new();
+        let mut row_ids = Vec::new();
+        let mut costs = Vec::new();
+        for _ in 0..state_count {
+            let accepting = reader.flag()?;
+            let inner = if reader.flag()? {
+                let head_position = reader.count()?;
+                let instruction_position = reader.count()?;
+                if head_position >= tape_length.get() || instruction_position > instruction_count {
+                    return Err(malformed("configuration doesn't fit the program"));
+                }
+                let cells = reader.bytes(tape_length.get().div_ceil(2))?;
+                Some(InnerState {
+                    cells: U4Vec(cells.iter().copied().collect()),
+                    head_position,
+                    instruction_position,
+                })
+            } else {
+                None
+            };
+            states.push(State { inner, accepting });
+            row_ids.push(reader.count()?);
+            let mut state_costs = [0; 16];
+            for cost in &mut state_costs {
+                *cost = u32::try_from(reader.u64()?).map_err(|_| malformed("cost too large"))?;
+            }
+            costs.push(state_costs);
+        }
+
+        let row_count = reader.count()?;
+        let mut rows = Vec::new();
+        for _ in 0..row_count {
+            let accepting = reader.flag()?;
+            let mut edges = [Table::MISSING; 16];
+            for edge in &mut edges {
+                *edge = match reader.u64()? {
+                    MISSING => Table::MISSING,
+                    to => usize::try_from(to)
+                        .ok()
+                        .filter(|&to| to < state_count)
+                        .ok_or_else(|| malformed("transition out of range"))?,
+                };
+            }
+            rows.push((accepting, edges));
+        }
+
+        let frontier_length = reader.count()?;
+        let frontier = (0..frontier_length)
+            .map(|_| {
+                reader
+                    .count()
+                    .ok()
+                    .filter(|&id| id < state_count)
+                    .ok_or_else(|| malformed("frontier state out of range"))
+            })
+            .collect::<Result<VecDeque<_>, _>>()?;
+        if !reader.0.is_empty() {
+            return Err(malformed("trailing bytes"));
+        }
+        let mut pending = vec![false; state_count];
+        for &id in &frontier {
+            pending[id] = true;
+        }
+        if state_count == 0 || (0..state_count).any(|id| !pending[id] && row_ids[id] >= row_count) {
+            return Err(malformed("row out of range"));
+        }
+
+        let state_ids: HashMap<_, _, _> = states
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(id, state)| (state, id))
+            .collect();
+        if state_ids.len() != states.len() {
+            return Err(malformed("duplicate states"));
+        }
+        let row_pool = rows
+            .iter()
+            .enumerate()
+            .map(|(id, &row)| (row, id))
+            .collect();
+        Ok(Self {
+            cell_count,
+            program,
+            order,
+            states,
+            state_ids,
+            table: Table {
+                rows,
+                row_ids,
+                costs,
+            },
+            row_pool,
+            frontier,
+            stats: RunStats {
+                steps,
+                divergences,
+                executed,
+                ..RunStats::default()
+            },
+            peak_frontier,
+            elapsed,
+        })
+    }
+}
+
+/// Little-endian numbers from the front of a byte slice.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], ImportError> {
+        if self.0.len() < count {
+            return Err(ImportError::Format("truncated".to_string()));
+        }
+        let (taken, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u64(&mut self) -> Result<u64, ImportError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn count(&mut self) -> Result<usize, ImportError> {
+        usize::try_from(self.u64()?).map_err(|_| ImportError::Format("count too large".to_string()))
+    }
+
+    fn flag(&mut self) -> Result<bool, ImportError> {
+        match self.u64()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ImportError::Format("flags must be 0 or 1".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{BuildOptions, ExploreOrder, Program, Table};
+
+    use super::PartialBuild;
+
+    #[test]
+    fn resuming_matches_an_uninterrupted_build() {
+        for order in [ExploreOrder::Bfs, ExploreOrder::Dfs] {
+            for program in crate::tests::benchmarks() {
+                let options = BuildOptions {
+                    order,
+                    ..BuildOptions::default()
+                };
+                let (expected, expected_report) = Table::build_with(&program, &options).unwrap();
+
+                let mut build = PartialBuild::new(&program, order);
+                let mut installments = 0;
+                // saved and loaded between every few states
+                while !build.run_for_states(3) {
+                    build = PartialBuild::from_bytes(&build.to_bytes()).unwrap();
+                    assert!(build.is_for(&program));
+                    installments += 1;
+                }
+                assert_eq!(build.states_discovered(), expected.state_count());
+                let (table, report) = build.finish();
+
+                assert!(table.rows().eq(expected.rows()));
+                assert_eq!(table.costs, expected.costs);
+                assert_eq!(report.states_explored, expected_report.states_explored);
+                assert_eq!(report.unique_rows, expected_report.unique_rows);
+                assert!(installments > 0 || expected.state_count() <= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn a_finished_build_round_trips() {
+        let program = Program::new(",[.,]", NonZeroUsize::new(1).unwrap());
+        let mut build = PartialBuild::new(&program, ExploreOrder::Bfs);
+        assert!(build.run_for_states(usize::MAX));
+        let loaded = PartialBuild::from_bytes(&build.to_bytes()).unwrap();
+        assert!(loaded.is_finished());
+        assert!(loaded.finish().0.rows().eq(build.finish().0.rows()));
+    }
+
+    #[test]
+    fn reads_at_the_end_of_the_program_round_trip() {
+        // the configurations waiting at the last `,` are past every instruction
+        for (text, cells) in [(",", 1), (",[.,]>,", 2)] {
+            let program = Program::new(text, NonZeroUsize::new(cells).unwrap());
+            let mut build = PartialBuild::new(&program, ExploreOrder::Bfs);
+            while !build.run_for_states(1) {
+                build = PartialBuild::from_bytes(&build.to_bytes()).unwrap();
+            }
+            let build = PartialBuild::from_bytes(&build.to_bytes()).unwrap();
+            assert!(build.finish().0.rows().eq(Table::build(&program).rows()));
+        }
+    }
+
+    #[test]
+    fn checkpoints_are_checked() {
+        let program = Program::new(",[.,]", NonZeroUsize::new(1).unwrap());
+        let other = Program::new(",[.,]", NonZeroUsize::new(2).unwrap());
+        let build = PartialBuild::new(&program, ExploreOrder::Bfs);
+        assert!(!build.is_for(&other));
+
+        let bytes = build.to_bytes();
+        assert!(PartialBuild::from_bytes(&bytes[1..]).is_err());
+        assert!(PartialBuild::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(PartialBuild::from_bytes(b"").is_err());
+    }
+}
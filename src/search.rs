@@ -0,0 +1,103 @@
+use std::ops::Range;
+
+use crate::Table;
+
+impl Table {
+    /// Finds the leftmost occurrence of a string of this language inside `haystack`, and the
+    /// shortest one among those starting there. An empty match is possible when the empty
+    /// string is accepted.
+    pub fn search(&self, haystack: &[u8]) -> Option<Range<usize>> {
+        self.search_from(haystack, 0, &self.co_reachable())
+    }
+
+    /// Successive non-overlapping matches as found by [`Table::search`], each one starting at
+    /// or after the end of the previous one. After an empty match the scan moves on by one
+    /// symbol.
+    pub fn find_iter<'a>(&'a self, haystack: &'a [u8]) -> impl Iterator<Item = Range<usize>> + 'a {
+        let co_reachable = self.co_reachable();
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            if position > haystack.len() {
+                return None;
+            }
+            let found = self.search_from(haystack, position, &co_reachable)?;
+            position = if found.is_empty() {
+                found.end + 1
+            } else {
+                found.end
+            };
+            Some(found)
+        })
+    }
+
+    fn search_from(
+        &self,
+        haystack: &[u8],
+        from: usize,
+        co_reachable: &[bool],
+    ) -> Option<Range<usize>> {
+        if !co_reachable[0] {
+            return None;
+        }
+
+        for start in from..=haystack.len() {
+            let mut state = 0;
+            if self.is_accepting(state) {
+                return Some(start..start);
+            }
+            for (end, &symbol) in haystack.iter().enumerate().skip(start) {
+                state = self.transition(state, symbol);
+                if state == Table::MISSING || !co_reachable[state] {
+                    break;
+                }
+                if self.is_accepting(state) {
+                    return Some(start..end + 1);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::exactly, Table};
+
+    #[test]
+    fn finds_the_pattern_anywhere() {
+        let pattern = exactly(&[1, 2]);
+        assert_eq!(pattern.search(&[1, 2, 5]), Some(0..2));
+        assert_eq!(pattern.search(&[5, 1, 2, 5]), Some(1..3));
+        assert_eq!(pattern.search(&[5, 5, 1, 2]), Some(2..4));
+        assert_eq!(pattern.search(&[2, 1, 1]), None);
+        assert_eq!(pattern.search(&[]), None);
+    }
+
+    #[test]
+    fn leftmost_then_shortest() {
+        // the leftmost match wins even though a shorter one starts later
+        let pattern = exactly(&[1, 2]).union(&exactly(&[2]));
+        assert_eq!(pattern.search(&[1, 2]), Some(0..2));
+        // among the matches starting at the same place, the shortest wins
+        let pattern = exactly(&[1]).union(&exactly(&[1, 2]));
+        assert_eq!(pattern.search(&[1, 2]), Some(0..1));
+    }
+
+    #[test]
+    fn non_overlapping_matches() {
+        let pattern = exactly(&[1, 1]);
+        assert_eq!(
+            pattern.find_iter(&[1, 1, 1, 1, 1]).collect::<Vec<_>>(),
+            vec![0..2, 2..4]
+        );
+        // empty matches move on by one symbol
+        let empty = exactly(&[]);
+        assert_eq!(
+            empty.find_iter(&[5, 5]).collect::<Vec<_>>(),
+            vec![0..0, 1..1, 2..2]
+        );
+        let nothing = Table::from_rows(vec![(false, [0; 16])]);
+        assert_eq!(nothing.find_iter(&[1, 2, 3]).count(), 0);
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::Write;
+
+use crate::Table;
+
+impl Table {
+    /// The automaton in SMT-LIB 2, for asking a solver about it. States and symbols are
+    /// integers, with `valid-state` and `valid-input` constraining their range, `initial` is
+    /// the start state, `(trans s i)` the state reached from `s` on `i` and `(accepting s)`
+    /// whether `s` is accepting. Partial tables are completed first.
+    ///
+    /// `trans` is a chain of `ite`s over the states, each holding one over the inputs, with
+    /// the last state and input as the fallthrough so that every pair appears once. Only
+    /// valid arguments give meaningful results.
+    pub fn to_smt2(&self) -> String {
+        let table = self.completed();
+        let state_count = table.state_count();
+        let mut output = String::new();
+        writeln!(&mut output, "; Generated by bfa, {state_count} states").unwrap();
+        writeln!(
+            &mut output,
+            "(define-fun valid-state ((s Int)) Bool (and (<= 0 s) (< s {state_count})))"
+        )
+        .unwrap();
+        output.push_str("(define-fun valid-input ((i Int)) Bool (and (<= 0 i) (< i 16)))\n");
+        output.push_str("(define-fun initial () Int 0)\n");
+
+        output.push_str("(define-fun trans ((s Int) (i Int)) Int");
+        if state_count == 0 {
+            output.push_str(" 0)\n");
+        } else {
+            let mut closing = 1;
+            for (from, (_, edges)) in table.rows().enumerate() {
+                let mut inputs = String::new();
+                for (input, &to) in edges.iter().enumerate().take(15) {
+                    write!(&mut inputs, "(ite (= i {input}) {to} ").unwrap();
+                }
+                write!(&mut inputs, "{}{}", edges[15], ")".repeat(15)).unwrap();
+                if from + 1 < state_count {
+                    write!(&mut output, "\n  (ite (= s {from}) {inputs}").unwrap();
+                    closing += 1;
+                } else {
+                    write!(&mut output, "\n  {inputs}").unwrap();
+                }
+            }
+            writeln!(&mut output, "{}", ")".repeat(closing)).unwrap();
+        }
+
+        let accepting: Vec<String> = (0..state_count)
+            .filter(|&state| table.is_accepting(state))
+            .map(|state| format!("(= s {state})"))
+            .collect();
+        let body = match accepting.len() {
+            0 => "false".to_string(),
+            1 => accepting[0].clone(),
+            _ => format!("(or {})", accepting.join(" ")),
+        };
+        writeln!(&mut output, "(define-fun accepting ((s Int)) Bool {body})").unwrap();
+        output
+    }
+
+    /// [`Table::to_smt2`] followed by a query for an accepted string of exactly `length`
+    /// symbols: `x0` to `x{length - 1}` are the symbols and `s0` to `s{length}` the states
+    /// passed through. The solver answers `sat` with such a string in the model, or `unsat`.
+    pub fn smt2_acceptance_query(&self, length: usize) -> String {
+        let mut output = self.to_smt2();
+        output.push_str("\n(declare-const s0 Int)\n(assert (= s0 initial))\n");
+        for position in 0..length {
+            let next = position + 1;
+            writeln!(
+                &mut output,
+                "(declare-const x{position} Int)\n(assert (valid-input x{position}))\n(declare-const s{next} Int)\n(assert (= s{next} (trans s{position} x{position})))"
+            )
+            .unwrap();
+        }
+        writeln!(
+            &mut output,
+            "(assert (accepting s{length}))\n(check-sat)\n(get-model)"
+        )
+        .unwrap();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::exactly, Table};
+
+    #[derive(Debug)]
+    enum Expr {
+        Atom(String),
+        List(Vec<Expr>),
+    }
+
+    /// Parses the s-expression at the start of `tokens`.
+    fn parse(tokens: &mut std::iter::Peekable<impl Iterator<Item = String>>) -> Expr {
+        let token = tokens.next().unwrap();
+        if token != "(" {
+            return Expr::Atom(token);
+        }
+        let mut list = Vec::new();
+        while tokens.peek().unwrap() != ")" {
+            list.push(parse(tokens));
+        }
+        tokens.next();
+        Expr::List(list)
+    }
+
+    /// The top-level expressions of `smt2`, comments removed.
+    fn expressions(smt2: &str) -> Vec<Expr> {
+        let text: String = smt2
+            .lines()
+            .filter(|line| !line.starts_with(';'))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut tokens = text
+            .replace('(', " ( ")
+            .replace(')', " ) ")
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .peekable();
+        let mut expressions = Vec::new();
+        while tokens.peek().is_some() {
+            expressions.push(parse(&mut tokens));
+        }
+        expressions
+    }
+
+    /// The value of an integer or boolean expression over `s` and `i`, booleans being 0 or 1.
+    fn eval(expr: &Expr, s: i64, i: i64) -> i64 {
+        match expr {
+            Expr::Atom(atom) => match atom.as_str() {
+                "s" => s,
+                "i" => i,
+                "true" => 1,
+                "false" => 0,
+                number => number.parse().unwrap(),
+            },
+            Expr::List(list) => {
+                let Expr::Atom(operator) = &list[0] else {
+                    panic!("unexpected {list:?}");
+                };
+                let args: Vec<i64> = list[1..].iter().map(|arg| eval(arg, s, i)).collect();
+                match operator.as_str() {
+                    "ite" => {
+                        if args[0] != 0 {
+                            args[1]
+                        } else {
+                            args[2]
+                        }
+                    }
+                    "=" => i64::from(args[0] == args[1]),
+                    "<=" => i64::from(args[0] <= args[1]),
+                    "<" => i64::from(args[0] < args[1]),
+                    "and" => i64::from(args.iter().all(|&arg| arg != 0)),
+                    "or" => i64::from(args.iter().any(|&arg| arg != 0)),
+                    other => panic!("unknown operator {other}"),
+                }
+            }
+        }
+    }
+
+    /// The body of each `define-fun`, by name.
+    fn definitions(smt2: &str) -> std::collections::HashMap<String, Expr> {
+        expressions(smt2)
+            .into_iter()
+            .map(|expr| {
+                let Expr::List(mut list) = expr else {
+                    panic!("expected a definition");
+                };
+                let body = list.pop().unwrap();
+                let Expr::Atom(name) = &list[1] else {
+                    panic!("expected a name");
+                };
+                (name.clone(), body)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn definitions_agree_with_the_table() {
+        let tables = crate::tests::benchmarks()
+            .map(|program| Table::build(&program).minimized())
+            .chain([exactly(&[1, 2]), Table::from_rows(vec![(false, [0; 16])])]);
+        for table in tables {
+            let definitions = definitions(&table.to_smt2());
+            assert_eq!(eval(&definitions["initial"], 0, 0), 0);
+            for state in 0..table.state_count() {
+                assert_eq!(
+                    eval(&definitions["accepting"], state as i64, 0) != 0,
+                    table.is_accepting(state)
+                );
+                assert_eq!(eval(&definitions["valid-state"], state as i64, 0), 1);
+                for input in 0..16 {
+                    assert_eq!(
+                        eval(&definitions["trans"], state as i64, input),
+                        table.transition(state, input as u8) as i64
+                    );
+                }
+            }
+            let past_the_end = table.state_count() as i64;
+            assert_eq!(eval(&definitions["valid-state"], past_the_end, 0), 0);
+            assert_eq!(eval(&definitions["valid-input"], 0, 16), 0);
+            assert_eq!(eval(&definitions["valid-input"], 0, 15), 1);
+        }
+    }
+
+    #[test]
+    fn partial_tables_are_completed() {
+        let smt2 = exactly(&[1, 2]).to_partial().to_smt2();
+        assert!(smt2.starts_with("; Generated by bfa, 4 states\n"));
+        let definitions = definitions(&smt2);
+        assert_eq!(eval(&definitions["trans"], 0, 5), 3);
+        assert_eq!(eval(&definitions["trans"], 3, 1), 3);
+    }
+
+    #[test]
+    fn acceptance_queries() {
+        let query = exactly(&[1, 2]).smt2_acceptance_query(2);
+        assert!(query.starts_with(&exactly(&[1, 2]).to_smt2()));
+        for declared in ["s0", "x0", "s1", "x1", "s2"] {
+            assert!(query.contains(&format!("(declare-const {declared} Int)\n")));
+        }
+        assert!(!query.contains("x2"));
+        assert!(query.contains("(assert (= s2 (trans s1 x1)))\n"));
+        assert!(query.ends_with("(assert (accepting s2))\n(check-sat)\n(get-model)\n"));
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    ops::RangeInclusive,
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use rustc_hash::FxHasher;
+
+use crate::{BuildError, BuildOptions, BuildReport, EquivResult, Program, Table};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SweepEntry {
+    pub cell_count: NonZeroUsize,
+    /// States of the minimized table.
+    pub states: usize,
+    /// Hash of the canonical minimal table, equal for equal languages.
+    pub fingerprint: u64,
+    /// Whether the language is the same as for the previous entry's cell count.
+    pub same_as_previous: bool,
+}
+
+impl Table {
+    /// The minimal table with unreachable states dropped and states numbered breadth-first,
+    /// which is the same for any two tables with the same language.
+    pub(crate) fn canonical(&self) -> Table {
+        self.minimized().restrict_reachable(0)
+    }
+
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = FxHasher::default();
+        for row in self.rows() {
+            row.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// Builds the program for each cell count in `cells`, zero being skipped, to show how the
+/// language changes as the tape grows.
+///
+/// With `stop_after` set to `k`, the sweep ends early once `k` consecutive cell counts have
+/// produced the same language.
+pub fn sweep(
+    program_text: &str,
+    cells: RangeInclusive<usize>,
+    stop_after: Option<usize>,
+) -> Vec<SweepEntry> {
+    let mut entries: Vec<SweepEntry> = Vec::new();
+    let mut previous: Option<Table> = None;
+    let mut equal_run = 0;
+
+    for cell_count in cells.filter_map(NonZeroUsize::new) {
+        let table = Table::build(&Program::new(program_text, cell_count)).canonical();
+        let same_as_previous = previous.as_ref().is_some_and(|previous| {
+            matches!(previous.compare(&table), EquivResult::Equivalent { .. })
+        });
+        equal_run = if same_as_previous { equal_run + 1 } else { 1 };
+
+        entries.push(SweepEntry {
+            cell_count,
+            states: table.state_count(),
+            fingerprint: table.fingerprint(),
+            same_as_previous,
+        });
+        if stop_after.is_some_and(|k| equal_run >= k) {
+            break;
+        }
+        previous = Some(table);
+    }
+
+    entries
+}
+
+/// One cell count of [`sweep_with`]: its entry, the report of its build and its canonical
+/// table, or the limit the build hit.
+pub type SweepResult = (
+    NonZeroUsize,
+    Result<(SweepEntry, BuildReport, Table), BuildError>,
+);
+
+/// Like [`sweep`] without stopping early, with every cell count built under `options`, in
+/// parallel with the `rayon` feature. A cell count whose build hits a limit is reported as
+/// such and the next one isn't compared against it.
+pub fn sweep_with(
+    program_text: &str,
+    cells: RangeInclusive<usize>,
+    options: &BuildOptions,
+) -> Vec<SweepResult> {
+    let cell_counts: Vec<NonZeroUsize> = cells.filter_map(NonZeroUsize::new).collect();
+    let build = |&cell_count: &NonZeroUsize| {
+        Table::build_with(&Program::new(program_text, cell_count), options)
+            .map(|(table, report)| (table.canonical(), report))
+    };
+    #[cfg(feature = "rayon")]
+    let builds: Vec<_> = cell_counts.par_iter().map(build).collect();
+    #[cfg(not(feature = "rayon"))]
+    let builds: Vec<_> = cell_counts.iter().map(build).collect();
+
+    let mut results: Vec<SweepResult> = Vec::with_capacity(builds.len());
+    for (cell_count, build) in cell_counts.into_iter().zip(builds) {
+        let result = build.map(|(table, report)| {
+            let same_as_previous = matches!(
+                results.last(),
+                Some((_, Ok((_, _, previous))))
+                    if matches!(previous.compare(&table), EquivResult::Equivalent { .. })
+            );
+            let entry = SweepEntry {
+                cell_count,
+                states: table.state_count(),
+                fingerprint: table.fingerprint(),
+                same_as_previous,
+            };
+            (entry, report, table)
+        });
+        results.push((cell_count, result));
+    }
+    results
+}
+
+impl Program {
+    /// The smallest cell count up to `max` whose language is the same for the next
+    /// `stable_window` larger counts, all of which must also be at most `max`.
+    pub fn infer_cell_count(&self, max: usize, stable_window: usize) -> Option<NonZeroUsize> {
+        self.infer_cell_count_with(max, stable_window, &BuildOptions::default())
+    }
+
+    /// Like [`Program::infer_cell_count`], with every candidate built under `options`. A cell
+    /// count whose build hits a limit never matches any other.
+    pub fn infer_cell_count_with(
+        &self,
+        max: usize,
+        stable_window: usize,
+        options: &BuildOptions,
+    ) -> Option<NonZeroUsize> {
+        let build = |cell_count: usize| {
+            let program = Program {
+                cell_count: NonZeroUsize::new(cell_count).unwrap(),
+                instructions: self.instructions.clone(),
+                spans: self.spans.clone(),
+            };
+            Table::build_with(&program, options)
+                .ok()
+                .map(|(table, _)| table.canonical())
+        };
+
+        // canonical table for each cell count so far, `None` where the build failed
+        let mut tables: Vec<Option<Table>> = vec![None];
+        for candidate in 1..=max.saturating_sub(stable_window) {
+            while tables.len() <= candidate + stable_window {
+                tables.push(build(tables.len()));
+            }
+            let Some(base) = &tables[candidate] else {
+                continue;
+            };
+            let stable = tables[candidate + 1..=candidate + stable_window]
+                .iter()
+                .all(|larger| {
+                    larger.as_ref().is_some_and(|larger| {
+                        matches!(base.compare(larger), EquivResult::Equivalent { .. })
+                    })
+                });
+            if stable {
+                return NonZeroUsize::new(candidate);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::{sweep, sweep_with};
+    use crate::{BuildOptions, Program};
+
+    /// Accepts the inputs starting with a nonzero symbol on one or two cells, where moving
+    /// two cells left comes back to the first, and nothing on more.
+    const WRAPS_ON_TWO_CELLS: &str = ",<<[.[-]]";
+
+    #[test]
+    fn language_changes_between_two_and_three_cells() {
+        let entries = sweep(WRAPS_ON_TWO_CELLS, 0..=5, None);
+        let summary: Vec<(usize, usize, bool)> = entries
+            .iter()
+            .map(|entry| (entry.cell_count.get(), entry.states, entry.same_as_previous))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, 3, false),
+                (2, 3, true),
+                (3, 1, false),
+                (4, 1, true),
+                (5, 1, true)
+            ]
+        );
+        assert_eq!(entries[0].fingerprint, entries[1].fingerprint);
+        assert_ne!(entries[1].fingerprint, entries[2].fingerprint);
+        assert_eq!(entries[2].fingerprint, entries[4].fingerprint);
+    }
+
+    #[test]
+    fn stops_once_stable() {
+        let entries = sweep(WRAPS_ON_TWO_CELLS, 1..=8, Some(3));
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries.last().unwrap().cell_count.get(), 5);
+    }
+
+    #[test]
+    fn sweep_with_agrees_with_sweep() {
+        let entries = sweep(WRAPS_ON_TWO_CELLS, 1..=5, None);
+        let results = sweep_with(WRAPS_ON_TWO_CELLS, 1..=5, &BuildOptions::default());
+        assert_eq!(results.len(), entries.len());
+        for ((cell_count, result), entry) in results.iter().zip(&entries) {
+            assert_eq!(*cell_count, entry.cell_count);
+            assert_eq!(&result.as_ref().unwrap().0, entry);
+        }
+    }
+
+    #[test]
+    fn failed_builds_are_reported() {
+        let options = BuildOptions {
+            max_states: Some(1),
+            ..Default::default()
+        };
+        let results = sweep_with(WRAPS_ON_TWO_CELLS, 1..=2, &options);
+        assert!(results.iter().all(|(_, result)| result.is_err()));
+    }
+
+    fn inferred(text: &str, max: usize) -> Option<usize> {
+        Program::new(text, NonZeroUsize::MIN)
+            .infer_cell_count(max, 2)
+            .map(NonZeroUsize::get)
+    }
+
+    #[test]
+    fn infers_the_smallest_stable_cell_count() {
+        assert_eq!(inferred(",[.[-]]", 6), Some(1));
+        // clears the first symbol on one cell, where the second cell is the first
+        assert_eq!(inferred(",>[-]<[.[-]]", 6), Some(2));
+        assert_eq!(inferred(WRAPS_ON_TWO_CELLS, 6), Some(3));
+    }
+
+    #[test]
+    fn stability_must_be_seen_within_the_maximum() {
+        assert_eq!(inferred(WRAPS_ON_TWO_CELLS, 4), None);
+        assert_eq!(inferred(WRAPS_ON_TWO_CELLS, 5), Some(3));
+    }
+
+    #[test]
+    fn failed_builds_never_match() {
+        let options = BuildOptions {
+            max_states: Some(2),
+            ..Default::default()
+        };
+        let program = Program::new(",[.[-]]", NonZeroUsize::MIN);
+        assert_eq!(program.infer_cell_count_with(6, 2, &options), None);
+        // the empty language of three or more cells fits in two states
+        let program = Program::new(WRAPS_ON_TWO_CELLS, NonZeroUsize::MIN);
+        assert_eq!(
+            program.infer_cell_count_with(6, 2, &options),
+            NonZeroUsize::new(3)
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{fmt::Display, num::NonZeroUsize};
+
+use crate::{EquivResult, Program, Table};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SynthesisError {
+    /// The encoding needs `needed` cells but only `available` were offered.
+    TooFewCells { needed: usize, available: usize },
+    /// The synthesized program doesn't recognize the table's language, `witness` being a
+    /// string they disagree on. This is a bug in bfa.
+    Mismatch { witness: Vec<u8> },
+}
+
+impl Display for SynthesisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooFewCells { needed, available } => write!(
+                f,
+                "the program needs {needed} cells but only {available} are available"
+            ),
+            Self::Mismatch { witness } => {
+                f.write_str("the synthesized program disagrees with the table on \"")?;
+                for symbol in witness {
+                    write!(f, "{symbol:X}")?;
+                }
+                f.write_str("\"")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SynthesisError {}
+
+/// Program text with the head position tracked, so that cells can be addressed by index.
+struct Emitter {
+    text: String,
+    head: usize,
+}
+
+impl Emitter {
+    fn goto(&mut self, cell: usize) {
+        let step = if cell > self.head { '>' } else { '<' };
+        self.text
+            .extend(std::iter::repeat_n(step, cell.abs_diff(self.head)));
+        self.head = cell;
+    }
+
+    /// Adds `amount` modulo 16, going whichever way is shorter.
+    fn add(&mut self, cell: usize, amount: u8) {
+        self.goto(cell);
+        let amount = (amount & 0x0F) as usize;
+        if amount <= 8 {
+            self.text.extend(std::iter::repeat_n('+', amount));
+        } else {
+            self.text.extend(std::iter::repeat_n('-', 16 - amount));
+        }
+    }
+
+    fn clear(&mut self, cell: usize) {
+        self.goto(cell);
+        self.text.push_str("[-]");
+    }
+
+    /// Adds `from` to each of `to`, leaving `from` at zero.
+    fn move_to(&mut self, from: usize, to: &[usize]) {
+        self.goto(from);
+        self.text.push_str("[-");
+        for &cell in to {
+            self.add(cell, 1);
+        }
+        self.goto(from);
+        self.text.push(']');
+    }
+
+    /// Runs `body` once if `cell` is nonzero, clearing it afterwards.
+    fn if_nonzero(&mut self, cell: usize, body: impl FnOnce(&mut Self)) {
+        self.goto(cell);
+        self.text.push('[');
+        body(self);
+        self.clear(cell);
+        self.text.push(']');
+    }
+}
+
+/// Cells used by the encoding of [`Table::to_program`].
+struct Layout {
+    /// Always 1, to keep the main loop going.
+    running: usize,
+    input: usize,
+    /// Base 16 digits of the current state, least significant first.
+    state: Vec<usize>,
+    /// The next state, built up while dispatching on the current one.
+    next: Vec<usize>,
+    /// Scratch cells, zero between uses.
+    difference: usize,
+    scratch: usize,
+    /// Set while the current state matches, and while the input matches within that.
+    state_matches: usize,
+    input_matches: usize,
+}
+
+impl Layout {
+    fn new(digits: usize) -> Self {
+        Self {
+            running: 0,
+            input: 1,
+            state: (2..2 + digits).collect(),
+            next: (2 + digits..2 + 2 * digits).collect(),
+            difference: 2 + 2 * digits,
+            scratch: 3 + 2 * digits,
+            state_matches: 4 + 2 * digits,
+            input_matches: 5 + 2 * digits,
+        }
+    }
+
+    fn cell_count(&self) -> usize {
+        self.input_matches + 1
+    }
+
+    /// Runs `body` if every cell in `cells` holds its paired value, using `flag`.
+    fn if_equal(
+        &self,
+        emitter: &mut Emitter,
+        cells: &[(usize, u8)],
+        flag: usize,
+        body: impl FnOnce(&mut Emitter),
+    ) {
+        emitter.add(flag, 1);
+        for &(cell, value) in cells {
+            emitter.move_to(cell, &[self.difference, self.scratch]);
+            emitter.move_to(self.scratch, &[cell]);
+            emitter.add(self.difference, 16 - (value & 0x0F));
+            emitter.if_nonzero(self.difference, |emitter| emitter.clear(flag));
+        }
+        emitter.if_nonzero(flag, body);
+    }
+}
+
+/// Base 16 digits of `value`, least significant first.
+fn digits(value: usize, count: usize) -> impl Iterator<Item = u8> {
+    (0..count).map(move |digit| ((value >> (4 * digit)) & 0x0F) as u8)
+}
+
+impl Table {
+    /// A program whose language is this table's, using at most `cells_available` cells.
+    ///
+    /// The program keeps the current state in base 16 on the tape and, after each read,
+    /// compares it and the symbol against every transition to find the next state, running
+    /// a `.` if that one is accepting. The output grows with the number of transitions, so
+    /// minimize first. The result is rebuilt and compared against the table before it's
+    /// returned.
+    pub fn to_program(&self, cells_available: NonZeroUsize) -> Result<String, SynthesisError> {
+        let table = self.completed();
+        let state_count = table.state_count();
+        let mut digit_count = 1;
+        while digit_count < usize::BITS as usize / 4 && state_count > 1 << (4 * digit_count) {
+            digit_count += 1;
+        }
+        let layout = Layout::new(digit_count);
+        if layout.cell_count() > cells_available.get() {
+            return Err(SynthesisError::TooFewCells {
+                needed: layout.cell_count(),
+                available: cells_available.get(),
+            });
+        }
+
+        let mut emitter = Emitter {
+            text: String::new(),
+            head: 0,
+        };
+        if table.is_accepting(0) {
+            emitter.text.push('.');
+        }
+        emitter.add(layout.running, 1);
+        emitter.text.push('[');
+        emitter.goto(layout.input);
+        emitter.text.push(',');
+
+        for state in 0..state_count {
+            let state_digits: Vec<(usize, u8)> = layout
+                .state
+                .iter()
+                .copied()
+                .zip(digits(state, digit_count))
+                .collect();
+            layout.if_equal(
+                &mut emitter,
+                &state_digits,
+                layout.state_matches,
+                |emitter| {
+                    for input in 0..16 {
+                        let to = table.transition(state, input);
+                        let input_digit = [(layout.input, input)];
+                        layout.if_equal(emitter, &input_digit, layout.input_matches, |emitter| {
+                            for (&cell, digit) in layout.next.iter().zip(digits(to, digit_count)) {
+                                emitter.add(cell, digit);
+                            }
+                            if table.is_accepting(to) {
+                                emitter.text.push('.');
+                            }
+                        });
+                    }
+                },
+            );
+        }
+
+        for (&state_cell, &next_cell) in layout.state.iter().zip(&layout.next) {
+            emitter.clear(state_cell);
+            emitter.move_to(next_cell, &[state_cell]);
+        }
+        // the last symbol would otherwise be part of the next configuration
+        emitter.clear(layout.input);
+        emitter.goto(layout.running);
+        emitter.text.push(']');
+
+        let program = Program::new(&emitter.text, cells_available);
+        let rebuilt = Table::build(&program);
+        match self.compare(&rebuilt) {
+            EquivResult::Equivalent { .. } => Ok(emitter.text),
+            EquivResult::Different { witness, .. } => Err(SynthesisError::Mismatch { witness }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{tests::exactly, EquivResult, Program, Table};
+
+    use super::SynthesisError;
+
+    fn round_trip(table: &Table, cells: usize) {
+        let cells = NonZeroUsize::new(cells).unwrap();
+        let text = table.to_program(cells).unwrap();
+        let rebuilt = Table::build(&Program::new(&text, cells)).minimized();
+        assert!(matches!(
+            table.compare(&rebuilt),
+            EquivResult::Equivalent { .. }
+        ));
+        assert_eq!(rebuilt.state_count(), table.minimized().state_count());
+    }
+
+    #[test]
+    fn benchmarks_round_trip() {
+        for program in crate::tests::benchmarks() {
+            // the largest has 33 states, so two digits
+            round_trip(&Table::build(&program).minimized(), 10);
+        }
+    }
+
+    #[test]
+    fn hand_built_tables_round_trip() {
+        // the empty word is accepted, so the program starts with a `.`
+        round_trip(&exactly(&[]), 8);
+        round_trip(&exactly(&[1, 2, 3]), 8);
+        // a missing transition rejects
+        let mut edges = [Table::MISSING; 16];
+        edges[0xA] = 0;
+        round_trip(&Table::from_rows(vec![(true, edges)]), 8);
+        // 18 states need a second digit
+        round_trip(&exactly(&[0; 16]), 10);
+    }
+
+    #[test]
+    fn too_few_cells() {
+        let cells = NonZeroUsize::new(7).unwrap();
+        assert_eq!(
+            exactly(&[1]).to_program(cells),
+            Err(SynthesisError::TooFewCells {
+                needed: 8,
+                available: 7
+            })
+        );
+        let cells = NonZeroUsize::new(9).unwrap();
+        assert_eq!(
+            exactly(&[0; 16]).to_program(cells),
+            Err(SynthesisError::TooFewCells {
+                needed: 10,
+                available: 9
+            })
+        );
+    }
+
+    #[test]
+    fn mismatches_show_the_witness() {
+        let error = SynthesisError::Mismatch {
+            witness: vec![0, 0xA, 3],
+        };
+        assert_eq!(
+            error.to_string(),
+            "the synthesized program disagrees with the table on \"0A3\""
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+use crate::{BuildError, BuildOptions, Program, Table};
+
+/// The instructions executed by each transition of a table built with
+/// [`Table::build_traced`].
+#[derive(Clone, Debug, Default)]
+pub struct TraceMap {
+    /// Instruction positions for each `(state, input)`, at index `state * 16 + input`.
+    traces: Vec<Vec<usize>>,
+    truncated: Vec<bool>,
+}
+
+impl TraceMap {
+    pub(crate) fn record(&mut self, state: usize, input: u8, trace: Vec<usize>, truncated: bool) {
+        let index = state * 16 + input as usize;
+        if self.traces.len() <= index {
+            self.traces.resize_with((state + 1) * 16, Vec::new);
+            self.truncated.resize((state + 1) * 16, false);
+        }
+        self.traces[index] = trace;
+        self.truncated[index] = truncated;
+    }
+
+    /// Positions of the instructions executed after reading `input` in `state`, up to the
+    /// next read. Empty for halted states, whose transitions run nothing.
+    pub fn trace(&self, state: usize, input: u8) -> &[usize] {
+        self.traces
+            .get(state * 16 + (input & 0x0F) as usize)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether the trace was cut short by [`BuildOptions::trace_limit`].
+    pub fn is_truncated(&self, state: usize, input: u8) -> bool {
+        self.truncated
+            .get(state * 16 + (input & 0x0F) as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// The trace as instruction characters, each followed by its byte offset in the source
+    /// (or its position if the program has no spans), ending in `…` if truncated.
+    pub fn display(&self, program: &Program, state: usize, input: u8) -> String {
+        let mut text = String::new();
+        for (i, &position) in self.trace(state, input).iter().enumerate() {
+            if i > 0 {
+                text.push(' ');
+            }
+            let offset = program
+                .spans
+                .get(position)
+                .map_or(position, |span| span.start);
+            let _ = write!(
+                text,
+                "{}@{offset}",
+                program.instructions[position].to_char()
+            );
+        }
+        if self.is_truncated(state, input) {
+            text.push_str(" …");
+        }
+        text
+    }
+}
+
+impl Table {
+    /// Like [`Table::build`], but also records every transition's instructions. This can use a
+    /// lot of memory; see [`Table::build_traced_with`] for capping it.
+    pub fn build_traced(program: &Program) -> (Self, TraceMap) {
+        Self::build_traced_with(program, &BuildOptions::default())
+            .expect("no limits are set by default")
+    }
+
+    pub fn build_traced_with(
+        program: &Program,
+        options: &BuildOptions,
+    ) -> Result<(Self, TraceMap), BuildError> {
+        let mut traces = TraceMap::default();
+        let (table, _, _) = Self::explore(program, options, Some(&mut traces), None)?;
+        Ok((table, traces))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use crate::{BuildOptions, Program, Table};
+
+    fn program() -> Program {
+        Program::new(", [-].", NonZeroUsize::new(1).unwrap())
+    }
+
+    #[test]
+    fn hand_checked_trace() {
+        let program = program();
+        let (table, traces) = Table::build_traced(&program);
+        assert!(table.rows().eq(Table::build(&program).rows()));
+
+        // the loop runs twice, then its test fails and jumps past the `]`
+        assert_eq!(traces.trace(0, 2), [1, 2, 3, 1, 2, 3, 1, 4]);
+        assert_eq!(traces.trace(0, 0), [1, 4]);
+        assert_eq!(traces.display(&program, 0, 1), "[@2 -@3 ]@4 [@2 .@5");
+        assert!(!traces.is_truncated(0, 2));
+
+        let halted = table.transition(0, 0);
+        assert!(traces.trace(halted, 0).is_empty());
+    }
+
+    #[test]
+    fn traces_are_capped() {
+        let program = program();
+        let options = BuildOptions {
+            trace_limit: Some(3),
+            ..Default::default()
+        };
+        let (_, traces) = Table::build_traced_with(&program, &options).unwrap();
+        assert_eq!(traces.trace(0, 1), [1, 2, 3]);
+        assert!(traces.is_truncated(0, 1));
+        assert_eq!(traces.display(&program, 0, 1), "[@2 -@3 ]@4 …");
+
+        // shorter traces are kept whole
+        assert_eq!(traces.trace(0, 0), [1, 4]);
+        assert!(!traces.is_truncated(0, 0));
+    }
+}
@@ -0,0 +1,276 @@
+//! Cross-checks a [`Table`] against a direct interpretation of its [`Program`].
+
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{rng::Rng, Instruction, Program, Table};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub input: Vec<u8>,
+    /// Verdict of the reference interpreter.
+    pub expected: bool,
+    /// Verdict of the table.
+    pub actual: bool,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("input \"")?;
+        for symbol in &self.input {
+            write!(f, "{symbol:X}")?;
+        }
+        write!(
+            f,
+            "\" is {} by the program but {} by the table",
+            if self.expected {
+                "accepted"
+            } else {
+                "rejected"
+            },
+            if self.actual { "accepted" } else { "rejected" },
+        )
+    }
+}
+
+/// Runs the program on the whole input, one instruction at a time.
+///
+/// This deliberately shares no code with the table builder: brackets are matched by scanning,
+/// the tape is a plain `Vec<u8>`, and divergence is detected by remembering every configuration
+/// since the last read.
+pub fn interpret(program: &Program, input: &[u8]) -> bool {
+    let instructions = &program.instructions;
+    let cell_count = program.cell_count.get();
+    let mut cells = vec![0u8; cell_count];
+    let mut head = 0;
+    let mut position = 0;
+    let mut remaining = input.iter();
+    let mut accepting = false;
+    let mut seen = HashSet::new();
+
+    while position < instructions.len() {
+        if !seen.insert((cells.clone(), head, position)) {
+            return accepting;
+        }
+
+        match instructions[position] {
+            Instruction::MoveLeft => head = (head + cell_count - 1) % cell_count,
+            Instruction::MoveRight => head = (head + 1) % cell_count,
+            Instruction::Increment => cells[head] = (cells[head] + 1) % 16,
+            Instruction::Decrement => cells[head] = (cells[head] + 15) % 16,
+            Instruction::StartLoop => {
+                if cells[head] == 0 {
+                    match matching_end(instructions, position) {
+                        Some(end) => position = end,
+                        None => return accepting,
+                    }
+                }
+            }
+            Instruction::EndLoop => match matching_start(instructions, position) {
+                Some(start) => {
+                    position = start;
+                    continue;
+                }
+                None => return accepting,
+            },
+            Instruction::Read => match remaining.next() {
+                Some(&symbol) => {
+                    cells[head] = symbol & 0x0F;
+                    accepting = false;
+                    seen.clear();
+                }
+                None => return accepting,
+            },
+            Instruction::Accept => accepting = true,
+        }
+        position += 1;
+    }
+
+    accepting
+}
+
+fn matching_end(instructions: &[Instruction], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (position, instruction) in instructions.iter().enumerate().skip(start) {
+        match instruction {
+            Instruction::StartLoop => depth += 1,
+            Instruction::EndLoop => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(position);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn matching_start(instructions: &[Instruction], end: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (position, instruction) in instructions[..=end].iter().enumerate().rev() {
+        match instruction {
+            Instruction::EndLoop => depth += 1,
+            Instruction::StartLoop => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(position);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn check(program: &Program, table: &Table, input: &[u8]) -> Result<(), Mismatch> {
+    let expected = interpret(program, input);
+    let actual = table.accepts(input);
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Mismatch {
+            input: input.to_vec(),
+            expected,
+            actual,
+        })
+    }
+}
+
+/// Compares the table against [`interpret`] on `samples` random inputs of length at most
+/// `max_len`, returning the first disagreement.
+pub fn random_check(
+    program: &Program,
+    table: &Table,
+    samples: usize,
+    max_len: usize,
+    seed: u64,
+) -> Result<(), Mismatch> {
+    let mut rng = Rng::new(seed);
+    let mut input = Vec::with_capacity(max_len);
+    for _ in 0..samples {
+        input.clear();
+        let len = rng.below(max_len + 1);
+        input.extend((0..len).map(|_| rng.below(16) as u8));
+        check(program, table, &input)?;
+    }
+    Ok(())
+}
+
+/// Longest inputs [`exhaustive_check`] accepts, checking about 17 million strings.
+pub const EXHAUSTIVE_MAX_LEN: usize = 6;
+
+/// Compares the table against [`interpret`] on every input of length at most `max_len`,
+/// shortest first and then in lexicographic order, returning the first disagreement.
+///
+/// # Panics
+///
+/// If `max_len` exceeds [`EXHAUSTIVE_MAX_LEN`].
+pub fn exhaustive_check(program: &Program, table: &Table, max_len: usize) -> Result<(), Mismatch> {
+    assert!(
+        max_len <= EXHAUSTIVE_MAX_LEN,
+        "exhaustive_check is limited to inputs of length {EXHAUSTIVE_MAX_LEN}"
+    );
+
+    for len in 0..=max_len {
+        let mut input = vec![0; len];
+        loop {
+            check(program, table, &input)?;
+
+            // advance to the next input of this length, like an odometer
+            let Some(position) = input.iter().rposition(|&symbol| symbol < 0x0F) else {
+                break;
+            };
+            input[position] += 1;
+            for symbol in &mut input[position + 1..] {
+                *symbol = 0;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::{exhaustive_check, interpret, random_check, Mismatch, EXHAUSTIVE_MAX_LEN};
+    use crate::{Program, Table};
+
+    #[test]
+    fn benchmark_tables_agree_with_the_interpreter() {
+        for program in crate::tests::benchmarks() {
+            let table = Table::build(&program);
+            random_check(&program, &table, 500, 8, 110).unwrap();
+            random_check(&program, &table.minimized(), 500, 8, 111).unwrap();
+        }
+    }
+
+    #[test]
+    fn broken_table_is_caught() {
+        // accepts the inputs without a 0
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let mut table = Table::build(&program).minimized();
+        // reject everything the start state accepts
+        table.rows_mut()[0].0 = false;
+
+        let mismatch = random_check(&program, &table, 100, 4, 110).unwrap_err();
+        assert!(!mismatch.input.contains(&0));
+        assert!(mismatch.expected);
+        assert!(!mismatch.actual);
+        assert!(interpret(&program, &mismatch.input));
+        assert!(!table.accepts(&mismatch.input));
+    }
+
+    #[test]
+    fn exhaustive_check_passes_on_the_benchmarks() {
+        for program in crate::tests::benchmarks() {
+            exhaustive_check(&program, &Table::build(&program), 3).unwrap();
+        }
+    }
+
+    #[test]
+    fn exhaustive_check_finds_the_first_mismatch() {
+        // accepts the inputs without a 0
+        let program = Program::new(">+[>.,[>]<<]", NonZeroUsize::new(3).unwrap());
+        let mut table = Table::build(&program);
+        // after a 5, let a 0 keep accepting
+        let after_five = table.transition(0, 5);
+        table.rows_mut()[after_five].1[0] = after_five;
+
+        assert_eq!(
+            exhaustive_check(&program, &table, 3),
+            Err(Mismatch {
+                input: vec![5, 0],
+                expected: false,
+                actual: true,
+            })
+        );
+        assert_eq!(
+            exhaustive_check(&program, &table, 3)
+                .unwrap_err()
+                .to_string(),
+            "input \"50\" is rejected by the program but accepted by the table"
+        );
+        assert!(exhaustive_check(&program, &table, 1).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "exhaustive_check is limited")]
+    fn exhaustive_check_is_capped() {
+        let program = Program::new(",", NonZeroUsize::new(1).unwrap());
+        let _ = exhaustive_check(&program, &Table::build(&program), EXHAUSTIVE_MAX_LEN + 1);
+    }
+
+    #[test]
+    fn interpreter_basics() {
+        let cells = NonZeroUsize::new(1).unwrap();
+        // accepts after a nonzero symbol
+        let program = Program::new(",[.]", cells);
+        assert!(!interpret(&program, &[]));
+        assert!(!interpret(&program, &[0]));
+        assert!(interpret(&program, &[3]));
+        // the loop diverges, keeping the flag set before it
+        assert!(interpret(&Program::new(".+[]", cells), &[]));
+        assert!(!interpret(&Program::new("+[]", cells), &[]));
+    }
+}
@@ -0,0 +1,184 @@
+use std::fmt::Write;
+
+use crate::{Program, Table};
+
+/// Reserved words that can't name a module, as far as they look like identifiers.
+const KEYWORDS: &[&str] = &[
+    "always",
+    "assign",
+    "begin",
+    "case",
+    "default",
+    "else",
+    "end",
+    "endcase",
+    "endmodule",
+    "if",
+    "initial",
+    "input",
+    "module",
+    "output",
+    "posedge",
+    "reg",
+    "wire",
+];
+
+/// `name` with everything but letters, digits, `_` and `$` replaced by `_`, prefixed with `_`
+/// if it would start with a digit or `$`, be empty or be a keyword.
+fn identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '$' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let clashes = identifier
+        .chars()
+        .next()
+        .is_none_or(|first| first.is_ascii_digit() || first == '$');
+    if clashes || KEYWORDS.contains(&identifier.as_str()) {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+impl Table {
+    /// A synthesizable module reading one symbol per clock edge on which `step` is high,
+    /// with a synchronous active-high `reset` to the start state. The state is a binary
+    /// encoded register and `accepting` is high while it's in an accepting state. Partial
+    /// tables are completed first. `program`, if given, is quoted in the header comment.
+    pub fn to_verilog(&self, module_name: &str, program: Option<&Program>) -> String {
+        let table = self.completed();
+        let width = (usize::BITS - table.state_count().saturating_sub(1).leading_zeros()).max(1);
+        let state = |id: usize| format!("{width}'d{id}");
+
+        let mut output = String::new();
+        writeln!(&mut output, "// Generated by bfa").unwrap();
+        if let Some(program) = program {
+            writeln!(
+                &mut output,
+                "// from the program, with {} cells:",
+                program.cell_count
+            )
+            .unwrap();
+            let text = program.to_string();
+            for line in text.as_bytes().chunks(72) {
+                writeln!(&mut output, "//   {}", std::str::from_utf8(line).unwrap()).unwrap();
+            }
+        }
+        writeln!(&mut output, "module {} (", identifier(module_name)).unwrap();
+        output.push_str(
+            "    input wire clk,\n    input wire reset,\n    input wire step,\n    input wire [3:0] symbol,\n    output wire accepting\n);\n",
+        );
+        writeln!(&mut output, "    reg [{}:0] state;", width - 1).unwrap();
+        writeln!(&mut output, "    reg [{}:0] next_state;\n", width - 1).unwrap();
+
+        output.push_str("    always @(*) begin\n        case (state)\n");
+        for (from, (_, edges)) in table.rows().enumerate() {
+            writeln!(&mut output, "            {}: case (symbol)", state(from)).unwrap();
+            let mut targets = edges.to_vec();
+            targets.sort_unstable();
+            targets.dedup();
+            for &to in &targets {
+                let inputs: Vec<String> = (0..16)
+                    .filter(|&input| edges[input] == to)
+                    .map(|input| format!("4'h{input:X}"))
+                    .collect();
+                writeln!(
+                    &mut output,
+                    "                {}: next_state = {};",
+                    inputs.join(", "),
+                    state(to)
+                )
+                .unwrap();
+            }
+            writeln!(
+                &mut output,
+                "                default: next_state = {};",
+                state(from)
+            )
+            .unwrap();
+            output.push_str("            endcase\n");
+        }
+        output.push_str("            default: next_state = state;\n        endcase\n    end\n\n");
+
+        output.push_str("    always @(posedge clk) begin\n");
+        writeln!(
+            &mut output,
+            "        if (reset)\n            state <= {};",
+            state(0)
+        )
+        .unwrap();
+        output.push_str("        else if (step)\n            state <= next_state;\n    end\n\n");
+
+        let accepting: Vec<String> = (0..table.state_count())
+            .filter(|&id| table.is_accepting(id))
+            .map(|id| format!("state == {}", state(id)))
+            .collect();
+        if accepting.is_empty() {
+            output.push_str("    assign accepting = 1'b0;\n");
+        } else {
+            writeln!(
+                &mut output,
+                "    assign accepting = {};",
+                accepting.join("\n        || ")
+            )
+            .unwrap();
+        }
+        output.push_str("endmodule\n");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::identifier;
+    use crate::Table;
+
+    /// Accepts the strings of even length.
+    fn even_length() -> Table {
+        Table::from_rows(vec![(true, [1; 16]), (false, [0; 16])])
+    }
+
+    #[test]
+    fn one_case_arm_per_state() {
+        let verilog = even_length().to_verilog("even", None);
+        assert_eq!(verilog.matches(": case (symbol)").count(), 2);
+        assert!(verilog.contains("reg [0:0] state;"));
+        assert!(verilog.contains("1'd0: case (symbol)"));
+        assert!(verilog.contains("1'd1: case (symbol)"));
+        assert!(verilog.contains("state <= 1'd0;"));
+        assert!(verilog.ends_with("endmodule\n"));
+    }
+
+    #[test]
+    fn accepting_matches_the_table() {
+        assert!(even_length()
+            .to_verilog("even", None)
+            .contains("assign accepting = state == 1'd0;"));
+        let none = Table::from_rows(vec![(false, [0; 16])]);
+        assert!(none
+            .to_verilog("none", None)
+            .contains("assign accepting = 1'b0;"));
+    }
+
+    #[test]
+    fn register_width_grows_with_the_states() {
+        let rows = (0..5).map(|state| (false, [(state + 1) % 5; 16])).collect();
+        let verilog = Table::from_rows(rows).to_verilog("five", None);
+        assert!(verilog.contains("reg [2:0] state;"));
+        assert_eq!(verilog.matches(": case (symbol)").count(), 5);
+    }
+
+    #[test]
+    fn sanitizes_identifiers() {
+        assert_eq!(identifier("my-module"), "my_module");
+        assert_eq!(identifier("3cells"), "_3cells");
+        assert_eq!(identifier(""), "_");
+        assert_eq!(identifier("module"), "_module");
+    }
+}
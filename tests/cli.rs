@@ -0,0 +1,521 @@
+use std::{
+    env, fs,
+    io::Write,
+    num::NonZeroUsize,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+use bfa::{Program, Table};
+
+fn bfa(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bfa"))
+        .args(args)
+        .output()
+        .expect("the binary runs")
+}
+
+/// A fresh directory for one test, removed if it's left over from an earlier run.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("bfa-cli-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn bfa_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bfa"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn large_builds_need_force() {
+    let output = bfa(&["8", ",[>,]"]);
+    assert!(!output.status.success());
+    let message = stderr(&output);
+    assert!(message.contains("warning: the build may explore up to 103079215104 states"));
+    assert!(message.contains("Refusing to start such a large build without --force"));
+    assert!(output.stdout.is_empty());
+
+    let output = bfa(&["2", ",[>,]"]);
+    assert!(output.status.success());
+    assert!(!stderr(&output).contains("warning"));
+    assert!(output.stdout.starts_with(b"digraph G {\n"));
+}
+
+#[test]
+fn second_build_is_a_cache_hit() {
+    let cache = temp_dir("cache");
+    let cache_arg = cache.to_str().unwrap();
+    let args = ["--stats", "--cache", cache_arg, "2", "+[>,,.<]"];
+
+    let first = bfa(&args);
+    assert!(first.status.success());
+    assert!(stderr(&first).contains("states explored:"));
+    let second = bfa(&args);
+    assert!(second.status.success());
+    assert_eq!(stderr(&second).trim(), "loaded from cache");
+    assert_eq!(stdout(&second), stdout(&first));
+
+    // a different cell count is a different entry
+    let other = bfa(&["--stats", "--cache", cache_arg, "3", "+[>,,.<]"]);
+    assert!(stderr(&other).contains("states explored:"));
+
+    for entry in fs::read_dir(&cache).unwrap() {
+        fs::write(entry.unwrap().path(), b"garbage").unwrap();
+    }
+    let corrupted = bfa(&args);
+    assert!(corrupted.status.success());
+    assert!(stderr(&corrupted).contains("states explored:"));
+    assert_eq!(stdout(&corrupted), stdout(&first));
+
+    let uncached = bfa(&[
+        "--stats",
+        "--cache",
+        cache_arg,
+        "--no-cache",
+        "2",
+        "+[>,,.<]",
+    ]);
+    assert!(stderr(&uncached).contains("states explored:"));
+
+    fs::remove_dir_all(&cache).unwrap();
+}
+
+#[test]
+fn diff_of_equivalent_programs() {
+    // both accept the inputs without a 0
+    let output = bfa(&["diff", "--cells", "3", ">+[>.,[>]<<]", "+[.,]"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "states: 2 and 2\nequivalent\n");
+}
+
+#[test]
+fn diff_of_different_programs() {
+    // the second also rejects the inputs with an F
+    let programs = [">+[>.,[>]<<]", ">+[>.,+[-[>]]<<]"];
+    let output = bfa(&["diff", "--cells", "3", programs[0], programs[1]]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "states: 2 and 2\ndifferent\nonly accepted by the first: \"F\", \"1F\", \"2F\"\n"
+    );
+
+    let output = bfa(&[
+        "diff",
+        "--format",
+        "dot",
+        "--cells",
+        "3",
+        programs[0],
+        programs[1],
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let dot = stdout(&output);
+    assert!(dot.starts_with("digraph G {\n"));
+    // only the state after an F and no 0 accepts in the product
+    assert_eq!(dot.matches("fillcolor=\"red\"").count(), 1);
+}
+
+#[test]
+fn table_from_a_json_file() {
+    let dir = temp_dir("table");
+    let program = Program::new(",>,[-<->]<[>.,<]", NonZeroUsize::new(2).unwrap());
+    let table = Table::build(&program);
+    let json = dir.join("table.json");
+    fs::write(&json, table.to_json()).unwrap();
+
+    let output = bfa(&["table", "--in", json.to_str().unwrap(), "--minimize"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("{}\n", table.minimized().dot()));
+
+    // the extension picks the format unless it's given
+    let renamed = dir.join("table.txt");
+    fs::rename(&json, &renamed).unwrap();
+    let output = bfa(&["table", "--in", renamed.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("use --in-format"));
+    let output = bfa(&[
+        "table",
+        "--in",
+        renamed.to_str().unwrap(),
+        "--in-format",
+        "json",
+        "--format",
+        "json",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("{}\n", table.to_json()));
+
+    fs::write(&renamed, "{").unwrap();
+    let output = bfa(&[
+        "table",
+        "--in",
+        renamed.to_str().unwrap(),
+        "--in-format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watch_needs_a_file_and_an_output() {
+    let output = bfa(&["--watch", "--cells", "2", "+[>,,.<]"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--watch needs both --file and --out"));
+}
+
+#[test]
+fn watch_rejects_a_spec() {
+    let dir = temp_dir("watch-spec");
+    let file = dir.join("program.bf");
+    fs::write(&file, "+[>,,.<]").unwrap();
+    let out = dir.join("table.dot");
+    let output = bfa(&[
+        "--watch",
+        "--spec",
+        ".*",
+        "--cells",
+        "2",
+        "--file",
+        file.to_str().unwrap(),
+        "--out",
+        out.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--spec can't be combined with --watch"));
+    assert!(!out.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn watch_rebuilds_on_change() {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let dir = temp_dir("watch");
+    let file = dir.join("program.bf");
+    let out = dir.join("table.dot");
+    let minimized_dot = |text| {
+        let program = Program::new(text, NonZeroUsize::new(2).unwrap());
+        Table::build(&program).minimized().dot()
+    };
+    let wait_for = |expected: &str| {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while fs::read_to_string(&out).ok().as_deref() != Some(expected) {
+            assert!(Instant::now() < deadline, "timed out waiting for a rebuild");
+            thread::sleep(Duration::from_millis(50));
+        }
+    };
+
+    fs::write(&file, "+[>,,.<]").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bfa"))
+        .args(["--watch", "--cells", "2", "--file"])
+        .arg(&file)
+        .arg("--out")
+        .arg(&out)
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    wait_for(&minimized_dot("+[>,,.<]"));
+
+    // a failed rebuild keeps the previous output
+    fs::remove_file(&file).unwrap();
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(fs::read_to_string(&out).unwrap(), minimized_dot("+[>,,.<]"));
+
+    fs::write(&file, ",>,[-<->]<[>.,<]").unwrap();
+    wait_for(&minimized_dot(",>,[-<->]<[>.,<]"));
+
+    child.kill().unwrap();
+    let output = child.wait_with_output().unwrap();
+    let message = stderr(&output);
+    assert!(message.starts_with("rebuilt: "));
+    assert!(message.contains("error: Couldn't read"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(not(feature = "watch"))]
+#[test]
+fn watch_needs_the_feature() {
+    let dir = temp_dir("no-watch");
+    let file = dir.join("program.bf");
+    fs::write(&file, "+[>,,.<]").unwrap();
+    let out = dir.join("table.dot");
+    let output = bfa(&[
+        "--watch",
+        "--cells",
+        "2",
+        "--file",
+        file.to_str().unwrap(),
+        "--out",
+        out.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("built without the `watch` feature"));
+    assert!(!out.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn repl_transcript() {
+    // minimized, 0 is the start, 1 has read only nonzero symbols and 2 has halted
+    let commands = "3\n0\nback\nshow\nx\n5 A\nreset\nback\n";
+    let output = bfa_with_stdin(
+        &["repl", "--configuration", "--cells", "1", ",[.,]"],
+        commands,
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "state 0 (rejecting): [0] at instruction 1\n\
+         3 -> state 1 (accepting): [3] at instruction 4\n\
+         0 -> state 2 (rejecting): halted\n\
+         state 1 (accepting): [3] at instruction 4\n\
+         \"3\" -> state 1 (accepting): [3] at instruction 4\n\
+         error: `x` is neither a hex digit nor a command\n\
+         5 -> state 1 (accepting): [5] at instruction 4\n\
+         A -> state 1 (accepting): [A] at instruction 4\n\
+         state 0 (rejecting): [0] at instruction 1\n\
+         error: nothing to go back to\n"
+    );
+
+    let output = bfa_with_stdin(&["repl", "--cells", "1", ",[.,]"], "3\n");
+    assert_eq!(
+        stdout(&output),
+        "state 0 (rejecting)\n3 -> state 1 (accepting)\n"
+    );
+}
+
+#[test]
+fn equivalence_matrix_of_a_manifest() {
+    let dir = temp_dir("matrix");
+    let manifest = dir.join("manifest.txt");
+    fs::write(
+        &manifest,
+        "# the inputs without a 0, twice, then those without a 0 or an F\n\
+         no0 3 >+[>.,[>]<<]\n\
+         same 1 +[.,]\n\
+         \n\
+         noF 3 >+[>.,+[-[>]]<<]\n",
+    )
+    .unwrap();
+
+    let output = bfa(&["equiv-matrix", manifest.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "no0  = = x\n\
+         same = = x\n\
+         noF  x x =\n\
+         no0 vs noF: \"F\" is only accepted by no0\n\
+         same vs noF: \"F\" is only accepted by same\n"
+    );
+
+    fs::write(&manifest, "no0 3\n").unwrap();
+    let output = bfa(&["equiv-matrix", manifest.to_str().unwrap()]);
+    assert!(stderr(&output).contains("Line 1 of the manifest"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sweep_stabilizes() {
+    let dir = temp_dir("sweep");
+    let file = dir.join("program.bf");
+    // the head wraps back to the incremented cell with fewer than 3 cells
+    fs::write(&file, "+>>[.,]").unwrap();
+    let out = dir.join("out");
+
+    let output = bfa(&[
+        "sweep",
+        "--cells",
+        "1..4",
+        "--out",
+        out.to_str().unwrap(),
+        file.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    let mut lines = stdout.lines();
+    assert_eq!(
+        lines.next(),
+        Some("cells  states  minimized  fingerprint       same  time")
+    );
+    let rows: Vec<Vec<&str>> = lines
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    let column = |i: usize| rows.iter().map(|row| row[i]).collect::<Vec<_>>();
+    assert_eq!(column(0), ["1", "2", "3", "4"]);
+    assert_eq!(column(2), ["2", "2", "1", "1"]);
+    assert_eq!(column(4), ["no", "yes", "no", "yes"]);
+    assert_eq!(rows[0][3], rows[1][3]);
+    assert_eq!(rows[2][3], rows[3][3]);
+    assert_ne!(rows[1][3], rows[2][3]);
+
+    for cell_count in 1..=4 {
+        let dot = fs::read_to_string(out.join(format!("cells-{cell_count}.dot"))).unwrap();
+        assert!(dot.starts_with("digraph G {\n"));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sweep_skips_builds_over_the_limit() {
+    let dir = temp_dir("sweep-limit");
+    let file = dir.join("program.bf");
+    fs::write(&file, ",[>,]").unwrap();
+
+    let output = bfa(&[
+        "sweep",
+        "--jobs",
+        "2",
+        "--cells",
+        "1..5",
+        file.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    let lines: Vec<&str> = stdout.lines().skip(1).collect();
+    assert_eq!(lines.len(), 5);
+    // 16^5 configurations are reachable with 5 cells
+    assert!(lines[..4].iter().all(|line| !line.contains("skipped")));
+    assert!(lines[4].starts_with("5      skipped (limit): "));
+
+    assert!(!bfa(&["sweep", "--cells", "1..4"]).status.success());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn explain_classes() {
+    let output = bfa(&["explain", "--classes", "--cells", "1", ",[.,]"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "class 0: ε, accepts after 1 2 3\n\
+         class 1: 0, never accepted\n\
+         class 2: 1, accepting, accepts after ε 1 2\n"
+    );
+
+    let output = bfa(&["explain", "--cells", "1", ",[.,]"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("explain --classes"));
+}
+
+#[test]
+fn both_tables_side_by_side() {
+    let dir = temp_dir("both");
+    let out_dir = dir.join("out");
+    let output = bfa(&[
+        "--both",
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+        "--cells",
+        "2",
+        ",>,[-<->]<[>.,<]",
+    ]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let raw = Table::build(&Program::new(
+        ",>,[-<->]<[>.,<]",
+        NonZeroUsize::new(2).unwrap(),
+    ));
+    let minimized = raw.minimized();
+    assert!(raw.state_count() >= minimized.state_count());
+    assert_eq!(
+        fs::read_to_string(out_dir.join("raw.dot")).unwrap(),
+        raw.dot()
+    );
+    assert_eq!(
+        fs::read_to_string(out_dir.join("min.dot")).unwrap(),
+        minimized.dot()
+    );
+
+    // one line per minimized state, together listing each raw state once
+    let mapping = fs::read_to_string(out_dir.join("mapping.txt")).unwrap();
+    let mut states = Vec::new();
+    for (block, line) in mapping.lines().enumerate() {
+        let (number, members) = line.split_once(": ").unwrap();
+        assert_eq!(number, block.to_string());
+        for state in members.split(' ') {
+            let state: usize = state.parse().unwrap();
+            assert_eq!(raw.minimize_mapping()[state], block);
+            states.push(state);
+        }
+    }
+    assert_eq!(mapping.lines().count(), minimized.state_count());
+    states.sort_unstable();
+    assert!(states.into_iter().eq(0..raw.state_count()));
+
+    let output = bfa(&[
+        "--both",
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+        "--format",
+        "html",
+        "--cells",
+        "2",
+        ",>,[-<->]<[>.,<]",
+    ]);
+    assert!(output.status.success());
+    assert!(out_dir.join("raw.html").exists() && out_dir.join("min.html").exists());
+
+    for args in [
+        &["--both", "--cells", "2", "+[>,,.<]"][..],
+        &[
+            "--out-dir",
+            out_dir.to_str().unwrap(),
+            "--cells",
+            "2",
+            "+[>,,.<]",
+        ],
+        &[
+            "--both",
+            "--out-dir",
+            out_dir.to_str().unwrap(),
+            "--out",
+            "table.dot",
+            "--cells",
+            "2",
+            "+[>,,.<]",
+        ],
+    ] {
+        let output = bfa(args);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("--both goes with --out-dir"));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}